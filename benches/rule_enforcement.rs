@@ -2,7 +2,8 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion, Benchmark
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
-use synapse_mcp::{RuleGraph, check_rules, CompiledRule, Rule, RuleType};
+use synapse_mcp::enforcement::lifecycle::ShadowCounter;
+use synapse_mcp::{RuleGraph, RuleSystem, RulePrefilter, check_rules, check_rules_with, CompiledRule, Rule, RuleType};
 
 /// Generate test files with various content patterns
 fn generate_test_files(temp_dir: &TempDir, count: usize) -> Vec<PathBuf> {
@@ -97,6 +98,7 @@ fn generate_test_rules() -> Vec<CompiledRule> {
             message: "TODO comments should be converted to proper issue tracking".to_string(),
             tags: vec!["code-quality".to_string()],
             metadata: std::collections::HashMap::new(),
+            owners: vec![],
         },
         Rule {
             id: "forbidden-println".to_string(),
@@ -106,6 +108,7 @@ fn generate_test_rules() -> Vec<CompiledRule> {
             message: "Use logging instead of direct println! calls".to_string(),
             tags: vec!["logging".to_string()],
             metadata: std::collections::HashMap::new(),
+            owners: vec![],
         },
         Rule {
             id: "required-tests".to_string(),
@@ -115,6 +118,7 @@ fn generate_test_rules() -> Vec<CompiledRule> {
             message: "All modules must have tests".to_string(),
             tags: vec!["testing".to_string()],
             metadata: std::collections::HashMap::new(),
+            owners: vec![],
         },
     ];
     
@@ -230,6 +234,32 @@ FORBIDDEN: `unwrap()` - Use proper error handling instead of unwrap
         });
     });
     
+    // Indexed lookup with precompiled matchers, as used by the check and pre-write hooks
+    group.bench_function("resolved_for", |b| {
+        b.iter(|| {
+            for file_path in &test_files {
+                let resolved = rule_graph.resolved_for(black_box(file_path));
+                black_box(resolved);
+            }
+        });
+    });
+    
+    // Baseline: resolve from the rule sets and compile every rule on each call
+    let rule_system = RuleSystem::new();
+    let rule_sets = rule_system.load_rules(&project_root).expect("Failed to load rules");
+    group.bench_function("resolve_and_compile_unindexed", |b| {
+        b.iter(|| {
+            for file_path in &test_files {
+                let composite = rule_system.rules_for_path(black_box(file_path), &rule_sets);
+                let compiled: Vec<CompiledRule> = composite.applicable_rules
+                    .into_iter()
+                    .map(CompiledRule::from_rule)
+                    .collect();
+                black_box(compiled);
+            }
+        });
+    });
+    
     group.finish();
 }
 
@@ -286,6 +316,7 @@ fn bench_pattern_matching(c: &mut Criterion) {
         message: "No TODOs".to_string(),
         tags: vec![],
         metadata: std::collections::HashMap::new(),
+        owners: vec![],
     });
     
     let regex_rule = CompiledRule::from_rule(Rule {
@@ -296,6 +327,7 @@ fn bench_pattern_matching(c: &mut Criterion) {
         message: "No println!".to_string(),
         tags: vec![],
         metadata: std::collections::HashMap::new(),
+        owners: vec![],
     });
     
     group.bench_function("literal_pattern", |b| {
@@ -323,11 +355,63 @@ fn bench_pattern_matching(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark a pack-sized rule list with and without the combined prefilter
+fn bench_many_rules(c: &mut Criterion) {
+    let mut group = c.benchmark_group("many_rules");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let contents: Vec<(PathBuf, String)> = generate_test_files(&temp_dir, 20)
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path).expect("Failed to read file");
+            (path, content)
+        })
+        .collect();
+    
+    // Half literal, half regex; only the generated test rules ever match
+    let mut rules = generate_test_rules();
+    rules.extend((0..300).map(|i| CompiledRule::from_rule(Rule {
+        id: format!("pack-{}", i),
+        name: format!("Pack rule {}", i),
+        rule_type: RuleType::Forbidden,
+        pattern: if i % 2 == 0 { format!("forbidden_call_{}", i) } else { format!(r"\bdeprecated_{}\s*\(", i) },
+        message: "Generated pack rule".to_string(),
+        tags: vec![],
+        metadata: std::collections::HashMap::new(),
+        owners: vec![],
+    })));
+    let prefilter = RulePrefilter::new(&rules);
+    let shadow_hits = ShadowCounter::default();
+    
+    group.bench_function("prefiltered", |b| {
+        b.iter(|| {
+            for (path, content) in &contents {
+                let violations = check_rules_with(path, black_box(content), &rules, &prefilter, &shadow_hits)
+                    .expect("Rule checking failed");
+                black_box(violations);
+            }
+        });
+    });
+    
+    group.bench_function("unfiltered", |b| {
+        let unfiltered = RulePrefilter::default();
+        b.iter(|| {
+            for (path, content) in &contents {
+                let violations = check_rules_with(path, black_box(content), &rules, &unfiltered, &shadow_hits)
+                    .expect("Rule checking failed");
+                black_box(violations);
+            }
+        });
+    });
+    
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_rule_checking,
     bench_rule_graph_operations,
     bench_rule_checking_100_files,
-    bench_pattern_matching
+    bench_pattern_matching,
+    bench_many_rules
 );
 criterion_main!(benches);
\ No newline at end of file
@@ -109,7 +109,7 @@ setup_environment() {
 
 # Setup git hooks
 setup_git_hooks() {
-    log_info "Setting up git pre-commit hooks..."
+    log_info "Setting up git pre-commit and commit-msg hooks..."
     
     if [ ! -f ".pre-commit-config.yaml" ]; then
         log_error ".pre-commit-config.yaml not found"
@@ -118,6 +118,9 @@ setup_git_hooks() {
     
     pre-commit install
     log_success "Git pre-commit hooks installed"
+    
+    pre-commit install --hook-type commit-msg
+    log_success "Git commit-msg hook installed (checks commit message rules)"
 }
 
 # Test the pipeline
@@ -198,6 +201,7 @@ case "${1:-setup}" in
     "clean")
         log_info "Cleaning up hooks and temporary files..."
         pre-commit uninstall 2>/dev/null || true
+        pre-commit uninstall --hook-type commit-msg 2>/dev/null || true
         rm -f .synapse_context
         ./claude-hook.sh stop 2>/dev/null || true
         log_success "Cleanup complete"
@@ -90,6 +90,12 @@ pub struct RuleViolationDto {
     pub message: String,
     pub line_number: Option<usize>,
     pub line_content: Option<String>,
+    /// Owners of the rule file the rule came from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// The rule is not enforced yet, so the violation doesn't fail the check
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub warning: bool,
 }
 
 impl From<&Violation> for RuleViolationDto {
@@ -102,10 +108,33 @@ impl From<&Violation> for RuleViolationDto {
             message: violation.rule.message.clone(),
             line_number: violation.line_number,
             line_content: violation.line_content.clone(),
+            owners: violation.rule.owners().into_iter().map(String::from).collect(),
+            warning: violation.warning,
         }
     }
 }
 
+/// Violations of the rules one owner is responsible for
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OwnerViolations {
+    /// `None` for rules from files without `owners:`
+    pub owner: Option<String>,
+    pub violations: Vec<RuleViolationDto>,
+}
+
+impl OwnerViolations {
+    /// Violations grouped by rule owner (see [`crate::enforcement::group_by_owner`])
+    pub fn group(violations: &[Violation]) -> Vec<Self> {
+        crate::enforcement::group_by_owner(violations)
+            .into_iter()
+            .map(|(owner, violations)| Self {
+                owner: owner.map(String::from),
+                violations: violations.into_iter().map(RuleViolationDto::from).collect(),
+            })
+            .collect()
+    }
+}
+
 /// Rule information formatted for AI consumption
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RuleContextInfo {
@@ -6,9 +6,11 @@
 
 #[cfg(feature = "ast-fixes")]
 pub mod safe_unwrap_replacer;
+pub mod structural;
 
 #[cfg(feature = "ast-fixes")]
 pub use safe_unwrap_replacer::{UnwrapReplacer, Replacement, safely_replace_unwrap};
+pub use structural::{StructuralCheck, StructuralFinding, find_structural_violations};
 
 /// Error types for AST analysis operations
 #[derive(Debug, thiserror::Error)]
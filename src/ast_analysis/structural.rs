@@ -0,0 +1,391 @@
+//! Structural Rule Checks using AST Analysis
+//!
+//! This module implements the catalog of built-in structural checks that can be
+//! selected from rule files with `FORBIDDEN[ast]: <check-name> - message`.
+//! Unlike regex rules, these checks walk the syntax tree and report each
+//! offending item with its precise line number.
+
+#[cfg(feature = "ast-fixes")]
+use syn::{
+    visit::{self, Visit},
+    Attribute, ExprMethodCall, ExprUnsafe, ImplItemFn, ItemEnum, ItemFn, ItemMod, ItemStruct,
+    ItemTrait, ItemType, Visibility, parse_file, spanned::Spanned,
+};
+
+use super::{AstResult, AstAnalysisError};
+
+/// Built-in structural checks available to `[ast]` rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StructuralCheck {
+    /// `unsafe` block without a `// SAFETY:` comment directly above it
+    UnsafeWithoutSafetyComment,
+    /// `pub fn` without a `///` doc comment
+    PubFnWithoutDoc,
+    /// `pub` struct, enum, trait or type alias without a `///` doc comment
+    PubTypeWithoutDoc,
+    /// `.unwrap()` or `.expect()` call outside of test code
+    UnwrapOutsideTests,
+}
+
+impl StructuralCheck {
+    /// All checks in the built-in catalog
+    pub const ALL: [StructuralCheck; 4] = [
+        StructuralCheck::UnsafeWithoutSafetyComment,
+        StructuralCheck::PubFnWithoutDoc,
+        StructuralCheck::PubTypeWithoutDoc,
+        StructuralCheck::UnwrapOutsideTests,
+    ];
+
+    /// Look up a check by the name used in rule files
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|check| check.name() == name)
+    }
+
+    /// Name used to select this check in rule files
+    pub fn name(&self) -> &'static str {
+        match self {
+            StructuralCheck::UnsafeWithoutSafetyComment => "unsafe-without-safety-comment",
+            StructuralCheck::PubFnWithoutDoc => "pub-fn-without-doc",
+            StructuralCheck::PubTypeWithoutDoc => "pub-type-without-doc",
+            StructuralCheck::UnwrapOutsideTests => "unwrap-outside-tests",
+        }
+    }
+
+    /// Short description of what the check reports
+    pub fn description(&self) -> &'static str {
+        match self {
+            StructuralCheck::UnsafeWithoutSafetyComment => "`unsafe` block without a `// SAFETY:` comment",
+            StructuralCheck::PubFnWithoutDoc => "`pub fn` without a `///` doc comment",
+            StructuralCheck::PubTypeWithoutDoc => "`pub` struct, enum, trait or type alias without a `///` doc comment",
+            StructuralCheck::UnwrapOutsideTests => "`.unwrap()` or `.expect()` call outside of test code",
+        }
+    }
+}
+
+/// A single item reported by a structural check
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuralFinding {
+    /// 1-based line number of the offending item
+    pub line: usize,
+    /// Human-readable description of the offending item
+    pub item: String,
+}
+
+/// AST visitor that collects findings for one structural check
+#[cfg(feature = "ast-fixes")]
+struct StructuralVisitor<'a> {
+    check: StructuralCheck,
+    source_lines: Vec<&'a str>,
+    /// Depth of enclosing `#[test]` functions or `#[cfg(test)]` modules
+    test_depth: usize,
+    findings: Vec<StructuralFinding>,
+}
+
+#[cfg(feature = "ast-fixes")]
+impl<'a> StructuralVisitor<'a> {
+    fn new(check: StructuralCheck, source: &'a str) -> Self {
+        Self {
+            check,
+            source_lines: source.lines().collect(),
+            test_depth: 0,
+            findings: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, line: usize, item: String) {
+        self.findings.push(StructuralFinding { line, item });
+    }
+
+    fn has_doc(attrs: &[Attribute]) -> bool {
+        attrs.iter().any(|attr| attr.path().is_ident("doc"))
+    }
+
+    fn is_pub(vis: &Visibility) -> bool {
+        matches!(vis, Visibility::Public(_))
+    }
+
+    fn is_test_code(attrs: &[Attribute]) -> bool {
+        attrs.iter().any(|attr| {
+            if attr.path().is_ident("test") {
+                return true;
+            }
+            if attr.path().segments.last().is_some_and(|s| s.ident == "test") {
+                // #[tokio::test] and friends
+                return true;
+            }
+            attr.path().is_ident("cfg")
+                && attr.meta.require_list().is_ok_and(|list| list.tokens.to_string() == "test")
+        })
+    }
+
+    /// Check the lines directly above `line` (skipping attributes) for a `// SAFETY:` comment
+    ///
+    /// Only comments count: a string literal mentioning `SAFETY:` doesn't.
+    fn has_safety_comment(&self, line: usize) -> bool {
+        if line == 0 {
+            return false;
+        }
+        // Same-line comments count, e.g. `let x = unsafe { .. }; // SAFETY: ...`
+        if self.source_lines.get(line - 1).and_then(|l| comment_of(l)).is_some_and(|c| c.contains("SAFETY:")) {
+            return true;
+        }
+        for index in (0..line - 1).rev() {
+            let trimmed = self.source_lines[index].trim();
+            if trimmed.starts_with("//") || trimmed.starts_with("/*") {
+                if trimmed.contains("SAFETY:") {
+                    return true;
+                }
+            } else if !trimmed.starts_with("#[") {
+                return false;
+            }
+        }
+        false
+    }
+
+    fn check_type_item(&mut self, kind: &str, vis: &Visibility, attrs: &[Attribute], ident: &syn::Ident) {
+        if self.check == StructuralCheck::PubTypeWithoutDoc && Self::is_pub(vis) && !Self::has_doc(attrs) {
+            self.record(ident.span().start().line, format!("pub {} {}", kind, ident));
+        }
+    }
+}
+
+#[cfg(feature = "ast-fixes")]
+impl<'ast> Visit<'ast> for StructuralVisitor<'_> {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        if self.check == StructuralCheck::PubFnWithoutDoc && Self::is_pub(&node.vis) && !Self::has_doc(&node.attrs) {
+            self.record(node.sig.ident.span().start().line, format!("pub fn {}", node.sig.ident));
+        }
+
+        let is_test = Self::is_test_code(&node.attrs);
+        if is_test {
+            self.test_depth += 1;
+        }
+        visit::visit_item_fn(self, node);
+        if is_test {
+            self.test_depth -= 1;
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        if self.check == StructuralCheck::PubFnWithoutDoc && Self::is_pub(&node.vis) && !Self::has_doc(&node.attrs) {
+            self.record(node.sig.ident.span().start().line, format!("pub fn {}", node.sig.ident));
+        }
+
+        let is_test = Self::is_test_code(&node.attrs);
+        if is_test {
+            self.test_depth += 1;
+        }
+        visit::visit_impl_item_fn(self, node);
+        if is_test {
+            self.test_depth -= 1;
+        }
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        let is_test = Self::is_test_code(&node.attrs);
+        if is_test {
+            self.test_depth += 1;
+        }
+        visit::visit_item_mod(self, node);
+        if is_test {
+            self.test_depth -= 1;
+        }
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
+        self.check_type_item("struct", &node.vis, &node.attrs, &node.ident);
+        visit::visit_item_struct(self, node);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast ItemEnum) {
+        self.check_type_item("enum", &node.vis, &node.attrs, &node.ident);
+        visit::visit_item_enum(self, node);
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast ItemTrait) {
+        self.check_type_item("trait", &node.vis, &node.attrs, &node.ident);
+        visit::visit_item_trait(self, node);
+    }
+
+    fn visit_item_type(&mut self, node: &'ast ItemType) {
+        self.check_type_item("type", &node.vis, &node.attrs, &node.ident);
+        visit::visit_item_type(self, node);
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast ExprUnsafe) {
+        if self.check == StructuralCheck::UnsafeWithoutSafetyComment {
+            let line = node.unsafe_token.span().start().line;
+            if !self.has_safety_comment(line) {
+                self.record(line, "unsafe block".to_string());
+            }
+        }
+        visit::visit_expr_unsafe(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        if self.check == StructuralCheck::UnwrapOutsideTests
+            && self.test_depth == 0
+            && (node.method == "unwrap" || node.method == "expect")
+        {
+            self.record(node.method.span().start().line, format!(".{}() call", node.method));
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+}
+
+/// The comment on a line of Rust code: from the first `//` or `/*` outside
+/// a string literal
+#[cfg(feature = "ast-fixes")]
+fn comment_of(line: &str) -> Option<&str> {
+    let mut in_string = false;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            // A `'"'` character literal doesn't open a string
+            '\'' if !in_string && line[index..].starts_with("'\"'") => {
+                chars.next();
+                chars.next();
+            }
+            '/' if !in_string && matches!(chars.peek(), Some((_, '/' | '*'))) => return Some(&line[index..]),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Run a structural check against Rust source code
+///
+/// Returns one finding per offending item, ordered by line number.
+/// Returns `AstAnalysisError::ParseError` if the source is not valid Rust and
+/// `AstAnalysisError::FeatureNotEnabled` when built without `ast-fixes`.
+pub fn find_structural_violations(code: &str, check: StructuralCheck) -> AstResult<Vec<StructuralFinding>> {
+    #[cfg(not(feature = "ast-fixes"))]
+    {
+        let _ = (code, check);
+        Err(AstAnalysisError::FeatureNotEnabled)
+    }
+
+    #[cfg(feature = "ast-fixes")]
+    {
+        let syntax_tree = parse_file(code)
+            .map_err(|e| AstAnalysisError::ParseError(e.to_string()))?;
+
+        let mut visitor = StructuralVisitor::new(check, code);
+        visitor.visit_file(&syntax_tree);
+
+        let mut findings = visitor.findings;
+        findings.sort_by_key(|finding| finding.line);
+        Ok(findings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_catalog_names_round_trip() {
+        for check in StructuralCheck::ALL {
+            assert_eq!(StructuralCheck::from_name(check.name()), Some(check));
+        }
+        assert_eq!(StructuralCheck::from_name("no-such-check"), None);
+    }
+
+    #[cfg(not(feature = "ast-fixes"))]
+    #[test]
+    fn test_structural_check_feature_disabled() {
+        let result = find_structural_violations("fn main() {}", StructuralCheck::PubFnWithoutDoc);
+        assert!(matches!(result, Err(AstAnalysisError::FeatureNotEnabled)));
+    }
+
+    #[cfg(feature = "ast-fixes")]
+    #[test]
+    fn test_unsafe_without_safety_comment() {
+        let code = r#"
+fn read(ptr: *const u8) -> u8 {
+    // SAFETY: caller guarantees ptr is valid
+    let a = unsafe { *ptr };
+    let b = unsafe { *ptr };
+    a + b
+}
+"#;
+        let findings = find_structural_violations(code, StructuralCheck::UnsafeWithoutSafetyComment).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].line, 5);
+    }
+
+    #[cfg(feature = "ast-fixes")]
+    #[test]
+    fn test_safety_comment_must_be_a_comment() {
+        let code = r#"
+fn read(ptr: *const u8) -> u8 {
+    let note = "SAFETY: not a comment"; let a = unsafe { *ptr };
+    // SAFETY: caller guarantees ptr is valid
+    let b = unsafe { *ptr };
+    let c = unsafe { *ptr }; // SAFETY: same invariant
+    let message = "// SAFETY: in a string";
+    let d = unsafe { *ptr };
+    a + b + c + d
+}
+"#;
+        let findings = find_structural_violations(code, StructuralCheck::UnsafeWithoutSafetyComment).unwrap();
+        let lines: Vec<usize> = findings.iter().map(|f| f.line).collect();
+        assert_eq!(lines, vec![3, 8]);
+        assert_eq!(comment_of(r#"let quote = '"'; // SAFETY: ok"#), Some("// SAFETY: ok"));
+    }
+
+    #[cfg(feature = "ast-fixes")]
+    #[test]
+    fn test_pub_fn_without_doc() {
+        let code = r#"
+/// Documented
+pub fn documented() {}
+
+pub fn undocumented() {}
+
+fn private() {}
+
+pub struct Widget;
+
+impl Widget {
+    pub fn method(&self) {}
+}
+"#;
+        let findings = find_structural_violations(code, StructuralCheck::PubFnWithoutDoc).unwrap();
+        let lines: Vec<usize> = findings.iter().map(|f| f.line).collect();
+        assert_eq!(lines, vec![5, 12]);
+        assert_eq!(findings[0].item, "pub fn undocumented");
+    }
+
+    #[cfg(feature = "ast-fixes")]
+    #[test]
+    fn test_unwrap_outside_tests() {
+        let code = r#"
+fn load() -> u32 {
+    "42".parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_load() {
+        "1".parse::<u32>().unwrap();
+    }
+}
+"#;
+        let findings = find_structural_violations(code, StructuralCheck::UnwrapOutsideTests).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].line, 3);
+    }
+
+    #[cfg(feature = "ast-fixes")]
+    #[test]
+    fn test_invalid_rust_is_parse_error() {
+        let result = find_structural_violations("fn {", StructuralCheck::PubFnWithoutDoc);
+        assert!(matches!(result, Err(AstAnalysisError::ParseError(_))));
+    }
+}
@@ -231,6 +231,7 @@ mod tests {
                 message: "Test rule".to_string(),
                 tags: vec![],
                 metadata: HashMap::new(),
+                owners: vec![],
             }],
             inheritance_chain: vec![],
             overridden_rules: vec![],
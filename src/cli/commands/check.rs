@@ -1,10 +1,20 @@
 use anyhow::Result;
 use clap::ArgMatches;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::{Arc, mpsc};
 
-use synapse_mcp::{RuleGraph, RuleType, Violation, check_rules};
+use synapse_mcp::{
+    CheckResultData, CompiledRule, OwnerViolations, RuleGraph, RuleType, RuleViolationDto, Violation,
+    RuleMetrics, RulePrefilter, RuleStatsStore, check_project_imports, check_rules_with, group_by_owner,
+};
+use synapse_mcp::enforcement::lifecycle::ShadowHits;
+use synapse_mcp::enforcement::header::{fix_headers, template_for};
+use synapse_mcp::enforcement::imports::is_import_rule;
+use synapse_mcp::enforcement::scan::{FileScan, SkipReason, read_text, scan_project};
 
 /// Result of checking files against rules
 #[derive(Debug)]
@@ -12,6 +22,37 @@ pub struct CheckResult {
     pub violations: Vec<Violation>,
     pub files_checked: usize,
     pub rules_applied: usize,
+    /// Files that were read but could not be checked against their rules
+    pub files_failed: usize,
+}
+
+impl CheckResult {
+    /// Whether the check passes: every file was checked and no violation is enforced
+    fn passes(&self) -> bool {
+        self.files_failed == 0 && self.violations.iter().all(|v| v.warning)
+    }
+}
+
+/// How violations are reported (`--format`, `--group-by`)
+#[derive(Debug, Clone, Copy)]
+struct Output {
+    json: bool,
+    by_owner: bool,
+}
+
+impl Output {
+    /// Whether each file's violations can be printed as soon as it is checked
+    fn streams(&self) -> bool {
+        !self.json && !self.by_owner
+    }
+}
+
+/// JSON output of `check --group-by owner`
+#[derive(Debug, Serialize)]
+struct OwnerReport {
+    owners: Vec<OwnerViolations>,
+    files_checked: usize,
+    rules_applied: usize,
 }
 
 pub async fn handle_check(matches: &ArgMatches, rule_graph_opt: Option<&RuleGraph>) -> Result<()> {
@@ -22,13 +63,28 @@ pub async fn handle_check(matches: &ArgMatches, rule_graph_opt: Option<&RuleGrap
         
     let verbose = matches.get_flag("verbose");
     let dry_run = matches.get_flag("dry-run");
+    let imports = matches.get_flag("imports");
+    let all = matches.get_flag("all");
+    let fix = matches.get_flag("fix");
+    let output = Output {
+        json: matches.get_one::<String>("format").map(String::as_str) == Some("json"),
+        by_owner: matches.get_one::<String>("group-by").map(String::as_str) == Some("owner"),
+    };
     
-    if files.is_empty() {
+    if files.is_empty() && !imports && !all {
         eprintln!("❌ No files provided to check");
         process::exit(1);
     }
     
-    if verbose {
+    if verbose && all {
+        println!("🔍 Synapse Rule Enforcement");
+        println!("Scanning all files under: {}", if files.is_empty() {
+            ".".to_string()
+        } else {
+            files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ")
+        });
+        println!();
+    } else if verbose {
         println!("🔍 Synapse Rule Enforcement");
         println!("Files to check: {}", files.len());
         for file in &files {
@@ -56,10 +112,37 @@ pub async fn handle_check(matches: &ArgMatches, rule_graph_opt: Option<&RuleGrap
         }
     };
     
+    let metrics = RuleMetrics::default();
+    let shadow_before = rule_graph.shadow_hits();
+    
+    if all {
+        let roots: Vec<PathBuf> = if files.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            files.into_iter().cloned().collect()
+        };
+        let check_result = check_all(&roots, rule_graph, verbose, output, &metrics, fix)?;
+        if !dry_run {
+            save_rule_stats(&metrics, &shadow_before, rule_graph);
+        }
+        return finish(check_result, dry_run, verbose, output);
+    }
+    
     // Check each file against applicable rules
     let mut all_violations = Vec::new();
     let mut total_rules_applied = 0;
     
+    // Layering rules need the whole project, not just the files passed in
+    if imports {
+        let project_root = std::env::current_dir()?;
+        let violations = check_project_imports(&project_root, rule_graph)?;
+        if verbose {
+            println!("🧭 Import pass over {} found {} violation(s)", project_root.display(), violations.len());
+        }
+        metrics.record_hits(&violations);
+        all_violations.extend(violations);
+    }
+    
     for file_path in &files {
         if !file_path.exists() {
             if verbose {
@@ -69,7 +152,8 @@ pub async fn handle_check(matches: &ArgMatches, rule_graph_opt: Option<&RuleGrap
         }
         
         // Get applicable rules for this file
-        let composite_rules = rule_graph.rules_for(file_path)?;
+        let resolved = rule_graph.resolved_for(file_path);
+        let composite_rules = &resolved.composite;
         total_rules_applied += composite_rules.applicable_rules.len();
         
         if verbose {
@@ -89,23 +173,32 @@ pub async fn handle_check(matches: &ArgMatches, rule_graph_opt: Option<&RuleGrap
             }
         }
         
-        // Read file content
-        let content = match fs::read_to_string(file_path) {
+        // Binary and non-UTF-8 files are skipped, as with `--all`
+        let content = match read_text(file_path) {
             Ok(content) => content,
-            Err(e) => {
-                eprintln!("❌ Failed to read {}: {}", file_path.display(), e);
+            Err(reason) => {
+                print_skipped(file_path, &reason, verbose);
                 continue;
             }
         };
         
-        // Convert rules to CompiledRule format for enforcement
-        let compiled_rules: Vec<synapse_mcp::CompiledRule> = composite_rules.applicable_rules
-            .iter()
-            .map(|rule| synapse_mcp::CompiledRule::from_rule(rule.clone()))
-            .collect();
-        
-        // Check file against rules using unified enforcement
-        let violations = check_rules(file_path, &content, &compiled_rules)?;
+        // Check file against rules using unified enforcement; the project-wide
+        // pass has already evaluated import rules
+        let violations = if imports {
+            let compiled_rules: Vec<_> = resolved.compiled
+                .iter()
+                .filter(|rule| !is_import_rule(rule))
+                .cloned()
+                .collect();
+            let violations = check_rules_with(file_path, &content, &compiled_rules, &RulePrefilter::default(), &resolved.shadow_hits)?;
+            metrics.record_file(&compiled_rules, &violations);
+            violations
+        } else {
+            let violations = resolved.check(file_path, &content)?;
+            metrics.record_file(&resolved.compiled, &violations);
+            violations
+        };
+        let violations = if fix { fix_header_violations(file_path, violations, &resolved.compiled, output) } else { violations };
         
         if verbose && !violations.is_empty() {
             println!("   ❌ Found {} violation(s)", violations.len());
@@ -120,34 +213,207 @@ pub async fn handle_check(matches: &ArgMatches, rule_graph_opt: Option<&RuleGrap
         violations: all_violations,
         files_checked: files.len(),
         rules_applied: total_rules_applied,
+        files_failed: 0,
     };
     
     // Display results
-    display_check_results(&check_result, verbose);
+    report(&check_result, output, verbose)?;
+    if !dry_run {
+        save_rule_stats(&metrics, &shadow_before, rule_graph);
+    }
     
-    // Exit with appropriate code for pre-commit hook
+    finish(check_result, dry_run, verbose, output)
+}
+
+/// Add this run's rule hits to the project's rule statistics
+fn save_rule_stats(metrics: &RuleMetrics, shadow_before: &ShadowHits, rule_graph: &RuleGraph) {
+    metrics.record_shadow_hits(shadow_before, &rule_graph.shadow_hits());
+    let saved = std::env::current_dir()
+        .map_err(Into::into)
+        .and_then(|root| RuleStatsStore::record(&root, metrics));
+    if let Err(e) = saved {
+        eprintln!("⚠️  Failed to save rule statistics: {}", e);
+    }
+}
+
+/// Rewrite a file to fix its header rule violations (`--fix`)
+///
+/// Returns the violations that remain, including header violations the fixer
+/// can't resolve, such as files without a known comment syntax.
+fn fix_header_violations(path: &Path, mut violations: Vec<Violation>, rules: &[Arc<CompiledRule>], output: Output) -> Vec<Violation> {
+    if !violations.iter().any(|v| v.rule.is_header()) {
+        return violations;
+    }
+    let fixed = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| fix_headers(path, &content, &violations, rules));
+    let Some(fixed) = fixed else {
+        return violations;
+    };
+    if let Err(e) = fs::write(path, &fixed) {
+        eprintln!("❌ Failed to fix header in {}: {}", path.display(), e);
+        return violations;
+    }
+    if output.json {
+        eprintln!("🔧 Fixed license header in {}", path.display());
+    } else {
+        println!("🔧 Fixed license header in {}", path.display());
+    }
+
+    let lines: Vec<&str> = fixed.lines().collect();
+    violations.retain(|v| {
+        !v.rule.is_header() || !template_for(rules, &v.rule).is_some_and(|template| template.matches(&lines))
+    });
+    violations
+}
+
+/// Exit with appropriate code for pre-commit hook
+fn finish(check_result: CheckResult, dry_run: bool, verbose: bool, output: Output) -> Result<()> {
     if dry_run {
-        println!("\n🧪 Dry run complete - no enforcement applied");
+        // Keep JSON on stdout parseable
+        if output.json {
+            eprintln!("🧪 Dry run complete - no enforcement applied");
+        } else {
+            println!("\n🧪 Dry run complete - no enforcement applied");
+        }
         Ok(())
-    } else if check_result.violations.is_empty() {
-        if verbose {
+    } else if check_result.passes() {
+        // Rules that aren't enforced yet only warn
+        if verbose && !output.json {
             println!("\n✅ All files pass rule enforcement");
         }
         Ok(())
     } else {
+        if check_result.files_failed > 0 {
+            eprintln!("❌ {} file(s) could not be checked", check_result.files_failed);
+        }
         process::exit(1);
     }
 }
 
-// Legacy function removed - now using unified enforcement::check_rules
+/// Check every file under `roots`, printing each file's violations as soon as
+/// it is checked when the output format allows it
+fn check_all(roots: &[PathBuf], rule_graph: &RuleGraph, verbose: bool, output: Output, metrics: &RuleMetrics, fix: bool) -> Result<CheckResult> {
+    let mut result = CheckResult {
+        violations: Vec::new(),
+        files_checked: 0,
+        rules_applied: 0,
+        files_failed: 0,
+    };
+    let mut skipped = 0;
+    let (sender, receiver) = mpsc::channel();
+    
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            for root in roots {
+                scan_project(root, rule_graph, |scan| {
+                    if let FileScan::Checked { rules, violations, .. } = &scan {
+                        metrics.record_file(&rules.compiled, violations);
+                    }
+                    let _ = sender.send(scan);
+                });
+            }
+        });
+        
+        for scan in receiver {
+            match scan {
+                FileScan::Checked { path, rules, violations } => {
+                    result.files_checked += 1;
+                    result.rules_applied += rules.compiled.len();
+                    let violations = if fix {
+                        fix_header_violations(&path, violations, &rules.compiled, output)
+                    } else {
+                        violations
+                    };
+                    if violations.is_empty() {
+                        continue;
+                    }
+                    if !output.streams() {
+                        result.violations.extend(violations);
+                        continue;
+                    }
+                    if result.violations.is_empty() {
+                        println!("\n❌ Rule Violations Found:");
+                    }
+                    print_file_violations(&path, violations.iter());
+                    result.violations.extend(violations);
+                }
+                FileScan::Skipped { path, reason } => {
+                    skipped += 1;
+                    if let SkipReason::CheckFailed(e) = &reason {
+                        result.files_failed += 1;
+                        eprintln!("❌ Failed to check {}: {}", path.display(), e);
+                    } else {
+                        print_skipped(&path, &reason, verbose);
+                    }
+                }
+            }
+        }
+    });
+    
+    if !output.streams() {
+        report(&result, output, verbose)?;
+        if verbose && !output.json {
+            println!("  Files skipped: {}", skipped);
+        }
+        return Ok(result);
+    }
+    
+    display_summary(&result, verbose);
+    if verbose {
+        println!("  Files skipped: {}", skipped);
+    }
+    if !result.violations.is_empty() {
+        println!("\n💡 Fix these violations before committing.");
+    }
+    Ok(result)
+}
 
-fn display_check_results(result: &CheckResult, verbose: bool) {
+/// Report a file that wasn't read; unreadable files are always reported
+fn print_skipped(path: &Path, reason: &SkipReason, verbose: bool) {
+    if let SkipReason::Unreadable(e) = reason {
+        eprintln!("❌ Failed to read {}: {}", path.display(), e);
+    } else if verbose {
+        println!("⏭️  Skipping {} ({})", path.display(), reason);
+    }
+}
+
+/// Print the violations in the requested format
+fn report(result: &CheckResult, output: Output, verbose: bool) -> Result<()> {
+    if output.json {
+        let json = if output.by_owner {
+            serde_json::to_string_pretty(&OwnerReport {
+                owners: OwnerViolations::group(&result.violations),
+                files_checked: result.files_checked,
+                rules_applied: result.rules_applied,
+            })?
+        } else {
+            serde_json::to_string_pretty(&CheckResultData {
+                violations: result.violations.iter().map(RuleViolationDto::from).collect(),
+                files_checked: result.files_checked,
+                rules_applied: result.rules_applied,
+            })?
+        };
+        println!("{}", json);
+    } else if output.by_owner {
+        display_owner_results(result, verbose);
+    } else {
+        display_check_results(result, verbose);
+    }
+    Ok(())
+}
+
+fn display_summary(result: &CheckResult, verbose: bool) {
     if verbose {
         println!("\n📊 Check Summary:");
         println!("  Files checked: {}", result.files_checked);
         println!("  Rules applied: {}", result.rules_applied);
         println!("  Violations found: {}", result.violations.len());
     }
+}
+
+fn display_check_results(result: &CheckResult, verbose: bool) {
+    display_summary(result, verbose);
     
     if result.violations.is_empty() {
         return;
@@ -164,33 +430,69 @@ fn display_check_results(result: &CheckResult, verbose: bool) {
     
     println!("\n❌ Rule Violations Found:");
     for (file_path, violations) in violations_by_file {
-        println!("\n📄 {}", file_path.display());
+        print_file_violations(file_path, violations.into_iter());
+    }
+    
+    println!("\n💡 Fix these violations before committing.");
+}
+
+fn display_owner_results(result: &CheckResult, verbose: bool) {
+    display_summary(result, verbose);
+    
+    if result.violations.is_empty() {
+        return;
+    }
+    
+    println!("\n❌ Rule Violations Found:");
+    for (owner, violations) in group_by_owner(&result.violations) {
+        println!("\n👥 {} ({} violation(s))", owner.unwrap_or("No owner"), violations.len());
         
+        let mut violations_by_file: BTreeMap<&Path, Vec<&Violation>> = BTreeMap::new();
         for violation in violations {
-            match violation.rule.rule_type {
-                RuleType::Forbidden => {
-                    println!("  ❌ FORBIDDEN: {} ({})", violation.rule.message, violation.rule.name);
-                    if let (Some(line_num), Some(line_content)) = (&violation.line_number, &violation.line_content) {
-                        println!("     Line {}: {}", line_num, line_content.trim());
-                        println!("     Pattern: {}", violation.rule.pattern);
-                    }
-                }
-                RuleType::Required => {
-                    println!("  ⚠️  MISSING REQUIRED: {} ({})", violation.rule.message, violation.rule.name);
-                    println!("     Required pattern: {}", violation.rule.pattern);
-                }
-                _ => {}
-            }
+            violations_by_file.entry(&violation.file_path).or_default().push(violation);
+        }
+        for (file_path, violations) in violations_by_file {
+            print_file_violations(file_path, violations.into_iter());
         }
     }
     
     println!("\n💡 Fix these violations before committing.");
 }
 
+pub(crate) fn print_file_violations<'a>(file_path: &Path, violations: impl Iterator<Item = &'a Violation>) {
+    println!("\n📄 {}", file_path.display());
+    
+    for violation in violations {
+        match violation.rule.rule_type {
+            RuleType::Forbidden => {
+                println!("  ❌ FORBIDDEN: {} ({})", violation.rule.message, violation.rule.name);
+                if let (Some(line_num), Some(line_content)) = (&violation.line_number, &violation.line_content) {
+                    println!("     Line {}: {}", line_num, line_content.trim());
+                    println!("     Pattern: {}", violation.rule.pattern);
+                }
+            }
+            RuleType::Required => {
+                println!("  ⚠️  MISSING REQUIRED: {} ({})", violation.rule.message, violation.rule.name);
+                println!("     Required pattern: {}", violation.rule.pattern);
+            }
+            RuleType::Convention => {
+                println!("  📛 NAMING CONVENTION: {} ({})", violation.rule.message, violation.rule.name);
+                println!("     Expected file name: {}", violation.rule.pattern);
+            }
+            _ => {}
+        }
+        if violation.warning
+            && let Some(date) = violation.rule.enforce_from()
+        {
+            println!("     ⏳ Warning only - enforced from {}", date);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use synapse_mcp::{Rule, CompiledRule};
+    use synapse_mcp::{Rule, CompiledRule, check_rules};
     use std::path::Path;
 
     #[test]
@@ -306,4 +608,19 @@ mod tests {
         // Standard rules should not create violations
         assert_eq!(violations.len(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_failed_files_fail_the_check() {
+        let warning = Violation::from_compiled_rule(
+            PathBuf::from("lib.rs"),
+            &CompiledRule::from_rule(Rule::new("todo".to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string())),
+            Some(1),
+            None,
+        ).into_warning();
+        let result = |violations: Vec<Violation>, files_failed| CheckResult { violations, files_checked: 2, rules_applied: 1, files_failed };
+
+        assert!(result(vec![warning.clone()], 0).passes());
+        assert!(!result(vec![warning], 1).passes());
+        assert!(!result(Vec::new(), 1).passes());
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use std::path::PathBuf;
+
+use synapse_mcp::{RuleGraph, RuleResolution, RuleSourceKind};
+
+pub async fn handle_explain(matches: &ArgMatches, rule_graph_opt: Option<&RuleGraph>) -> Result<()> {
+    let path: &PathBuf = matches.get_one::<PathBuf>("path")
+        .ok_or_else(|| anyhow::anyhow!("Path is required"))?;
+    let rule_id = matches.get_one::<String>("rule");
+
+    let Some(rule_graph) = rule_graph_opt else {
+        println!("⚠️  No rule graph available - no rules apply to {}", path.display());
+        return Ok(());
+    };
+
+    let resolution = rule_graph.explain(path)?;
+    display_resolution(&resolution, rule_id.map(String::as_str));
+    Ok(())
+}
+
+fn display_resolution(resolution: &RuleResolution, rule_id: Option<&str>) {
+    println!("🔍 Rule resolution for {}", resolution.target.display());
+
+    println!("\n📂 Directories walked (nearest first):");
+    for step in &resolution.directories {
+        if step.rule_files.is_empty() {
+            println!("  {}", step.dir.display());
+        } else {
+            let files: Vec<String> = step.rule_files.iter().map(|f| f.display().to_string()).collect();
+            println!("  {}  ← {}", step.dir.display(), files.join(", "));
+        }
+    }
+
+    if let Some(root) = &resolution.stopped_at_root {
+        println!("  (stopped: {} sets `root: true`)", root.display());
+    }
+
+    println!("\n📄 Contributing rule files (resolution order):");
+    if resolution.sources.is_empty() {
+        println!("  (none)");
+    }
+    for (index, source) in resolution.sources.iter().enumerate() {
+        match &source.kind {
+            RuleSourceKind::Directory => println!("  {}. {}", index + 1, source.path.display()),
+            RuleSourceKind::Inherited { from } => println!(
+                "  {}. {} (inherited from {})", index + 1, source.path.display(), from.display()
+            ),
+            RuleSourceKind::Extended { from, pack } if source.path.as_os_str() == pack.as_str() => println!(
+                "  {}. {} (extended by {})", index + 1, source.path.display(), from.display()
+            ),
+            RuleSourceKind::Extended { from, pack } => println!(
+                "  {}. {} (pack {} extended by {})", index + 1, source.path.display(), pack, from.display()
+            ),
+        }
+    }
+
+    let overridden: Vec<_> = resolution.overridden.iter()
+        .filter(|o| rule_id.is_none_or(|id| RuleResolution::rule_matches(&o.rule, id)))
+        .collect();
+    if !resolution.overrides.is_empty() {
+        println!("\n✂️  Dropped by overrides:");
+        if overridden.is_empty() {
+            println!("  (none)");
+        }
+        for entry in &overridden {
+            println!("  {} from {} — overridden by {}",
+                entry.rule.name, entry.source.display(), entry.overridden_by.display());
+        }
+    }
+
+    let shadowed: Vec<_> = resolution.shadowed.iter()
+        .filter(|s| rule_id.is_none_or(|id| RuleResolution::rule_matches(&s.rule, id)))
+        .collect();
+    if !shadowed.is_empty() {
+        println!("\n🌓 Shadowed by a nearer definition:");
+        for entry in &shadowed {
+            println!("  {} from {} — shadowed by {}",
+                entry.rule.name, entry.source.display(), entry.shadowed_by.display());
+        }
+    }
+
+    println!("\n📋 Final precedence order:");
+    let mut shown = 0;
+    for (index, applied) in resolution.applied.iter().enumerate() {
+        if rule_id.is_some_and(|id| !RuleResolution::rule_matches(&applied.rule, id)) {
+            continue;
+        }
+        shown += 1;
+        println!("  {}. {:?} {} `{}` ({})",
+            index + 1, applied.rule.rule_type, applied.rule.name, applied.rule.pattern, applied.source.display());
+        if let Some(modified_by) = &applied.modified_by {
+            println!("     modified by {}", modified_by.display());
+        }
+    }
+    if shown == 0 {
+        println!("  (none)");
+    }
+
+    if let Some(id) = rule_id
+        && shown == 0
+        && overridden.is_empty()
+        && shadowed.is_empty()
+    {
+        println!("\n💡 Rule '{}' is not defined in any rule file that applies to this path", id);
+    }
+}
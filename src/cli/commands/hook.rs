@@ -0,0 +1,42 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use synapse_mcp::{RuleGraph, check_commit_message};
+
+use super::check::print_file_violations;
+
+pub async fn handle_hook(matches: &ArgMatches, rule_graph_opt: Option<&RuleGraph>) -> Result<()> {
+    match matches.subcommand() {
+        Some(("commit-msg", sub_matches)) => handle_commit_msg(sub_matches, rule_graph_opt),
+        _ => unreachable!("clap requires a hook subcommand"),
+    }
+}
+
+/// Check a commit message file against the commit message rules that apply
+/// at the project root
+fn handle_commit_msg(matches: &ArgMatches, rule_graph_opt: Option<&RuleGraph>) -> Result<()> {
+    let message_file = matches.get_one::<PathBuf>("file").expect("required");
+    let Some(rule_graph) = rule_graph_opt else {
+        return Ok(());
+    };
+
+    let message = fs::read_to_string(message_file)?;
+    let resolved = rule_graph.resolved_for(&std::env::current_dir()?.join("COMMIT_EDITMSG"));
+    let violations = check_commit_message(message_file, &message, &resolved.compiled)?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    println!("\n❌ Commit Message Rule Violations:");
+    print_file_violations(message_file, violations.iter());
+
+    // Rules that aren't enforced yet only warn
+    if violations.iter().all(|v| v.warning) {
+        return Ok(());
+    }
+    println!("\n💡 Edit the message and commit again; it was saved in {}", message_file.display());
+    process::exit(1);
+}
@@ -4,4 +4,7 @@ pub mod context;
 pub mod query;
 pub mod status;
 pub mod check;
-pub mod enforce_context;
\ No newline at end of file
+pub mod enforce_context;
+pub mod explain;
+pub mod rules;
+pub mod hook;
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use std::path::PathBuf;
+use std::process;
+
+use synapse_mcp::{DocsFormat, ExampleExpectation, GraphFormat, LintSeverity, RuleDiff, RuleGraph, RuleStatsStore, RuleSystem};
+
+pub async fn handle_rules(matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("lint", sub_matches)) => handle_lint(sub_matches),
+        Some(("test", sub_matches)) => handle_test(sub_matches),
+        Some(("graph", sub_matches)) => handle_graph(sub_matches),
+        Some(("docs", sub_matches)) => handle_docs(sub_matches),
+        Some(("stats", sub_matches)) => handle_stats(sub_matches),
+        Some(("diff", sub_matches)) => handle_diff(sub_matches),
+        _ => unreachable!("clap requires a rules subcommand"),
+    }
+}
+
+fn handle_lint(matches: &ArgMatches) -> Result<()> {
+    let root = matches.get_one::<PathBuf>("path").cloned().unwrap_or_else(|| PathBuf::from("."));
+
+    let issues = RuleSystem::for_project(&root).lint_rules(&root)?;
+    if issues.is_empty() {
+        println!("✅ No problems found in rule files under {}", root.display());
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("{}", issue);
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == LintSeverity::Error).count();
+    let warnings = issues.len() - errors;
+    println!("\n{} error(s), {} warning(s)", errors, warnings);
+
+    if errors > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn handle_test(matches: &ArgMatches) -> Result<()> {
+    let root = matches.get_one::<PathBuf>("path").cloned().unwrap_or_else(|| PathBuf::from("."));
+
+    let results = RuleSystem::for_project(&root).test_rules(&root)?;
+    if results.is_empty() {
+        println!("ℹ️  No rule examples found under {}", root.display());
+        return Ok(());
+    }
+
+    let failures: Vec<_> = results.iter().filter(|r| !r.passed()).collect();
+    for failure in &failures {
+        let location = format!("{}:{}", failure.rule_file.display(), failure.line);
+        let expected = match failure.expectation {
+            ExampleExpectation::Pass => "pass",
+            ExampleExpectation::Fail => "fail",
+        };
+        match (&failure.rule_name, &failure.error) {
+            (None, _) => println!("❌ {}: example is not under any rule", location),
+            (Some(rule), Some(error)) => println!("❌ {}: rule '{}' could not check the example: {}", location, rule, error),
+            (Some(rule), None) => println!(
+                "❌ {}: rule '{}' should {} this example but reported {} violation(s)",
+                location, rule, expected, failure.violations
+            ),
+        }
+    }
+
+    println!("\n{} example(s), {} passed, {} failed", results.len(), results.len() - failures.len(), failures.len());
+
+    if !failures.is_empty() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn handle_graph(matches: &ArgMatches) -> Result<()> {
+    let root = matches.get_one::<PathBuf>("path").cloned().unwrap_or_else(|| PathBuf::from("."));
+    let format = matches.get_one::<String>("format")
+        .and_then(|name| GraphFormat::from_name(name))
+        .unwrap_or(GraphFormat::Dot);
+
+    let rule_graph = RuleGraph::from_project(&root)?;
+    print!("{}", rule_graph.export(&root).render(format)?);
+    Ok(())
+}
+
+fn handle_docs(matches: &ArgMatches) -> Result<()> {
+    let root = matches.get_one::<PathBuf>("path").cloned().unwrap_or_else(|| PathBuf::from("."));
+    let out = matches.get_one::<PathBuf>("out").cloned().unwrap_or_else(|| PathBuf::from("site"));
+    let format = matches.get_one::<String>("format")
+        .and_then(|name| DocsFormat::from_name(name))
+        .unwrap_or(DocsFormat::Html);
+
+    let catalog = RuleGraph::from_project(&root)?.catalog(&root);
+    let written = catalog.write_site(&out, format)?;
+    println!("📚 Wrote {} page(s) for {} rule(s) to {}", written, catalog.entries.len(), out.display());
+    Ok(())
+}
+
+fn handle_stats(matches: &ArgMatches) -> Result<()> {
+    let root = matches.get_one::<PathBuf>("path").cloned().unwrap_or_else(|| PathBuf::from("."));
+    let dead_after = matches.get_one::<u64>("dead-after").copied().unwrap_or(20);
+    let top = matches.get_one::<usize>("top").copied().unwrap_or(10);
+
+    let report = RuleStatsStore::load(&root)?.report(dead_after, top);
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if report.runs == 0 {
+        println!("ℹ️  No rule statistics recorded under {} yet", root.display());
+        return Ok(());
+    }
+    println!("📈 Rule statistics over {} run(s)", report.runs);
+
+    if report.dead.is_empty() {
+        println!("\n✅ Every rule matched within the last {} run(s)", dead_after);
+    } else {
+        println!("\n💤 Rules without a match in {} or more run(s):", dead_after);
+        for entry in &report.dead {
+            let last_hit = entry.stats.last_hit
+                .map(|at| format!("last hit {}", at.format("%Y-%m-%d")))
+                .unwrap_or_else(|| "never hit".to_string());
+            println!("  • {} ({} run(s) without a match, {})", entry.rule, entry.stats.runs_since_hit, last_hit);
+        }
+    }
+
+    if !report.noisiest.is_empty() {
+        println!("\n🔥 Rules with the most hits:");
+        for entry in &report.noisiest {
+            println!("  • {} ({} hit(s) in {} file check(s))", entry.rule, entry.stats.hits, entry.stats.files_checked);
+        }
+    }
+    Ok(())
+}
+
+fn handle_diff(matches: &ArgMatches) -> Result<()> {
+    let repo = matches.get_one::<PathBuf>("repo").cloned().unwrap_or_else(|| PathBuf::from("."));
+    let rev_a = matches.get_one::<String>("rev-a").expect("required");
+    let rev_b = matches.get_one::<String>("rev-b").expect("required");
+    let paths: Vec<PathBuf> = matches.get_many::<PathBuf>("path")
+        .map(|paths| paths.cloned().collect())
+        .unwrap_or_default();
+
+    let diff = RuleDiff::between(&repo, rev_a, rev_b, &paths)?;
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff.to_text());
+    }
+    Ok(())
+}
@@ -11,6 +11,8 @@ pub struct Config {
     pub runtime: RuntimeConfig,
     pub logging: LoggingConfig,
     pub cache: CacheConfig,
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
 }
 
 /// Neo4j database configuration with connection pooling support
@@ -80,6 +82,27 @@ pub struct CacheConfig {
     pub metrics_enabled: bool,
 }
 
+/// Rule file discovery settings (`[discovery]` in config.toml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DiscoveryConfig {
+    /// Names of directories holding rule files (default `[".synapse"]`);
+    /// setting it replaces the default, so list `.synapse` to keep it
+    pub rule_dirs: Vec<String>,
+    /// Globs of paths to skip, relative to the project root (`.git`,
+    /// `target` and `node_modules` are always skipped)
+    pub exclude: Vec<String>,
+    /// Follow symbolic links while walking the project
+    pub follow_symlinks: bool,
+    /// Maximum depth below the project root of the directories holding rule
+    /// directories; the project root is depth 0 and the rule directory
+    /// (`.synapse/`) doesn't count, so root rules are always found
+    pub max_depth: Option<usize>,
+    /// Rule files or directories outside the project, such as org-wide
+    /// policies; their rules apply to the whole project
+    pub external: Vec<PathBuf>,
+}
+
 impl Neo4jConfig {
     /// Convert to connection manager configuration
     pub fn to_connection_config(&self) -> crate::db::connection_manager::Neo4jConnectionConfig {
@@ -110,6 +133,7 @@ impl Default for Config {
             runtime: RuntimeConfig::default(),
             logging: LoggingConfig::default(),
             cache: CacheConfig::default(),
+            discovery: DiscoveryConfig::default(),
         }
     }
 }
@@ -181,6 +205,18 @@ impl Default for CacheConfig {
     }
 }
 
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            rule_dirs: vec![".synapse".to_string()],
+            exclude: Vec::new(),
+            follow_symlinks: false,
+            max_depth: None,
+            external: Vec::new(),
+        }
+    }
+}
+
 impl Config {
     /// Load configuration from multiple sources with precedence:
     /// 1. config.toml file (if exists)
@@ -231,6 +267,9 @@ impl Config {
                 if let Ok(runtime) = config.get::<RuntimeConfig>("runtime") {
                     result.runtime = runtime;
                 }
+                if let Ok(discovery) = config.get::<DiscoveryConfig>("discovery") {
+                    result.discovery = discovery;
+                }
             }
         }
         
@@ -280,6 +319,7 @@ impl Config {
                 max_entries: 100,
                 metrics_enabled: false,
             },
+            discovery: DiscoveryConfig::default(),
         }
     }
 
@@ -291,6 +331,7 @@ impl Config {
         self.server = other.server;
         self.runtime = other.runtime;
         self.cache = other.cache;
+        self.discovery = other.discovery;
     }
 
     /// Merge environment variables for backward compatibility
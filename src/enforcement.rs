@@ -1,6 +1,30 @@
-use crate::models::{CompiledRule, Violation, RuleType, PatternMatcher};
+pub mod commit_msg;
+pub mod dependencies;
+pub mod header;
+pub mod imports;
+pub mod lifecycle;
+pub mod naming;
+pub mod prefilter;
+pub mod scan;
+pub mod scope;
+pub mod secrets;
+
+use crate::models::{CompiledRule, Violation, RuleType, PatternMatcher, Threshold};
+use commit_msg::is_commit_message_rule;
+use dependencies::{is_dependency_rule, check_dependency_rule};
+use header::{is_header_rule, check_header_rule};
+use imports::{is_import_rule, check_import_rule};
+use lifecycle::{RolloutStage, ShadowCounter};
+use naming::{is_path_rule, check_path_rule, targets_file, CheckedPath};
+use prefilter::{RulePrefilter, PREFILTER_MIN_RULES};
+use scope::{RuleScope, extract_items};
+use secrets::{is_secret_rule, check_secret_rule};
+use crate::ast_analysis::{find_structural_violations, AstAnalysisError, StructuralCheck};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
-use tracing::instrument;
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, instrument, warn};
 
 /// Central rule checking function for enforcing development rules
 /// 
@@ -11,18 +35,22 @@ use tracing::instrument;
 /// 
 /// * `file_path` - Path to the file being checked (used in violation reporting)
 /// * `content` - Full text content of the file to analyze
-/// * `rules` - Pre-compiled rules with optimized pattern matchers
+/// * `rules` - Pre-compiled rules with optimized pattern matchers, owned or
+///   shared (`Arc<CompiledRule>`, as returned by `RuleGraph::resolved_for`)
 /// 
 /// # Returns
 /// 
 /// Returns a vector of violations found in the file. Empty vector means
-/// the file passes all rule checks.
+/// the file passes all rule checks. Hits of shadow rules are logged but not
+/// counted; use [`check_rules_with`] to count them.
 /// 
 /// # Performance
 /// 
 /// * Time complexity: O(n * m * k) where n = content length, m = number of rules, k = average pattern complexity
 /// * Pre-compiled regex patterns provide significant performance gains over string compilation
 /// * Content is split into lines once and reused for all rule checks
+/// * Lists of `PREFILTER_MIN_RULES` or more rules are prefiltered: one scan of
+///   the content selects the rules that can match (see `check_rules_with`)
 /// 
 /// # Error Conditions
 /// 
@@ -43,6 +71,7 @@ use tracing::instrument;
 ///     message: "Remove TODO comments".to_string(),
 ///     tags: vec![],
 ///     metadata: std::collections::HashMap::new(),
+///     owners: vec![],
 /// };
 /// 
 /// let compiled = CompiledRule::from_rule(rule);
@@ -54,112 +83,372 @@ use tracing::instrument;
 /// 
 /// assert_eq!(violations.len(), 1);
 /// ```
-#[instrument(skip(content, rules), fields(file_path = %file_path.display(), content_length = content.len(), rule_count = rules.len()))]
-pub fn check_rules(
+pub fn check_rules<R: Borrow<CompiledRule>>(
     file_path: &Path,
     content: &str, 
-    rules: &[CompiledRule]
+    rules: &[R]
+) -> crate::Result<Vec<Violation>> {
+    let prefilter = if rules.len() >= PREFILTER_MIN_RULES {
+        RulePrefilter::new(rules)
+    } else {
+        RulePrefilter::default()
+    };
+    check_rules_with(file_path, content, rules, &prefilter, &ShadowCounter::default())
+}
+
+/// Check a file against rules using a prefilter built from the same rules
+/// 
+/// The prefilter scans the content once with all literal and regex patterns
+/// combined; FORBIDDEN and REQUIRED rules it rules out skip line-by-line
+/// matching. Keep the prefilter alongside the rule list to reuse it across
+/// files, as `ResolvedRules::check` does.
+///
+/// Rollout settings apply here: hits of shadow rules are only counted in
+/// `shadow_hits`, and rules that aren't enforced yet yield warnings (see
+/// [`lifecycle`]).
+#[instrument(skip(content, rules, prefilter, shadow_hits), fields(file_path = %file_path.display(), content_length = content.len(), rule_count = rules.len()))]
+pub fn check_rules_with<R: Borrow<CompiledRule>>(
+    file_path: &Path,
+    content: &str,
+    rules: &[R],
+    prefilter: &RulePrefilter,
+    shadow_hits: &ShadowCounter,
 ) -> crate::Result<Vec<Violation>> {
     let mut violations = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
+    let candidates = prefilter.candidates(content, &lines);
+    let today = lifecycle::today();
+    let checked_path = CheckedPath::new(file_path);
     
-    for compiled_rule in rules {
+    for (index, compiled_rule) in rules.iter().enumerate() {
+        let compiled_rule: &CompiledRule = compiled_rule.borrow();
         let rule = &compiled_rule.rule;
         
-        match rule.rule_type {
-            RuleType::Forbidden => {
-                // Check if forbidden pattern exists
-                let found_violations = check_forbidden_pattern(
-                    file_path,
-                    &lines,
-                    compiled_rule,
-                )?;
-                violations.extend(found_violations);
-            }
-            RuleType::Required => {
-                // Check if required pattern is missing
-                if let Some(violation) = check_required_pattern(
-                    file_path,
-                    content,
-                    compiled_rule,
-                )? {
-                    violations.push(violation);
-                }
-            }
-            // Standard and Convention rules are suggestions, not enforced
-            RuleType::Standard | RuleType::Convention => {
-                // These could be implemented as warnings in the future
-                continue;
-            }
+        // Expired and commit message rules are skipped; `files:` limits a
+        // rule to matching files
+        let stage = RolloutStage::of(rule, today);
+        if stage == RolloutStage::Expired || is_commit_message_rule(compiled_rule) || !targets_file(&checked_path, compiled_rule) {
+            continue;
+        }
+        
+        let found = check_rule(&checked_path, content, &lines, compiled_rule, candidates.contains(index))?;
+        match stage {
+            RolloutStage::Shadow => shadow_hits.record(file_path, rule, found.len()),
+            RolloutStage::Warning => violations.extend(found.into_iter().map(Violation::into_warning)),
+            _ => violations.extend(found),
         }
     }
     
     Ok(violations)
 }
 
+/// Check a file against one rule
+///
+/// `candidate` is false when the prefilter ruled out any occurrence of the
+/// rule's pattern.
+fn check_rule(
+    checked_path: &CheckedPath,
+    content: &str,
+    lines: &[&str],
+    compiled_rule: &CompiledRule,
+    candidate: bool,
+) -> crate::Result<Vec<Violation>> {
+    let rule = &compiled_rule.rule;
+    let file_path = checked_path.path();
+    
+    // Path rules validate the file path rather than its content
+    if is_path_rule(compiled_rule) {
+        return Ok(check_path_rule(checked_path, compiled_rule).into_iter().collect());
+    }
+    
+    // Import rules validate the file's use/import statements
+    if is_import_rule(compiled_rule) {
+        return Ok(check_import_rule(file_path, lines, compiled_rule));
+    }
+    
+    // Dependency rules validate the packages a manifest declares
+    if is_dependency_rule(compiled_rule) {
+        return Ok(check_dependency_rule(file_path, lines, compiled_rule));
+    }
+    
+    // Header rules only look at the first lines of the file
+    if is_header_rule(compiled_rule) {
+        return Ok(check_header_rule(file_path, lines, compiled_rule).into_iter().collect());
+    }
+    
+    // Secret rules measure the entropy of what their detector matches
+    if is_secret_rule(compiled_rule) {
+        return Ok(check_secret_rule(file_path, lines, compiled_rule));
+    }
+    
+    // Structural rules report each offending item; the parser only accepts them on FORBIDDEN rules
+    if let PatternMatcher::Structural(check) = &compiled_rule.matcher {
+        if rule.rule_type == RuleType::Forbidden {
+            return Ok(check_structural_rule(file_path, content, lines, compiled_rule, *check));
+        }
+        return Ok(Vec::new());
+    }
+    
+    // File size and line length limits don't match a pattern
+    if let PatternMatcher::Threshold(threshold) = &compiled_rule.matcher {
+        return Ok(check_threshold_rule(file_path, lines, compiled_rule, *threshold));
+    }
+    
+    match rule.rule_type {
+        // Patterns the prefilter didn't find have no occurrences
+        RuleType::Forbidden if !candidate => Ok(Vec::new()),
+        // Check if forbidden pattern exists
+        RuleType::Forbidden => check_forbidden_pattern(file_path, lines, compiled_rule),
+        RuleType::Required => match rule_scope(compiled_rule) {
+            RuleScope::File if !candidate => {
+                if occurrence_limit(compiled_rule, "min").unwrap_or(1) > 0 {
+                    Ok(vec![missing_required(file_path, compiled_rule)])
+                } else {
+                    Ok(Vec::new())
+                }
+            }
+            // Check if required pattern is missing
+            RuleScope::File => Ok(check_required_pattern(file_path, content, compiled_rule)?.into_iter().collect()),
+            // Check every item in scope for the required pattern
+            scope => Ok(check_required_per_item(file_path, lines, compiled_rule, scope)),
+        },
+        // Standard and Convention rules are suggestions, not enforced
+        // (Convention rules tagged `[path]` are handled above)
+        RuleType::Standard | RuleType::Convention => Ok(Vec::new()),
+    }
+}
+
 /// Check for forbidden pattern violations
+/// 
+/// Rules with a `max` limit (`MAX 3: ...`) tolerate that many occurrences;
+/// every line that pushes the count over the limit is reported.
 fn check_forbidden_pattern(
     file_path: &Path,
     lines: &[&str],
     compiled_rule: &CompiledRule,
 ) -> crate::Result<Vec<Violation>> {
     let mut violations = Vec::new();
+    let allowed = occurrence_limit(compiled_rule, "max").unwrap_or(0);
+    let mut seen = 0;
     
-    match &compiled_rule.matcher {
-        PatternMatcher::Regex(regex) => {
-            for (line_num, line) in lines.iter().enumerate() {
-                if regex.is_match(line) {
-                    violations.push(Violation::from_compiled_rule(
-                        file_path.to_path_buf(),
-                        compiled_rule,
-                        Some(line_num + 1),
-                        Some(line.to_string()),
-                    ));
-                }
-            }
+    for (line_num, line) in lines.iter().enumerate() {
+        let occurrences = count_occurrences(&compiled_rule.matcher, line);
+        if occurrences == 0 {
+            continue;
         }
-        PatternMatcher::Literal(pattern) => {
-            for (line_num, line) in lines.iter().enumerate() {
-                if line.contains(pattern) {
-                    violations.push(Violation::from_compiled_rule(
-                        file_path.to_path_buf(),
-                        compiled_rule,
-                        Some(line_num + 1),
-                        Some(line.to_string()),
-                    ));
-                }
-            }
+        
+        seen += occurrences;
+        if seen > allowed {
+            violations.push(Violation::from_compiled_rule(
+                file_path.to_path_buf(),
+                compiled_rule,
+                Some(line_num + 1),
+                Some(line.to_string()),
+            ));
         }
     }
     
     Ok(violations)
 }
 
+/// Count pattern occurrences in a single line
+fn count_occurrences(matcher: &PatternMatcher, line: &str) -> usize {
+    match matcher {
+        PatternMatcher::Regex(regex) => regex.find_iter(line).count(),
+        PatternMatcher::Literal(pattern) => line.matches(pattern.as_str()).count(),
+        // Evaluated separately by check_structural_rule, check_threshold_rule,
+        // check_import_rule and check_header_rule
+        PatternMatcher::Structural(_) | PatternMatcher::Threshold(_) | PatternMatcher::Glob(_) | PatternMatcher::Header(_) => 0,
+    }
+}
+
+/// Occurrence limit (`max` or `min`) recorded on a count rule
+fn occurrence_limit(compiled_rule: &CompiledRule, key: &str) -> Option<usize> {
+    compiled_rule.rule.metadata.get(key).and_then(|value| value.parse().ok())
+}
+
+/// Check a file-size or line-length threshold rule
+fn check_threshold_rule(
+    file_path: &Path,
+    lines: &[&str],
+    compiled_rule: &CompiledRule,
+    threshold: Threshold,
+) -> Vec<Violation> {
+    match threshold {
+        Threshold::MaxLines(max) => {
+            if lines.len() > max {
+                vec![Violation::from_compiled_rule(
+                    file_path.to_path_buf(),
+                    compiled_rule,
+                    Some(max + 1),
+                    Some(lines[max].to_string()),
+                )]
+            } else {
+                Vec::new()
+            }
+        }
+        Threshold::MaxLineLength(max) => lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.chars().count() > max)
+            .map(|(line_num, line)| Violation::from_compiled_rule(
+                file_path.to_path_buf(),
+                compiled_rule,
+                Some(line_num + 1),
+                Some(line.to_string()),
+            ))
+            .collect(),
+    }
+}
+
+/// Warn, once per rule, that a structural rule can't run in this build
+///
+/// Returns whether a warning was logged.
+fn warn_structural_disabled(rule_name: &str) -> bool {
+    static WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    let first = WARNED.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(rule_name.to_string());
+    if first {
+        warn!("Structural rule '{}' is not checked: synapse was built without the `ast-fixes` feature", rule_name);
+    }
+    first
+}
+
+/// Check a structural rule by walking the syntax tree of a Rust source file
+/// 
+/// Non-Rust files, files that fail to parse and builds without the `ast-fixes`
+/// feature produce no violations.
+fn check_structural_rule(
+    file_path: &Path,
+    content: &str,
+    lines: &[&str],
+    compiled_rule: &CompiledRule,
+    check: StructuralCheck,
+) -> Vec<Violation> {
+    if file_path.extension() != Some("rs".as_ref()) {
+        return Vec::new();
+    }
+    
+    match find_structural_violations(content, check) {
+        Ok(findings) => findings
+            .into_iter()
+            .map(|finding| {
+                let line_content = lines.get(finding.line.saturating_sub(1))
+                    .map(|line| line.to_string())
+                    .unwrap_or(finding.item);
+                Violation::from_compiled_rule(
+                    file_path.to_path_buf(),
+                    compiled_rule,
+                    Some(finding.line),
+                    Some(line_content),
+                )
+            })
+            .collect(),
+        Err(AstAnalysisError::FeatureNotEnabled) => {
+            warn_structural_disabled(&compiled_rule.rule.name);
+            Vec::new()
+        }
+        Err(e) => {
+            debug!("Skipping structural rule '{}' for {}: {}", check.name(), file_path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Scope of a REQUIRED rule, taken from its `scope` attribute (defaults to file)
+fn rule_scope(compiled_rule: &CompiledRule) -> RuleScope {
+    compiled_rule.rule.metadata.get("scope")
+        .and_then(|name| RuleScope::from_name(name))
+        .unwrap_or(RuleScope::File)
+}
+
+/// Check a scoped REQUIRED rule against each item in the file
+/// 
+/// Each item that lacks the pattern produces its own violation pointing at
+/// the item's declaration line.
+fn check_required_per_item(
+    file_path: &Path,
+    lines: &[&str],
+    compiled_rule: &CompiledRule,
+    scope: RuleScope,
+) -> Vec<Violation> {
+    let required = occurrence_limit(compiled_rule, "min").unwrap_or(1);
+    extract_items(file_path, lines)
+        .into_iter()
+        .filter(|item| scope != RuleScope::Function || item.is_function())
+        .filter(|item| {
+            let occurrences: usize = lines[item.start..item.end]
+                .iter()
+                .map(|line| count_occurrences(&compiled_rule.matcher, line))
+                .sum();
+            occurrences < required
+        })
+        .map(|item| Violation::from_compiled_rule(
+            file_path.to_path_buf(),
+            compiled_rule,
+            Some(item.line),
+            Some(lines[item.line - 1].to_string()),
+        ))
+        .collect()
+}
+
 /// Check for required pattern violations
 fn check_required_pattern(
     file_path: &Path,
     content: &str,
     compiled_rule: &CompiledRule,
 ) -> crate::Result<Option<Violation>> {
+    // Rules with a `min` limit (`MIN 2: ...`) need that many occurrences
+    let required = occurrence_limit(compiled_rule, "min").unwrap_or(1);
     let pattern_found = match &compiled_rule.matcher {
         PatternMatcher::Regex(regex) => {
-            content.lines().any(|line| regex.is_match(line))
+            content.lines().map(|line| regex.find_iter(line).count()).sum::<usize>() >= required
         }
         PatternMatcher::Literal(pattern) => {
-            content.contains(pattern)
+            content.matches(pattern.as_str()).count() >= required
         }
+        // Evaluated separately by check_structural_rule, check_threshold_rule,
+        // check_import_rule and check_header_rule
+        PatternMatcher::Structural(_) | PatternMatcher::Threshold(_) | PatternMatcher::Glob(_) | PatternMatcher::Header(_) => true,
     };
     
     if pattern_found {
         Ok(None)
     } else {
-        Ok(Some(Violation::from_compiled_rule(
-            file_path.to_path_buf(),
-            compiled_rule,
-            None,
-            None,
-        )))
+        Ok(Some(missing_required(file_path, compiled_rule)))
+    }
+}
+
+/// File-level violation for a REQUIRED pattern that is missing
+fn missing_required(file_path: &Path, compiled_rule: &CompiledRule) -> Violation {
+    Violation::from_compiled_rule(
+        file_path.to_path_buf(),
+        compiled_rule,
+        None,
+        None,
+    )
+}
+
+/// Group violations by the owners of their rules, sorted by owner with
+/// violations of unowned rules last under `None`
+///
+/// A violation of a rule with several owners is listed under each of them.
+pub fn group_by_owner(violations: &[Violation]) -> Vec<(Option<&str>, Vec<&Violation>)> {
+    let mut groups: BTreeMap<(bool, &str), Vec<&Violation>> = BTreeMap::new();
+    for violation in violations {
+        let owners = violation.rule.owners();
+        if owners.is_empty() {
+            groups.entry((true, "")).or_default().push(violation);
+        }
+        for owner in owners {
+            groups.entry((false, owner)).or_default().push(violation);
+        }
     }
+    groups.into_iter()
+        .map(|((unowned, owner), violations)| ((!unowned).then_some(owner), violations))
+        .collect()
 }
 
 #[cfg(test)]
@@ -167,6 +456,23 @@ mod tests {
     use super::*;
     use crate::models::{Rule, RuleType, CompiledRule};
 
+    #[cfg(not(feature = "ast-fixes"))]
+    #[test]
+    fn test_structural_rule_without_feature_warns_once() {
+        let rule = Rule::new(
+            "docs-on-pub-fn".to_string(),
+            RuleType::Forbidden,
+            "pub-fn-without-doc".to_string(),
+            "Document public functions".to_string(),
+        ).with_metadata(std::collections::HashMap::from([("engine".to_string(), "ast".to_string())]));
+        let rules = [CompiledRule::from_rule(rule)];
+
+        assert!(check_rules(Path::new("lib.rs"), "pub fn f() {}\n", &rules).unwrap().is_empty());
+        // The check above already warned for this rule
+        assert!(!warn_structural_disabled("docs-on-pub-fn"));
+        assert!(warn_structural_disabled("another-ast-rule"));
+    }
+
     #[test]
     fn test_check_forbidden_pattern_with_regex() {
         let rule = Rule::new(
@@ -335,6 +641,214 @@ mod tests {
         assert_eq!(required_count, 1);
     }
     
+    #[test]
+    fn test_structural_rule_reports_each_item() {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("engine".to_string(), "ast".to_string());
+        let rule = Rule::new(
+            "doc-pub-fns".to_string(),
+            RuleType::Forbidden,
+            "pub-fn-without-doc".to_string(),
+            "Public functions need doc comments".to_string(),
+        )
+        .with_metadata(metadata);
+        
+        let compiled_rule = CompiledRule::from_rule(rule);
+        let content = "/// Documented\npub fn a() {}\n\npub fn b() {}\npub fn c() {}\n";
+        
+        let violations = check_rules(Path::new("lib.rs"), content, std::slice::from_ref(&compiled_rule)).unwrap();
+        
+        if cfg!(feature = "ast-fixes") {
+            let lines: Vec<_> = violations.iter().map(|v| v.line_number).collect();
+            assert_eq!(lines, vec![Some(4), Some(5)]);
+        } else {
+            assert!(violations.is_empty());
+        }
+        
+        // Structural checks only apply to Rust sources
+        let violations = check_rules(Path::new("notes.md"), content, &[compiled_rule]).unwrap();
+        assert!(violations.is_empty());
+    }
+    
+    #[test]
+    fn test_required_pattern_per_function() {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("scope".to_string(), "function".to_string());
+        let rule = Rule::new(
+            "doc-every-fn".to_string(),
+            RuleType::Required,
+            "///".to_string(),
+            "Every function needs a doc comment".to_string(),
+        )
+        .with_metadata(metadata);
+        
+        let compiled_rule = CompiledRule::from_rule(rule);
+        let content = r#"/// Documented
+fn documented() {}
+
+fn undocumented() {}
+
+struct NotAFunction;
+
+fn also_undocumented() {}
+"#;
+        
+        let violations = check_rules(Path::new("lib.rs"), content, &[compiled_rule]).unwrap();
+        
+        let lines: Vec<_> = violations.iter().map(|v| v.line_number).collect();
+        assert_eq!(lines, vec![Some(4), Some(8)]);
+        assert_eq!(violations[0].line_content.as_deref(), Some("fn undocumented() {}"));
+    }
+    
+    #[test]
+    fn test_required_pattern_per_item_skips_function_bodies() {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("scope".to_string(), "item".to_string());
+        let rule = Rule::new(
+            "doc-every-item".to_string(),
+            RuleType::Required,
+            "///".to_string(),
+            "Every declaration needs a doc comment".to_string(),
+        )
+        .with_metadata(metadata);
+        
+        let compiled_rule = CompiledRule::from_rule(rule);
+        let content = r#"/// Documented
+fn documented() -> u32 {
+    const LIMIT: u32 = 3;
+    LIMIT
+}
+
+fn undocumented() {}
+"#;
+        
+        let violations = check_rules(Path::new("lib.rs"), content, &[compiled_rule]).unwrap();
+        
+        let lines: Vec<_> = violations.iter().map(|v| v.line_number).collect();
+        assert_eq!(lines, vec![Some(7)]);
+    }
+    
+    #[test]
+    fn test_max_occurrences_rule() {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("max".to_string(), "2".to_string());
+        metadata.insert("kind".to_string(), "literal".to_string());
+        let rule = Rule::new(
+            "few-unwraps".to_string(),
+            RuleType::Forbidden,
+            "unwrap()".to_string(),
+            "At most two unwrap() calls".to_string(),
+        )
+        .with_metadata(metadata);
+        
+        let compiled_rule = CompiledRule::from_rule(rule);
+        let content = "a.unwrap();\nb.unwrap(); c.unwrap();\nd.unwrap();\n";
+        
+        let violations = check_rules(Path::new("lib.rs"), content, std::slice::from_ref(&compiled_rule)).unwrap();
+        
+        let lines: Vec<_> = violations.iter().map(|v| v.line_number).collect();
+        assert_eq!(lines, vec![Some(2), Some(3)]);
+        
+        // `unwrap()` is exact text: `unwrap_or` and friends aren't counted
+        let content = "a.unwrap();\nb.unwrap_or(1); c.unwrap_or_else(f);\nd.unwrap();\ne.unwrap_or_default();\n";
+        let violations = check_rules(Path::new("lib.rs"), content, &[compiled_rule]).unwrap();
+        assert!(violations.is_empty());
+    }
+    
+    #[test]
+    fn test_min_occurrences_rule() {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("min".to_string(), "2".to_string());
+        let rule = Rule::new(
+            "two-assertions".to_string(),
+            RuleType::Required,
+            "assert!".to_string(),
+            "At least two assertions".to_string(),
+        )
+        .with_metadata(metadata);
+        
+        let compiled_rule = CompiledRule::from_rule(rule);
+        
+        let violations = check_rules(Path::new("lib.rs"), "assert!(a);\nfn a() {}\n", std::slice::from_ref(&compiled_rule)).unwrap();
+        assert_eq!(violations.len(), 1);
+        
+        let violations = check_rules(Path::new("lib.rs"), "assert!(a); assert!(b);\n", &[compiled_rule]).unwrap();
+        assert!(violations.is_empty());
+    }
+    
+    #[test]
+    fn test_prefilter_matches_unfiltered_checks() {
+        let mut min_two = std::collections::HashMap::new();
+        min_two.insert("min".to_string(), "2".to_string());
+        let mut rules = vec![
+            Rule::new("todo".to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string()),
+            Rule::new("unsafe".to_string(), RuleType::Forbidden, r"^\s*unsafe\b".to_string(), "No unsafe".to_string()),
+            Rule::new("license".to_string(), RuleType::Required, "SPDX-License".to_string(), "License header".to_string()),
+            Rule::new("asserts".to_string(), RuleType::Required, "assert!".to_string(), "Two assertions".to_string())
+                .with_metadata(min_two),
+        ];
+        rules.extend((0..20).map(|i| Rule::new(
+            format!("unused-{}", i),
+            if i % 2 == 0 { RuleType::Forbidden } else { RuleType::Required },
+            format!("marker_{}", i),
+            "Generated".to_string(),
+        )));
+        let compiled: Vec<CompiledRule> = rules.into_iter().map(CompiledRule::from_rule).collect();
+        let content = "// TODO: tidy\n  unsafe { run() }\nassert!(ok);\nmarker_3();\n";
+
+        let prefilter = RulePrefilter::new(&compiled);
+        let names = |violations: Vec<Violation>| violations.iter()
+            .map(|v| (v.rule.name.clone(), v.line_number))
+            .collect::<Vec<_>>();
+        let filtered = check_rules_with(Path::new("lib.rs"), content, &compiled, &prefilter, &ShadowCounter::default()).unwrap();
+        let unfiltered = check_rules_with(Path::new("lib.rs"), content, &compiled, &RulePrefilter::default(), &ShadowCounter::default()).unwrap();
+
+        assert_eq!(prefilter.filtered_rules(), compiled.len());
+        assert_eq!(names(filtered), names(unfiltered.clone()));
+        assert_eq!(names(check_rules(Path::new("lib.rs"), content, &compiled).unwrap()), names(unfiltered));
+    }
+
+    #[test]
+    fn test_group_by_owner() {
+        let violation = |name: &str, owners: &[&str]| {
+            let rule = Rule::new(name.to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string())
+                .with_owners(owners.iter().map(|owner| owner.to_string()).collect());
+            Violation::new(Path::new("lib.rs").to_path_buf(), std::sync::Arc::new(rule), Some(1), None)
+        };
+        let violations = vec![violation("a", &[]), violation("b", &["security", "platform"]), violation("c", &["platform"])];
+
+        let groups: Vec<(Option<&str>, Vec<&str>)> = group_by_owner(&violations)
+            .into_iter()
+            .map(|(owner, violations)| (owner, violations.iter().map(|v| v.rule.name.as_str()).collect()))
+            .collect();
+        assert_eq!(groups, vec![
+            (Some("platform"), vec!["b", "c"]),
+            (Some("security"), vec!["b"]),
+            (None, vec!["a"]),
+        ]);
+    }
+
+    #[test]
+    fn test_max_lines_and_line_length_rules() {
+        let max_lines = CompiledRule::new(
+            Rule::new("max-lines".to_string(), RuleType::Forbidden, "3".to_string(), "File too long".to_string()),
+            PatternMatcher::Threshold(Threshold::MaxLines(3)),
+        );
+        let max_line_length = CompiledRule::new(
+            Rule::new("max-line-length".to_string(), RuleType::Forbidden, "10".to_string(), "Line too long".to_string()),
+            PatternMatcher::Threshold(Threshold::MaxLineLength(10)),
+        );
+        let content = "short\nthis line is too long\nok\nfourth\n";
+        
+        let violations = check_rules(Path::new("lib.rs"), content, &[max_lines, max_line_length]).unwrap();
+        
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].rule.name, "max-lines");
+        assert_eq!(violations[0].line_number, Some(4));
+        assert_eq!(violations[1].rule.name, "max-line-length");
+        assert_eq!(violations[1].line_number, Some(2));
+    }
+    
     #[test]
     fn test_standard_rules_not_enforced() {
         let standard_rule = Rule::new(
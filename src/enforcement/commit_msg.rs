@@ -0,0 +1,111 @@
+//! Commit message rules
+//!
+//! Rules with `target: commit-message` (as a rule attribute, or in frontmatter
+//! for every rule in a file) check commit messages instead of file content.
+//! File checks skip them; `synapse hook commit-msg` runs them from git's
+//! `commit-msg` hook. They go through [`check_rules`] like any other rule, so
+//! FORBIDDEN, REQUIRED, thresholds and rollout settings work as usual.
+
+use super::check_rules;
+use crate::models::{CompiledRule, Violation};
+use std::borrow::Borrow;
+use std::path::Path;
+use std::sync::Arc;
+
+/// `target` value of commit message rules
+pub const COMMIT_MESSAGE_TARGET: &str = "commit-message";
+
+/// Line below which `git commit --verbose` puts the diff
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// Whether a rule targets commit messages rather than files
+pub fn is_commit_message_rule(compiled_rule: &CompiledRule) -> bool {
+    compiled_rule.rule.metadata.get("target").map(String::as_str) == Some(COMMIT_MESSAGE_TARGET)
+}
+
+/// The message git records: comment lines and everything below the scissors
+/// line are dropped, as are trailing blank lines
+pub fn clean_message(message: &str) -> String {
+    let lines: Vec<&str> = message
+        .lines()
+        .take_while(|line| *line != SCISSORS)
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(0, |i| i + 1);
+    lines[..end].join("\n")
+}
+
+/// Check a commit message against the commit message rules among `rules`
+///
+/// Other rules are ignored. `message_path` is only used in violations.
+pub fn check_commit_message<R: Borrow<CompiledRule>>(
+    message_path: &Path,
+    message: &str,
+    rules: &[R],
+) -> crate::Result<Vec<Violation>> {
+    let rules: Vec<CompiledRule> = rules
+        .iter()
+        .map(Borrow::borrow)
+        .filter(|rule| is_commit_message_rule(rule))
+        .map(as_content_rule)
+        .collect();
+    check_rules(message_path, &clean_message(message), &rules)
+}
+
+/// A copy of the rule that checks the content it is given
+///
+/// `files:` globs don't apply to commit messages, so they are dropped too.
+fn as_content_rule(compiled_rule: &CompiledRule) -> CompiledRule {
+    let mut content_rule = compiled_rule.clone();
+    let rule = Arc::make_mut(&mut content_rule.rule);
+    rule.metadata.remove("target");
+    rule.metadata.remove("files");
+    content_rule.files = None;
+    content_rule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::parser::RuleParser;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check_commit_message() {
+        let content = r#"---
+mcp: synapse
+target: commit-message
+---
+REQUIRED: `regex:^(feat|fix|docs|refactor|test|chore)(\(\S+\))?: ` - Use a conventional commit subject
+REQUIRED: `regex:#\d+` - Reference an issue
+FORBIDDEN: `regex:(?i)^wip\b` - Don't commit work in progress
+"#;
+        let rule_set = RuleParser::new().parse_content(content, PathBuf::from("commits.md")).unwrap();
+        let mut rules: Vec<CompiledRule> = rule_set.rules.into_iter().map(CompiledRule::from_rule).collect();
+        rules.push(CompiledRule::from_rule(crate::Rule::new(
+            "todo".to_string(), crate::RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string(),
+        )));
+        let path = Path::new(".git/COMMIT_EDITMSG");
+
+        let message = "fix(parser): handle empty frontmatter\n\nTODO later. Closes #12\n# Please enter the commit message\n";
+        assert!(check_commit_message(path, message, &rules).unwrap().is_empty());
+
+        let message = "WIP parser\n# ------------------------ >8 ------------------------\nfix(x): #1\n";
+        let names: Vec<String> = check_commit_message(path, message, &rules).unwrap()
+            .into_iter().map(|v| v.rule.message.clone()).collect();
+        assert_eq!(names, vec![
+            "Use a conventional commit subject",
+            "Reference an issue",
+            "Don't commit work in progress",
+        ]);
+
+        // File checks skip commit message rules
+        assert!(check_rules(Path::new("notes.txt"), "WIP", &rules).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clean_message() {
+        assert_eq!(clean_message("subject\n\nbody\n\n# comment\n\n"), "subject\n\nbody");
+        assert_eq!(clean_message("# only comments\n"), "");
+    }
+}
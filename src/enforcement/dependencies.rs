@@ -0,0 +1,565 @@
+//! Dependency rules for package manifests
+//!
+//! Rules tagged `[dependency]` are evaluated against the dependencies declared
+//! in `Cargo.toml`, `package.json`, `pyproject.toml` and `requirements*.txt`
+//! instead of the manifest's raw text, so a package name is never confused
+//! with a feature or script of the same name:
+//!
+//! * `FORBIDDEN[dependency]` is a deny list - dependencies matching the pattern are reported
+//! * `REQUIRED[dependency]` is an allow list - dependencies *not* matching the pattern are reported
+//!
+//! The pattern must match the whole package name. With `version: <req>` (a
+//! Cargo-style requirement such as `<0.12` or `>=1.0 <2`) a rule only concerns
+//! dependencies matching the pattern: FORBIDDEN reports those whose declared
+//! version falls in the range, REQUIRED those whose declared version doesn't.
+//! The declared version is the lowest version a requirement allows; path, git
+//! and workspace dependencies without a version are left alone.
+//!
+//! Python names are compared in normalized form (lowercase, `-` for `_` and `.`).
+//! Names spelled out in a pattern (`PyYAML`, `django_rest|six`) are normalized
+//! the same way; other regexes ignore case and should write separators as `-`.
+//! Manifests are read line by line, which covers the way package managers
+//! write them; each violation points at the line declaring the dependency.
+
+use crate::models::{CompiledRule, PatternMatcher, RuleType, Violation};
+use regex::{Regex, RegexBuilder};
+use semver::{Version, VersionReq};
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// A dependency declared in a manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    /// 1-based line number of the declaration
+    pub line: usize,
+    /// Package name (the `package =` name for renamed Cargo dependencies)
+    pub name: String,
+    /// Version requirement as written (`^1.2`, `>=2.0,<3`), if any
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Manifest {
+    Cargo,
+    PackageJson,
+    Pyproject,
+    Requirements,
+}
+
+impl Manifest {
+    fn of(file_path: &Path) -> Option<Self> {
+        let name = file_path.file_name()?.to_str()?;
+        match name {
+            "Cargo.toml" => Some(Manifest::Cargo),
+            "package.json" => Some(Manifest::PackageJson),
+            "pyproject.toml" => Some(Manifest::Pyproject),
+            _ if name.starts_with("requirements") && name.ends_with(".txt") => Some(Manifest::Requirements),
+            _ => None,
+        }
+    }
+
+    fn is_python(self) -> bool {
+        matches!(self, Manifest::Pyproject | Manifest::Requirements)
+    }
+}
+
+/// Whether a rule targets the dependencies declared in manifests
+pub fn is_dependency_rule(compiled_rule: &CompiledRule) -> bool {
+    compiled_rule.rule.metadata.get("target").map(String::as_str) == Some("dependency")
+}
+
+/// Parse a `version:` constraint
+///
+/// Comparators may be separated by spaces as well as commas (`>=1.0 <2`),
+/// since commas separate rule attributes.
+pub fn parse_version_req(value: &str) -> crate::Result<VersionReq> {
+    let mut comparators: Vec<String> = Vec::new();
+    for token in value.split([' ', ',']).filter(|token| !token.is_empty()) {
+        match comparators.last_mut() {
+            // `>= 1.0` is one comparator
+            Some(last) if last.chars().all(|c| "<>=^~".contains(c)) => last.push_str(token),
+            _ => comparators.push(token.to_string()),
+        }
+    }
+    VersionReq::parse(&comparators.join(", "))
+        .map_err(|e| crate::SynapseError::Parse(format!("Invalid version constraint '{}': {}", value, e)))
+}
+
+/// The lowest version a declared requirement allows (`^1.2` is 1.2.0)
+fn lowest_version(requirement: &str) -> Option<Version> {
+    static VERSION: OnceLock<Regex> = OnceLock::new();
+    let captures = VERSION
+        .get_or_init(|| Regex::new(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?").unwrap())
+        .captures(requirement)?;
+    let part = |index: usize| captures.get(index).map_or(Ok(0), |m| m.as_str().parse());
+    Some(Version::new(part(1).ok()?, part(2).ok()?, part(3).ok()?))
+}
+
+/// PEP 503 normalized name
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Strip quotes around a TOML key or string
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// First quoted string of a TOML value (`"1.0"`)
+fn quoted_string(value: &str) -> Option<&str> {
+    let value = value.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &value[1..];
+    rest.find(quote).map(|end| &rest[..end])
+}
+
+/// Value of `key = "..."` inside an inline table
+fn inline_field<'a>(table: &'a str, key: &str) -> Option<&'a str> {
+    table
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .filter_map(|field| field.split_once('='))
+        .find(|(name, _)| unquote(name) == key)
+        .and_then(|(_, value)| quoted_string(value))
+}
+
+/// Parse a PEP 508 requirement (`requests[socks]>=2.0; python_version > "3.8"`)
+fn parse_requirement(line: usize, requirement: &str) -> Option<Dependency> {
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    let requirement = requirement.split(';').next()?.trim();
+    let name = NAME
+        .get_or_init(|| Regex::new(r"^[A-Za-z0-9][A-Za-z0-9._-]*").unwrap())
+        .find(requirement)?;
+    let rest = requirement[name.end()..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map_or("", |(_, rest)| rest),
+        None => rest,
+    };
+    let version = rest.trim().trim_start_matches('(').trim_end_matches(')').trim();
+    Some(Dependency {
+        line,
+        name: name.as_str().to_string(),
+        version: (!version.is_empty() && !version.starts_with('@')).then(|| version.to_string()),
+    })
+}
+
+/// Whether a TOML table holds `name = requirement` dependency entries
+fn is_dependency_table(manifest: Manifest, table: &str) -> bool {
+    match manifest {
+        // `dependencies`, `dev-dependencies`, `workspace.dependencies`,
+        // `target.'cfg(unix)'.build-dependencies`, ...
+        Manifest::Cargo => table.rsplit('.').next().is_some_and(|last| last.ends_with("dependencies")),
+        Manifest::Pyproject => {
+            table.starts_with("tool.poetry.") && table.ends_with("dependencies")
+        }
+        _ => false,
+    }
+}
+
+/// Whether a pyproject table holds arrays of PEP 508 requirements
+fn is_requirement_table(table: &str) -> bool {
+    matches!(table, "project" | "project.optional-dependencies" | "dependency-groups")
+}
+
+/// Extract the dependencies declared in a TOML manifest
+fn toml_dependencies(manifest: Manifest, lines: &[&str]) -> Vec<Dependency> {
+    let mut dependencies: Vec<Dependency> = Vec::new();
+    let mut table = String::new();
+    // Index of the dependency declared by a `[dependencies.<name>]` header
+    let mut table_dependency: Option<usize> = None;
+    let mut in_requirement_array = false;
+
+    for (index, raw_line) in lines.iter().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if in_requirement_array {
+            for requirement in quoted_strings(line) {
+                dependencies.extend(parse_requirement(index + 1, requirement));
+            }
+            in_requirement_array = !closes_array(line);
+            continue;
+        }
+
+        if line.starts_with('[') {
+            table = line.trim_start_matches('[').trim_end_matches(']').trim().to_string();
+            table_dependency = None;
+            if line.starts_with("[[") || is_dependency_table(manifest, &table) {
+                continue;
+            }
+            // `[dependencies.serde]` declares one dependency in its own table
+            if let Some((parent, name)) = table.rsplit_once('.')
+                && is_dependency_table(manifest, parent)
+            {
+                table_dependency = Some(dependencies.len());
+                dependencies.push(Dependency { line: index + 1, name: unquote(name).to_string(), version: None });
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        if let Some(position) = table_dependency {
+            match unquote(key) {
+                "version" => dependencies[position].version = quoted_string(value).map(str::to_string),
+                "package" => {
+                    if let Some(package) = quoted_string(value) {
+                        dependencies[position].name = package.to_string();
+                    }
+                }
+                _ => {}
+            }
+        } else if is_dependency_table(manifest, &table) {
+            // `serde.workspace = true` and `serde.version = "1"` are dotted keys
+            let (name, field) = match key.split_once('.') {
+                Some((name, field)) => (unquote(name), Some(unquote(field))),
+                None => (unquote(key), None),
+            };
+            if manifest == Manifest::Pyproject && name == "python" {
+                continue;
+            }
+            let (name, version) = match field {
+                Some("version") => (name, quoted_string(value)),
+                Some(_) => (name, None),
+                None if value.starts_with('{') => {
+                    (inline_field(value, "package").unwrap_or(name), inline_field(value, "version"))
+                }
+                None => (name, quoted_string(value)),
+            };
+            // A dotted key can repeat a dependency declared on an earlier line
+            if let Some(existing) = dependencies.iter_mut().rev().find(|d| d.name == name && field.is_some()) {
+                existing.version = existing.version.take().or(version.map(str::to_string));
+                continue;
+            }
+            dependencies.push(Dependency { line: index + 1, name: name.to_string(), version: version.map(str::to_string) });
+        } else if manifest == Manifest::Pyproject && is_requirement_table(&table) && value.starts_with('[') {
+            // `dependencies = [...]`, and optional groups in their tables
+            if table == "project" && unquote(key) != "dependencies" {
+                continue;
+            }
+            for requirement in quoted_strings(value) {
+                dependencies.extend(parse_requirement(index + 1, requirement));
+            }
+            in_requirement_array = !closes_array(value);
+        }
+    }
+
+    dependencies
+}
+
+/// Whether a line closes an array, ignoring brackets in strings (`"requests[socks]"`)
+fn closes_array(line: &str) -> bool {
+    let mut quote = None;
+    for c in line.chars() {
+        match quote {
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ']' => return true,
+            Some(open) if c == open => quote = None,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Every quoted string on a line
+fn quoted_strings(line: &str) -> Vec<&str> {
+    let mut strings = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(quote) else {
+            break;
+        };
+        strings.push(&after[..end]);
+        rest = &after[end + 1..];
+    }
+    strings
+}
+
+/// Extract the dependencies of a `package.json`
+fn package_json_dependencies(lines: &[&str]) -> Vec<Dependency> {
+    static SECTION: OnceLock<Regex> = OnceLock::new();
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    let section = SECTION.get_or_init(|| {
+        Regex::new(r#""(?:dependencies|devDependencies|peerDependencies|optionalDependencies)"\s*:\s*\{"#).unwrap()
+    });
+    let entry = ENTRY.get_or_init(|| Regex::new(r#""([^"]+)"\s*:\s*"([^"]*)""#).unwrap());
+
+    let mut dependencies = Vec::new();
+    let mut in_section = false;
+    for (index, line) in lines.iter().enumerate() {
+        let mut rest = *line;
+        if !in_section {
+            let Some(start) = section.find(line) else {
+                continue;
+            };
+            in_section = true;
+            rest = &line[start.end()..];
+        }
+        let body = match rest.find('}') {
+            Some(end) => {
+                in_section = false;
+                &rest[..end]
+            }
+            None => rest,
+        };
+        for captures in entry.captures_iter(body) {
+            dependencies.push(Dependency {
+                line: index + 1,
+                name: captures[1].to_string(),
+                version: Some(captures[2].to_string()),
+            });
+        }
+    }
+    dependencies
+}
+
+/// Extract the requirements of a `requirements.txt`
+///
+/// Options such as `-r other.txt` and `-e .` are skipped.
+fn requirements_txt_dependencies(lines: &[&str]) -> Vec<Dependency> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.split(" #").next()?.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+                return None;
+            }
+            parse_requirement(index + 1, line)
+        })
+        .collect()
+}
+
+/// Extract the dependencies declared in a manifest; other files have none
+pub fn extract_dependencies(file_path: &Path, lines: &[&str]) -> Vec<Dependency> {
+    match Manifest::of(file_path) {
+        Some(manifest @ (Manifest::Cargo | Manifest::Pyproject)) => toml_dependencies(manifest, lines),
+        Some(Manifest::PackageJson) => package_json_dependencies(lines),
+        Some(Manifest::Requirements) => requirements_txt_dependencies(lines),
+        None => Vec::new(),
+    }
+}
+
+/// A rule's regex as it applies to normalized Python names
+///
+/// Alternatives that are plain names are normalized like the names they
+/// match; the rest of the regex is kept and matched case-insensitively.
+fn python_regex(compiled_rule: &CompiledRule) -> Option<Regex> {
+    let PatternMatcher::Regex(regex) = &compiled_rule.matcher else {
+        return None;
+    };
+    // Dependency patterns are anchored as `^(?:...)$` at compile time
+    let source = regex.as_str().strip_prefix("^(?:")?.strip_suffix(")$")?;
+    let names: Option<Vec<String>> = source
+        .split('|')
+        .map(|alternative| {
+            let name = alternative.replace(r"\.", ".").replace(r"\-", "-");
+            name.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+                .then(|| regex::escape(&normalize_python_name(&name)))
+        })
+        .collect();
+    let source = names.map_or_else(|| source.to_string(), |names| names.join("|"));
+    RegexBuilder::new(&format!("^(?:{})$", source)).case_insensitive(true).build().ok()
+}
+
+/// Whether a dependency's name matches a rule pattern
+///
+/// `python_regex` is the rule's [`python_regex`] when checking a Python manifest.
+fn dependency_matches(name: &str, python: bool, python_regex: Option<&Regex>, compiled_rule: &CompiledRule) -> bool {
+    let name = if python { normalize_python_name(name) } else { name.to_string() };
+    // Patterns are anchored at compile time, so regexes match whole names
+    match &compiled_rule.matcher {
+        PatternMatcher::Regex(regex) => python_regex.unwrap_or(regex).is_match(&name),
+        PatternMatcher::Literal(literal) if python => name == normalize_python_name(literal),
+        PatternMatcher::Literal(literal) => name == *literal,
+        PatternMatcher::Structural(_) | PatternMatcher::Threshold(_) | PatternMatcher::Glob(_) | PatternMatcher::Header(_) => false,
+    }
+}
+
+/// Check a `[dependency]` rule against the dependencies of one manifest
+pub fn check_dependency_rule(file_path: &Path, lines: &[&str], compiled_rule: &CompiledRule) -> Vec<Violation> {
+    let rule = &compiled_rule.rule;
+    let Some(manifest) = Manifest::of(file_path) else {
+        return Vec::new();
+    };
+    if !matches!(rule.rule_type, RuleType::Forbidden | RuleType::Required) {
+        return Vec::new();
+    }
+
+    let constraint = match rule.metadata.get("version").map(|req| parse_version_req(req)) {
+        Some(Ok(constraint)) => Some(constraint),
+        Some(Err(e)) => {
+            warn!("Rule '{}' has an invalid version constraint, skipping it: {}", rule.name, e);
+            return Vec::new();
+        }
+        None => None,
+    };
+    let python_regex = manifest.is_python().then(|| python_regex(compiled_rule)).flatten();
+
+    extract_dependencies(file_path, lines)
+        .into_iter()
+        .filter(|dependency| {
+            let matched = dependency_matches(&dependency.name, manifest.is_python(), python_regex.as_ref(), compiled_rule);
+            match &constraint {
+                // Deny lists report matches, allow lists report everything else
+                None if rule.rule_type == RuleType::Forbidden => matched,
+                None => !matched,
+                Some(constraint) => {
+                    let Some(version) = dependency.version.as_deref().and_then(lowest_version) else {
+                        return false;
+                    };
+                    matched && constraint.matches(&version) == (rule.rule_type == RuleType::Forbidden)
+                }
+            }
+        })
+        .map(|dependency| Violation::from_compiled_rule(
+            file_path.to_path_buf(),
+            compiled_rule,
+            Some(dependency.line),
+            Some(lines[dependency.line - 1].to_string()),
+        ))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Rule;
+    use std::collections::HashMap;
+
+    fn rule(rule_type: RuleType, pattern: &str, version: Option<&str>) -> CompiledRule {
+        let mut metadata = HashMap::new();
+        metadata.insert("target".to_string(), "dependency".to_string());
+        if let Some(version) = version {
+            metadata.insert("version".to_string(), version.to_string());
+        }
+        CompiledRule::from_rule(Rule {
+            id: "dep".to_string(),
+            name: "dep".to_string(),
+            rule_type,
+            pattern: pattern.to_string(),
+            message: "message".to_string(),
+            tags: vec![],
+            metadata,
+            owners: vec![],
+        })
+    }
+
+    fn names(file: &str, content: &str) -> Vec<(usize, String, Option<String>)> {
+        let lines: Vec<&str> = content.lines().collect();
+        extract_dependencies(Path::new(file), &lines)
+            .into_iter()
+            .map(|d| (d.line, d.name, d.version))
+            .collect()
+    }
+
+    fn reported(file: &str, content: &str, compiled_rule: &CompiledRule) -> Vec<usize> {
+        let lines: Vec<&str> = content.lines().collect();
+        check_dependency_rule(Path::new(file), &lines, compiled_rule)
+            .into_iter()
+            .filter_map(|v| v.line_number)
+            .collect()
+    }
+
+    const CARGO: &str = r#"[package]
+name = "app"
+
+[features]
+openssl = ["dep:ssl"]
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+ssl = { package = "openssl", version = "0.10" }
+reqwest = "0.11.4"
+shared.workspace = true
+
+[target.'cfg(unix)'.dev-dependencies]
+nix = "0.27"
+
+[build-dependencies.cc]
+version = "1.0"
+"#;
+
+    #[test]
+    fn test_extract_cargo_dependencies() {
+        let s = |v: &str| Some(v.to_string());
+        assert_eq!(names("Cargo.toml", CARGO), vec![
+            (8, "serde".to_string(), s("1.0")),
+            (9, "openssl".to_string(), s("0.10")),
+            (10, "reqwest".to_string(), s("0.11.4")),
+            (11, "shared".to_string(), None),
+            (14, "nix".to_string(), s("0.27")),
+            (16, "cc".to_string(), s("1.0")),
+        ]);
+    }
+
+    #[test]
+    fn test_extract_other_manifests() {
+        let package_json = "{\n  \"scripts\": { \"lodash\": \"x\" },\n  \"dependencies\": {\n    \"lodash\": \"^4.17.0\",\n    \"@types/node\": \"20.1.0\"\n  },\n  \"devDependencies\": { \"jest\": \"29\" }\n}\n";
+        let found: Vec<(usize, String)> = names("package.json", package_json).into_iter().map(|(l, n, _)| (l, n)).collect();
+        assert_eq!(found, vec![(4, "lodash".to_string()), (5, "@types/node".to_string()), (7, "jest".to_string())]);
+
+        let pyproject = "[project]\nname = \"app\"\ndependencies = [\n  \"requests[socks]>=2.31\",\n  \"PyYAML\",\n]\n\n[project.optional-dependencies]\ndev = [\"pytest>=7\"]\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\nDjango = \"^4.2\"\n";
+        assert_eq!(names("pyproject.toml", pyproject), vec![
+            (4, "requests".to_string(), Some(">=2.31".to_string())),
+            (5, "PyYAML".to_string(), None),
+            (9, "pytest".to_string(), Some(">=7".to_string())),
+            (13, "Django".to_string(), Some("^4.2".to_string())),
+        ]);
+
+        let requirements = "# pinned\n-r base.txt\nflask==2.3.2  # web\nsix\n";
+        let found: Vec<(usize, String)> = names("requirements-dev.txt", requirements).into_iter().map(|(l, n, _)| (l, n)).collect();
+        assert_eq!(found, vec![(3, "flask".to_string()), (4, "six".to_string())]);
+        assert!(names("README.md", "serde = \"1.0\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_check_dependency_rules() {
+        // The `openssl` feature isn't a dependency; the renamed `ssl` one is
+        assert_eq!(reported("Cargo.toml", CARGO, &rule(RuleType::Forbidden, "openssl", None)), vec![9]);
+        assert!(reported("Cargo.toml", CARGO, &rule(RuleType::Forbidden, "open", None)).is_empty());
+        assert_eq!(reported("Cargo.toml", CARGO, &rule(RuleType::Required, "serde|openssl|reqwest|shared|nix|cc", None)), Vec::<usize>::new());
+        assert_eq!(reported("Cargo.toml", CARGO, &rule(RuleType::Required, "serde|shared", None)), vec![9, 10, 14, 16]);
+
+        // Version constraints
+        assert_eq!(reported("Cargo.toml", CARGO, &rule(RuleType::Forbidden, "reqwest", Some("<0.12"))), vec![10]);
+        assert!(reported("Cargo.toml", CARGO, &rule(RuleType::Forbidden, "reqwest", Some("<0.11"))).is_empty());
+        assert_eq!(reported("Cargo.toml", CARGO, &rule(RuleType::Required, "serde|nix", Some(">= 1.0 <2"))), vec![14]);
+        assert!(reported("Cargo.toml", CARGO, &rule(RuleType::Required, "shared", Some(">=2"))).is_empty());
+
+        // Python names are normalized
+        assert_eq!(reported("requirements.txt", "PyYAML==6.0\n", &rule(RuleType::Forbidden, "pyyaml", None)), vec![1]);
+        assert!(parse_version_req("not a version").is_err());
+    }
+
+    #[test]
+    fn test_python_patterns_are_normalized() {
+        let requirements = "pyyaml==6.0\nDjango-REST==3.14\nsix\n";
+        assert_eq!(reported("requirements.txt", requirements, &rule(RuleType::Forbidden, "PyYAML", None)), vec![1]);
+        assert_eq!(reported("requirements.txt", requirements, &rule(RuleType::Forbidden, "django_rest", None)), vec![2]);
+        assert_eq!(reported("requirements.txt", requirements, &rule(RuleType::Required, "PyYAML|django.rest|Six", None)), Vec::<usize>::new());
+        assert_eq!(reported("requirements.txt", requirements, &rule(RuleType::Forbidden, "django-.*", None)), vec![2]);
+
+        let pyproject = "[project]\ndependencies = [\"Django_Rest>=3\"]\n";
+        assert_eq!(reported("pyproject.toml", pyproject, &rule(RuleType::Forbidden, "django_rest", Some("<4"))), vec![2]);
+        // Cargo names are compared as written
+        assert!(reported("Cargo.toml", "[dependencies]\nserde-json = \"1\"\n", &rule(RuleType::Forbidden, "serde_json", None)).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_version_constraint_skips_the_rule() {
+        assert!(reported("Cargo.toml", CARGO, &rule(RuleType::Forbidden, "reqwest", Some("not a version"))).is_empty());
+    }
+}
@@ -0,0 +1,511 @@
+//! License header rules
+//!
+//! `REQUIRED[header]` rules check that a file starts with a header built from
+//! a template instead of accepting the pattern anywhere in the file. The
+//! template's lines are separated by `\n` and may contain placeholders:
+//!
+//! * `{year}` - a year or range (`2024`, `2019-2026`)
+//! * `{spdx}` - an SPDX license expression (`Apache-2.0`, `MIT OR Apache-2.0`)
+//! * `{<name>}` - any other text, such as `{company}`
+//!
+//! `{<name>=<value>}` pins a placeholder to a value. The header must appear,
+//! in any comment syntax, within the first `lines: N` lines (default
+//! [`DEFAULT_HEADER_LINES`]).
+//!
+//! [`fix_header`] inserts a missing header, or replaces the lines of an
+//! outdated copyright or SPDX header, using the file's comment syntax and line
+//! endings. Comments that follow the old header are kept. Placeholder values come
+//! from the template, then from the header being replaced; `{year}` becomes
+//! the current year, or a range from the year of the replaced header.
+
+use crate::models::{CompiledRule, PatternMatcher, Rule, Violation};
+use chrono::Datelike;
+use regex::Regex;
+use std::borrow::Borrow;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Lines searched for the header when a rule sets no `lines:`
+pub const DEFAULT_HEADER_LINES: usize = 10;
+
+const YEAR_SOURCE: &str = r"\d{4}(?:\s*[-–]\s*(?:\d{4}|present))?";
+const SPDX_SOURCE: &str = r"[A-Za-z0-9.+-]+(?:\s+(?:AND|OR|WITH)\s+[A-Za-z0-9.+-]+)*";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Placeholder { name: String, value: Option<String> },
+}
+
+/// A parsed header template
+#[derive(Debug, Clone)]
+pub struct HeaderTemplate {
+    lines: Vec<Vec<Segment>>,
+    /// One anchored regex per template line; placeholder `i` is group `p<i>`
+    regexes: Vec<Regex>,
+    scan_lines: usize,
+}
+
+impl HeaderTemplate {
+    /// Parse a template such as `Copyright {year} {company}\nSPDX-License-Identifier: {spdx}`
+    pub fn parse(template: &str) -> crate::Result<Self> {
+        let mut lines = Vec::new();
+        for line in template.split("\\n") {
+            lines.push(parse_segments(line.trim())?);
+        }
+        let regexes = lines
+            .iter()
+            .map(|segments| line_regex(segments))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| crate::SynapseError::Parse(format!("Invalid header template '{}': {}", template, e)))?;
+        Ok(Self { lines, regexes, scan_lines: DEFAULT_HEADER_LINES })
+    }
+
+    /// The template of a `[header]` rule, with its `lines:` setting
+    pub fn from_rule(rule: &Rule) -> Option<Self> {
+        if !rule.is_header() {
+            return None;
+        }
+        let mut template = Self::parse(&rule.pattern).ok()?;
+        if let Some(lines) = rule.metadata.get("lines").and_then(|lines| lines.parse().ok()) {
+            template.scan_lines = lines;
+        }
+        Some(template)
+    }
+
+    /// Whether the header appears within the first lines of a file
+    pub fn matches(&self, lines: &[&str]) -> bool {
+        let texts: Vec<&str> = lines
+            .iter()
+            .take(self.scan_lines)
+            .map(|line| comment_text(line))
+            .filter(|text| !text.is_empty())
+            .collect();
+        texts.windows(self.regexes.len()).any(|window| {
+            window.iter().zip(&self.regexes).all(|(text, regex)| regex.is_match(text))
+        })
+    }
+
+    /// Header lines with placeholders filled in, taking values missing from
+    /// the template from `existing` header lines
+    fn render(&self, existing: &[&str], year: i32) -> Option<Vec<String>> {
+        let existing: Vec<&str> = existing.iter().map(|line| comment_text(line)).collect();
+        let captured = |name: &str| -> Option<String> {
+            self.lines.iter().zip(&self.regexes).find_map(|(segments, regex)| {
+                let index = segments.iter().position(|segment| {
+                    matches!(segment, Segment::Placeholder { name: n, .. } if n == name)
+                })?;
+                existing.iter().find_map(|text| {
+                    regex.captures(text)?.name(&format!("p{}", index)).map(|m| m.as_str().to_string())
+                })
+            })
+        };
+
+        let mut rendered = Vec::new();
+        for segments in &self.lines {
+            let mut line = String::new();
+            for segment in segments {
+                match segment {
+                    Segment::Text(text) => line.push_str(text),
+                    Segment::Placeholder { value: Some(value), .. } => line.push_str(value),
+                    Segment::Placeholder { name, value: None } if name == "year" => {
+                        line.push_str(&year_range(captured("year").as_deref(), &existing, year));
+                    }
+                    Segment::Placeholder { name, value: None } => line.push_str(&captured(name)?),
+                }
+            }
+            rendered.push(line);
+        }
+        Some(rendered)
+    }
+}
+
+fn parse_segments(line: &str) -> crate::Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            return Err(crate::SynapseError::Parse(format!("Unclosed placeholder in header template line: {}", line)));
+        };
+        if start > 0 {
+            segments.push(Segment::Text(rest[..start].to_string()));
+        }
+        let (name, value) = match rest[start + 1..end].split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim().to_string())),
+            None => (rest[start + 1..end].trim(), None),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(crate::SynapseError::Parse(format!("Invalid placeholder '{{{}}}' in header template", name)));
+        }
+        segments.push(Segment::Placeholder { name: name.to_string(), value });
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+fn line_regex(segments: &[Segment]) -> Result<Regex, regex::Error> {
+    let mut source = String::from("^");
+    for (index, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Text(text) => source.push_str(&regex::escape(text)),
+            Segment::Placeholder { name, value } => {
+                let pattern = match (name.as_str(), value) {
+                    (_, Some(value)) => regex::escape(value),
+                    ("year", None) => YEAR_SOURCE.to_string(),
+                    ("spdx", None) => SPDX_SOURCE.to_string(),
+                    _ => ".+?".to_string(),
+                };
+                source.push_str(&format!("(?P<p{}>{})", index, pattern));
+            }
+        }
+    }
+    source.push('$');
+    Regex::new(&source)
+}
+
+/// `{year}` for a rewritten header: the current year, or a range starting at
+/// the year of the header being replaced
+fn year_range(previous: Option<&str>, existing: &[&str], year: i32) -> String {
+    let start = previous
+        .into_iter()
+        .chain(existing.iter().copied())
+        .find_map(|text| {
+            Regex::new(r"\b(19|20)\d{2}\b").ok()?.find(text)?.as_str().parse::<i32>().ok()
+        });
+    match start {
+        Some(start) if start < year => format!("{}-{}", start, year),
+        _ => year.to_string(),
+    }
+}
+
+/// A line's text without comment markers (`//`, `#`, `/*`, `*`, `<!--`, ...)
+fn comment_text(line: &str) -> &str {
+    let mut text = line.trim();
+    for prefix in ["<!--", "/*", "//!", "///", "//", "--", ";;", "#", "*"] {
+        if let Some(rest) = text.strip_prefix(prefix) {
+            text = rest;
+            break;
+        }
+    }
+    for suffix in ["-->", "*/"] {
+        if let Some(rest) = text.strip_suffix(suffix) {
+            text = rest;
+        }
+    }
+    text.trim()
+}
+
+/// Comment syntax used to write a header
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CommentStyle {
+    Line(&'static str),
+    Block { open: &'static str, prefix: &'static str, close: &'static str },
+}
+
+impl CommentStyle {
+    fn for_path(file_path: &Path) -> Option<Self> {
+        let name = file_path.file_name()?.to_str()?;
+        if matches!(name, "Dockerfile" | "Makefile" | "CMakeLists.txt") {
+            return Some(CommentStyle::Line("#"));
+        }
+        let extension = file_path.extension()?.to_str()?;
+        Some(match extension {
+            "rs" | "go" | "c" | "h" | "cc" | "cpp" | "hpp" | "java" | "kt" | "kts" | "swift" | "scala"
+            | "cs" | "dart" | "proto" | "zig" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "php" => {
+                CommentStyle::Line("//")
+            }
+            "py" | "rb" | "sh" | "bash" | "zsh" | "pl" | "r" | "toml" | "yaml" | "yml" | "tf" | "ex" | "exs"
+            | "cmake" => CommentStyle::Line("#"),
+            "sql" | "lua" | "hs" => CommentStyle::Line("--"),
+            "css" | "scss" | "less" => CommentStyle::Block { open: "/*", prefix: " * ", close: " */" },
+            "html" | "htm" | "xml" | "svg" | "vue" | "md" => CommentStyle::Block { open: "<!--", prefix: "  ", close: "-->" },
+            _ => return None,
+        })
+    }
+
+    fn wrap(&self, header: &[String]) -> Vec<String> {
+        match self {
+            CommentStyle::Line(marker) => header
+                .iter()
+                .map(|line| if line.is_empty() { marker.to_string() } else { format!("{} {}", marker, line) })
+                .collect(),
+            CommentStyle::Block { open, prefix, close } => {
+                let mut lines = vec![open.to_string()];
+                lines.extend(header.iter().map(|line| format!("{}{}", prefix, line).trim_end().to_string()));
+                lines.push(close.to_string());
+                lines
+            }
+        }
+    }
+
+    /// Number of lines of the comment block at the start of `lines`
+    ///
+    /// Line comment blocks end at doc comments (`//!`, `///`), which document
+    /// the code below rather than belong to a header.
+    fn leading_comment(&self, lines: &[&str]) -> usize {
+        match self {
+            CommentStyle::Line(marker) => lines
+                .iter()
+                .map(|line| line.trim_start())
+                .take_while(|line| line.starts_with(marker) && !line.starts_with("//!") && !line.starts_with("///"))
+                .count(),
+            CommentStyle::Block { open, close, .. } => {
+                if !lines.first().is_some_and(|line| line.trim_start().starts_with(open)) {
+                    return 0;
+                }
+                let close = close.trim();
+                lines.iter().position(|line| line.contains(close)).map_or(0, |end| end + 1)
+            }
+        }
+    }
+}
+
+/// Whether a comment line belongs to a copyright or license header
+fn is_header_line(template: &HeaderTemplate, line: &str) -> bool {
+    let text = comment_text(line);
+    let lowercase = text.to_lowercase();
+    ["copyright", "spdx-license-identifier", "all rights reserved", "(c)"]
+        .iter()
+        .any(|keyword| lowercase.contains(keyword))
+        || template.regexes.iter().any(|regex| regex.is_match(text))
+}
+
+/// Number of lines of the old header at the start of `lines`, if any
+///
+/// Only the comment lines up to the last header line are replaced, so
+/// comments that follow the header survive. A block comment that goes on
+/// after its header lines is left alone.
+fn old_header_len(template: &HeaderTemplate, style: CommentStyle, lines: &[&str]) -> usize {
+    let block = &lines[..style.leading_comment(lines)];
+    let Some(last) = block.iter().rposition(|line| is_header_line(template, line)) else {
+        return 0;
+    };
+    match style {
+        CommentStyle::Line(_) => last + 1,
+        CommentStyle::Block { .. } => {
+            let trailing = block.get(last + 1..block.len() - 1).unwrap_or_default();
+            if trailing.iter().all(|line| comment_text(line).is_empty()) {
+                block.len()
+            } else {
+                0
+            }
+        }
+    }
+}
+
+/// Lines that must stay above a header: shebangs, XML declarations, `<?php`
+/// and Python encoding declarations
+fn preamble_len(lines: &[&str]) -> usize {
+    lines
+        .iter()
+        .take_while(|line| {
+            let line = line.trim_start();
+            line.starts_with("#!") || line.starts_with("<?") || line.starts_with("# -*-") || line.starts_with("# coding")
+        })
+        .count()
+}
+
+/// Whether a rule checks a file header
+pub fn is_header_rule(compiled_rule: &CompiledRule) -> bool {
+    compiled_rule.rule.is_header()
+}
+
+/// The template a `[header]` rule was compiled with
+pub fn template_of(compiled_rule: &CompiledRule) -> Option<&HeaderTemplate> {
+    match &compiled_rule.matcher {
+        PatternMatcher::Header(template) => Some(template),
+        _ => None,
+    }
+}
+
+/// The compiled template of the rule a violation was reported for
+pub fn template_for<'a, R: Borrow<CompiledRule>>(rules: &'a [R], rule: &Arc<Rule>) -> Option<&'a HeaderTemplate> {
+    rules.iter()
+        .map(Borrow::borrow)
+        .find(|compiled_rule| Arc::ptr_eq(&compiled_rule.rule, rule))
+        .and_then(template_of)
+}
+
+/// Check a `[header]` rule against the first lines of a file
+pub fn check_header_rule(file_path: &Path, lines: &[&str], compiled_rule: &CompiledRule) -> Option<Violation> {
+    let template = template_of(compiled_rule)?;
+    if template.matches(lines) {
+        return None;
+    }
+    Some(Violation::from_compiled_rule(file_path.to_path_buf(), compiled_rule, None, None))
+}
+
+/// Insert or update the header a `[header]` rule requires
+///
+/// Returns `None` when the header is already present, the file type has no
+/// known comment syntax, or a placeholder has no value to fill in.
+pub fn fix_header(file_path: &Path, content: &str, template: &HeaderTemplate) -> Option<String> {
+    let style = CommentStyle::for_path(file_path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    if template.matches(&lines) {
+        return None;
+    }
+
+    let start = preamble_len(&lines);
+    let replaced = old_header_len(template, style, &lines[start..]);
+
+    let header = template.render(&lines[start..start + replaced], chrono::Local::now().year())?;
+    let mut fixed: Vec<String> = lines[..start].iter().map(|line| line.to_string()).collect();
+    fixed.extend(style.wrap(&header));
+    let rest = &lines[start + replaced..];
+    if rest.first().is_some_and(|line| !line.trim().is_empty()) {
+        fixed.push(String::new());
+    }
+    fixed.extend(rest.iter().map(|line| line.to_string()));
+
+    // Keep the file's line endings and final newline
+    let ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut fixed = fixed.join(ending);
+    if content.is_empty() || content.ends_with('\n') {
+        fixed.push_str(ending);
+    }
+    Some(fixed)
+}
+
+/// Apply the header fixes for the `[header]` rule violations of one file
+///
+/// `rules` are the compiled rules the violations were reported for.
+pub fn fix_headers<R: Borrow<CompiledRule>>(file_path: &Path, content: &str, violations: &[Violation], rules: &[R]) -> Option<String> {
+    let mut fixed: Option<String> = None;
+    for violation in violations.iter().filter(|v| v.rule.is_header()) {
+        let Some(template) = template_for(rules, &violation.rule) else {
+            continue;
+        };
+        let current = fixed.as_deref().unwrap_or(content);
+        if let Some(updated) = fix_header(file_path, current, template) {
+            fixed = Some(updated);
+        }
+    }
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_rules;
+    use crate::rules::parser::RuleParser;
+    use std::path::PathBuf;
+
+    fn header_rule(attributes: &str, template: &str) -> CompiledRule {
+        let content = format!(
+            "---\nmcp: synapse\n---\nREQUIRED[{}]: `{}` - Add the license header\n",
+            attributes, template
+        );
+        let rule_set = RuleParser::new().parse_content(&content, PathBuf::from("headers.md")).unwrap();
+        CompiledRule::from_rule(rule_set.rules.into_iter().next().unwrap())
+    }
+
+    fn header_template(attributes: &str, template: &str) -> HeaderTemplate {
+        template_of(&header_rule(attributes, template)).unwrap().clone()
+    }
+
+    const TEMPLATE: &str = r"Copyright (c) {year} {company=Acme, Inc.}\nSPDX-License-Identifier: {spdx=Apache-2.0}";
+
+    #[test]
+    fn test_check_header_rule() {
+        let rules = [header_rule("header", TEMPLATE)];
+        let check = |file: &str, content: &str| check_rules(Path::new(file), content, &rules).unwrap().len();
+
+        assert_eq!(check("lib.rs", "// Copyright (c) 2019-2026 Acme, Inc.\n// SPDX-License-Identifier: Apache-2.0\n\nfn main() {}\n"), 0);
+        assert_eq!(check("run.py", "#!/usr/bin/env python\n# Copyright (c) 2024 Acme, Inc.\n# SPDX-License-Identifier: Apache-2.0\n"), 0);
+        assert_eq!(check("site.css", "/*\n * Copyright (c) 2024 Acme, Inc.\n * SPDX-License-Identifier: Apache-2.0\n */\n"), 0);
+        // Anywhere but the top of the file doesn't count
+        let late = format!("{}// Copyright (c) 2024 Acme, Inc.\n// SPDX-License-Identifier: Apache-2.0\n", "fn f() {}\n".repeat(12));
+        assert_eq!(check("lib.rs", &late), 1);
+        assert_eq!(check("lib.rs", "// Copyright (c) 2024 Acme, Inc.\n// SPDX-License-Identifier: MIT\n"), 1);
+
+        let rules = [header_rule("header, lines: 2", r"SPDX-License-Identifier: {spdx}")];
+        assert!(check_rules(Path::new("a.rs"), "// SPDX-License-Identifier: MIT OR Apache-2.0\n", &rules).unwrap().is_empty());
+        assert_eq!(check_rules(Path::new("a.rs"), "\n\n\n// SPDX-License-Identifier: MIT\n", &rules).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fix_header() {
+        let template = header_template("header", TEMPLATE);
+        let year = chrono::Local::now().year();
+
+        let fixed = fix_header(Path::new("main.rs"), "fn main() {}\n", &template).unwrap();
+        assert_eq!(fixed, format!("// Copyright (c) {} Acme, Inc.\n// SPDX-License-Identifier: Apache-2.0\n\nfn main() {{}}\n", year));
+
+        // Outdated headers are replaced, keeping the first year
+        let fixed = fix_header(Path::new("tool.py"), "#!/usr/bin/env python\n# Copyright 2015 Acme\n# SPDX-License-Identifier: MIT\n\nimport os\n", &template).unwrap();
+        assert_eq!(fixed, format!("#!/usr/bin/env python\n# Copyright (c) 2015-{} Acme, Inc.\n# SPDX-License-Identifier: Apache-2.0\n\nimport os\n", year));
+
+        let fixed = fix_header(Path::new("page.html"), "<p>hi</p>\n", &template).unwrap();
+        assert!(fixed.starts_with("<!--\n  Copyright (c) "));
+        assert!(fix_header(Path::new("main.rs"), &fixed_header(year), &template).is_none());
+        assert!(fix_header(Path::new("data.bin"), "", &template).is_none());
+
+        // Placeholders without a value are taken from the replaced header
+        let template = header_template("header", r"Copyright {year} {company}\nSPDX-License-Identifier: {spdx=MIT}");
+        assert!(fix_header(Path::new("a.rs"), "fn f() {}\n", &template).is_none());
+        let fixed = fix_header(Path::new("a.rs"), "// Copyright 2020 Initech\n// SPDX-License-Identifier: GPL-2.0\nfn f() {}\n", &template).unwrap();
+        assert_eq!(fixed, format!("// Copyright 2020-{} Initech\n// SPDX-License-Identifier: MIT\n\nfn f() {{}}\n", year));
+    }
+
+    #[test]
+    fn test_fix_header_keeps_following_comments() {
+        let template = header_template("header", TEMPLATE);
+        let year = chrono::Local::now().year();
+        let header = |marker: &str| format!(
+            "{m} Copyright (c) 2015-{y} Acme, Inc.\n{m} SPDX-License-Identifier: Apache-2.0\n", m = marker, y = year
+        );
+
+        // Rust crate docs right after the header
+        let content = "// Copyright 2015 Acme\n//! Crate documentation\n//! More docs\n\npub fn f() {}\n";
+        let fixed = fix_header(Path::new("lib.rs"), content, &template).unwrap();
+        assert_eq!(fixed, format!("{}\n//! Crate documentation\n//! More docs\n\npub fn f() {{}}\n", header("//")));
+
+        // A plain comment directly below a Python header
+        let content = "# Copyright 2015 Acme\n# SPDX-License-Identifier: MIT\n# Helper that deploys the app\nimport os\n";
+        let fixed = fix_header(Path::new("deploy.py"), content, &template).unwrap();
+        assert_eq!(fixed, format!("{}\n# Helper that deploys the app\nimport os\n", header("#")));
+
+        // Block comments that document more than the license are kept whole
+        let content = "/*\n * Copyright 2015 Acme\n * Theme colors\n */\nbody {}\n";
+        let fixed = fix_header(Path::new("site.css"), content, &template).unwrap();
+        assert!(fixed.ends_with("\n\n/*\n * Copyright 2015 Acme\n * Theme colors\n */\nbody {}\n"));
+    }
+
+    #[test]
+    fn test_fix_header_keeps_line_endings() {
+        let template = header_template("header", TEMPLATE);
+        let fixed = fix_header(Path::new("main.rs"), "// Copyright 2015 Acme\r\nfn main() {}\r\n", &template).unwrap();
+        assert!(fixed.ends_with(" Acme, Inc.\r\n// SPDX-License-Identifier: Apache-2.0\r\n\r\nfn main() {}\r\n"));
+        assert!(!fixed.replace("\r\n", "").contains('\n'));
+
+        let fixed = fix_header(Path::new("main.rs"), "fn main() {}", &template).unwrap();
+        assert!(fixed.ends_with("\n\nfn main() {}"));
+    }
+
+    #[test]
+    fn test_fix_headers_uses_compiled_templates() {
+        let rules = vec![Arc::new(header_rule("header", TEMPLATE))];
+        let violations = check_rules(Path::new("main.rs"), "fn main() {}\n", &rules).unwrap();
+
+        let fixed = fix_headers(Path::new("main.rs"), "fn main() {}\n", &violations, &rules).unwrap();
+        assert!(template_for(&rules, &violations[0].rule).unwrap().matches(&fixed.lines().collect::<Vec<_>>()));
+        // Rules compiled separately don't share the violation's rule
+        assert!(fix_headers(Path::new("main.rs"), "fn main() {}\n", &violations, &[header_rule("header", TEMPLATE)]).is_none());
+    }
+
+    fn fixed_header(year: i32) -> String {
+        format!("// Copyright (c) {} Acme, Inc.\n// SPDX-License-Identifier: Apache-2.0\n", year)
+    }
+
+    #[test]
+    fn test_parse_header_template() {
+        assert!(HeaderTemplate::parse("Copyright {year").is_err());
+        assert!(HeaderTemplate::parse("Copyright {}").is_err());
+        let template = HeaderTemplate::parse(r"Copyright {year} {company}\nAll rights reserved").unwrap();
+        assert_eq!(template.lines.len(), 2);
+    }
+}
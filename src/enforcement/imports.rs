@@ -0,0 +1,536 @@
+//! Import dependency rules for architectural layering
+//!
+//! Rules tagged `[import]` are evaluated against the `use`/`import` statements
+//! of a file instead of its raw text:
+//!
+//! * `FORBIDDEN[import]` is a deny list - imports matching the pattern are reported
+//! * `REQUIRED[import]` is an allow list - imports *not* matching the pattern are reported
+//!
+//! A pattern containing `/` is a glob matched against the project path the
+//! import resolves to (`src/infra/**`); any other pattern is matched against
+//! the imported module name (`neo4rs`, `^crate::infra`). The `except: <glob>`
+//! attribute exempts importing files, so "only src/db may use neo4rs" becomes
+//! `FORBIDDEN[import, except: src/db/**]: neo4rs - ...`.
+
+use crate::enforcement::scan::{project_files, read_text};
+use crate::models::{CompiledRule, PatternMatcher, RuleType, Violation};
+use crate::{RuleGraph, check_rules};
+use globset::{Glob, GlobMatcher};
+use rayon::prelude::*;
+use regex::Regex;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tracing::debug;
+
+/// A single `use`/`import` statement found in a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportStatement {
+    /// 1-based line number of the statement
+    pub line: usize,
+    /// Imported module as written (`crate::infra::Repo`, `../infra/repo`, `os.path`)
+    pub module: String,
+}
+
+fn rust_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| vec![
+        Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+(.*)$").unwrap(),
+        Regex::new(r"^\s*extern\s+crate\s+(\w+)").unwrap(),
+    ])
+}
+
+/// Split a use-tree list on the commas outside nested braces
+fn split_use_list(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in list.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&list[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items
+}
+
+/// Expand a Rust use-tree into the full paths it imports
+///
+/// `crate::{infra::{Db, self}, domain::*}` yields `crate::infra::Db`,
+/// `crate::infra` and `crate::domain`; renames (`as x`) are dropped.
+fn expand_use_tree(prefix: &str, tree: &str) -> Vec<String> {
+    let join = |path: &str| match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}::{}", prefix, path),
+    };
+    let tree = tree.trim().trim_start_matches("::");
+
+    if let (Some(open), Some(close)) = (tree.find('{'), tree.rfind('}')) {
+        let base = join(tree[..open].trim().trim_end_matches("::"));
+        return split_use_list(&tree[open + 1..close])
+            .into_iter()
+            .filter(|item| !item.trim().is_empty())
+            .flat_map(|item| expand_use_tree(&base, item))
+            .collect();
+    }
+
+    let path = tree.split(" as ").next().unwrap_or(tree).trim();
+    let path = path.trim_end_matches('*').trim_end_matches("::");
+    let path = path.strip_suffix("::self").unwrap_or(path);
+    if path == "self" {
+        vec![prefix.to_string()]
+    } else {
+        vec![join(path)]
+    }
+}
+
+/// Imports of a Rust file, with grouped and multi-line use-trees expanded
+fn rust_imports(lines: &[&str]) -> Vec<ImportStatement> {
+    let [use_pattern, extern_pattern] = rust_patterns() else {
+        return Vec::new();
+    };
+    let strip_comment = |line: &str| line.split("//").next().unwrap_or("").to_string();
+    let mut imports = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line_number = index + 1;
+        if let Some(captures) = extern_pattern.captures(lines[index]) {
+            imports.push(ImportStatement { line: line_number, module: captures[1].to_string() });
+        } else if let Some(captures) = use_pattern.captures(lines[index]) {
+            // A statement runs until its `;`, possibly over several lines
+            let mut statement = strip_comment(&captures[1]);
+            while !statement.contains(';') && index + 1 < lines.len() {
+                index += 1;
+                statement.push(' ');
+                statement.push_str(&strip_comment(lines[index]));
+            }
+            let tree = statement.split(';').next().unwrap_or("");
+            for module in expand_use_tree("", tree) {
+                if !module.is_empty() {
+                    imports.push(ImportStatement { line: line_number, module });
+                }
+            }
+        }
+        index += 1;
+    }
+    imports
+}
+
+fn python_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| vec![
+        Regex::new(r"^\s*from\s+(\.*[\w.]*)\s+import\s").unwrap(),
+    ])
+}
+
+fn script_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| vec![
+        Regex::new(r#"^\s*(?:import|export)\s[^'"]*?\bfrom\s*['"]([^'"]+)['"]"#).unwrap(),
+        Regex::new(r#"^\s*import\s*['"]([^'"]+)['"]"#).unwrap(),
+        Regex::new(r#"\b(?:require|import)\s*\(\s*['"]([^'"]+)['"]\s*\)"#).unwrap(),
+    ])
+}
+
+/// Extract the imports of a Rust, Python, TypeScript or JavaScript file
+///
+/// Rust use-trees are expanded into one import per path, reported at the
+/// line the statement starts on. For other languages only the first line of
+/// a multi-line statement is inspected, which is enough to capture the
+/// imported module. Other languages yield no imports.
+pub fn extract_imports(file_path: &Path, lines: &[&str]) -> Vec<ImportStatement> {
+    let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if extension == "rs" {
+        let mut imports = rust_imports(lines);
+        imports.dedup();
+        return imports;
+    }
+    let mut imports = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let mut push = |module: &str| imports.push(ImportStatement {
+            line: index + 1,
+            module: module.to_string(),
+        });
+
+        match extension {
+            "py" => {
+                if let Some(captures) = python_patterns()[0].captures(line) {
+                    push(&captures[1]);
+                } else if let Some(rest) = line.trim_start().strip_prefix("import ") {
+                    // `import a.b as c, d` imports several modules at once
+                    for module in rest.split(',') {
+                        let module = module.split_whitespace().next().unwrap_or("");
+                        if !module.is_empty() {
+                            push(module);
+                        }
+                    }
+                }
+            }
+            "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => {
+                for pattern in script_patterns() {
+                    for captures in pattern.captures_iter(line) {
+                        push(&captures[1]);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    imports.dedup();
+    imports
+}
+
+/// Lexically normalize `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Directory holding the children of a Rust module file (`src/a/b.rs` -> `src/a/b`)
+fn rust_module_dir(file_path: &Path) -> PathBuf {
+    let parent = file_path.parent().unwrap_or_else(|| Path::new(""));
+    match file_path.file_stem().and_then(|s| s.to_str()) {
+        Some("mod") | Some("lib") | Some("main") | None => parent.to_path_buf(),
+        Some(stem) => parent.join(stem),
+    }
+}
+
+/// Resolve an import to the project path it refers to, if it is a local import
+///
+/// Rust `crate::`, `self::` and `super::` paths, relative Python imports,
+/// relative script imports and Python packages found in an ancestor directory
+/// are resolved; third-party modules return `None`.
+pub fn resolve_import(file_path: &Path, module: &str) -> Option<PathBuf> {
+    let file_dir = file_path.parent().unwrap_or_else(|| Path::new(""));
+    let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    match extension {
+        "rs" => {
+            let mut segments = module.split("::").peekable();
+            let mut base = match segments.next()? {
+                "crate" => {
+                    let crate_root = file_dir.ancestors().find(|dir| dir.join("Cargo.toml").exists())?;
+                    crate_root.join("src")
+                }
+                "self" => rust_module_dir(file_path),
+                "super" => rust_module_dir(file_path).parent()?.to_path_buf(),
+                _ => return None,
+            };
+            while segments.peek() == Some(&"super") {
+                segments.next();
+                base = base.parent()?.to_path_buf();
+            }
+            Some(normalize(&segments.fold(base, |path, segment| path.join(segment))))
+        }
+        "py" => {
+            if module.starts_with('.') {
+                let dots = module.chars().take_while(|c| *c == '.').count();
+                let mut base = file_dir.to_path_buf();
+                for _ in 1..dots {
+                    base = base.parent()?.to_path_buf();
+                }
+                let rest = &module[dots..];
+                Some(rest.split('.').filter(|s| !s.is_empty()).fold(base, |path, segment| path.join(segment)))
+            } else {
+                let relative: PathBuf = module.split('.').collect();
+                let first = module.split('.').next()?;
+                file_dir.ancestors()
+                    .find(|dir| dir.join(first).is_dir() || dir.join(format!("{}.py", first)).is_file())
+                    .map(|dir| dir.join(&relative))
+            }
+        }
+        _ if module.starts_with('.') => Some(normalize(&file_dir.join(module))),
+        _ => None,
+    }
+}
+
+/// Compile a project-relative glob so it matches anywhere in an absolute path
+pub fn project_glob(pattern: &str) -> Option<GlobMatcher> {
+    let pattern = if pattern.starts_with('/') || pattern.starts_with("**") {
+        pattern.to_string()
+    } else {
+        format!("**/{}", pattern.trim_start_matches("./"))
+    };
+    Glob::new(&pattern).ok().map(|glob| glob.compile_matcher())
+}
+
+/// Whether a rule targets the imports of a file
+pub fn is_import_rule(compiled_rule: &CompiledRule) -> bool {
+    compiled_rule.rule.metadata.get("target").map(String::as_str) == Some("import")
+}
+
+/// Whether an import matches a rule pattern (module name or resolved path glob)
+fn import_matches(file_path: &Path, import: &ImportStatement, compiled_rule: &CompiledRule) -> bool {
+    match &compiled_rule.matcher {
+        PatternMatcher::Glob(glob) => {
+            let Some(target) = resolve_import(file_path, &import.module) else {
+                return false;
+            };
+            // `src/infra/**` should also cover importing the `infra` module itself
+            glob.is_match(&target) || glob.is_match(target.join("_"))
+        }
+        // Path patterns whose glob is invalid match nothing
+        _ if compiled_rule.rule.pattern.contains('/') => false,
+        PatternMatcher::Regex(regex) => regex.is_match(&import.module),
+        PatternMatcher::Literal(literal) => import.module.contains(literal.as_str()),
+        PatternMatcher::Structural(_) | PatternMatcher::Threshold(_) | PatternMatcher::Header(_) => false,
+    }
+}
+
+/// Check an `[import]` rule against the imports of one file
+pub fn check_import_rule(file_path: &Path, lines: &[&str], compiled_rule: &CompiledRule) -> Vec<Violation> {
+    let rule = &compiled_rule.rule;
+    if !matches!(rule.rule_type, RuleType::Forbidden | RuleType::Required) {
+        return Vec::new();
+    }
+
+    if let Some(except) = rule.metadata.get("except") {
+        match project_glob(except) {
+            Some(glob) if glob.is_match(file_path) => return Vec::new(),
+            Some(_) => {}
+            None => debug!("Ignoring invalid except glob '{}' on rule '{}'", except, rule.name),
+        }
+    }
+
+    extract_imports(file_path, lines)
+        .into_iter()
+        .filter(|import| {
+            let matched = import_matches(file_path, import, compiled_rule);
+            // Deny lists report matches, allow lists report everything else
+            if rule.rule_type == RuleType::Forbidden { matched } else { !matched }
+        })
+        // A grouped import is reported once per statement
+        .fold(Vec::<ImportStatement>::new(), |mut reported, import| {
+            if reported.last().is_none_or(|last| last.line != import.line) {
+                reported.push(import);
+            }
+            reported
+        })
+        .into_iter()
+        .map(|import| Violation::from_compiled_rule(
+            file_path.to_path_buf(),
+            compiled_rule,
+            Some(import.line),
+            Some(lines[import.line - 1].to_string()),
+        ))
+        .collect()
+}
+
+/// Source files that can carry imports
+fn is_source_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("rs" | "py" | "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs")
+    )
+}
+
+/// Check the import rules of every source file in a project
+///
+/// This is the project-wide pass for layering rules: it walks the Rust,
+/// Python, TypeScript and JavaScript files under `root` that `check --all`
+/// covers (see [`project_files`]) and evaluates only `[import]` rules.
+pub fn check_project_imports(root: &Path, rule_graph: &RuleGraph) -> crate::Result<Vec<Violation>> {
+    let files: Vec<PathBuf> = project_files(root, rule_graph.discovery())
+        .filter(|path| is_source_file(path))
+        .collect();
+
+    let results: Vec<crate::Result<Vec<Violation>>> = files
+        .par_iter()
+        .map(|file_path| {
+            let import_rules: Vec<Arc<CompiledRule>> = rule_graph.resolved_for(file_path).compiled
+                .iter()
+                .filter(|rule| is_import_rule(rule))
+                .cloned()
+                .collect();
+            if import_rules.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let content = match read_text(file_path) {
+                Ok(content) => content,
+                Err(reason) => {
+                    debug!("Skipping {} in import pass: {}", file_path.display(), reason);
+                    return Ok(Vec::new());
+                }
+            };
+            check_rules(file_path, &content, &import_rules)
+        })
+        .collect();
+
+    let mut violations = Vec::new();
+    for result in results {
+        violations.extend(result?);
+    }
+    violations.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line_number.cmp(&b.line_number)));
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Rule;
+    use crate::test_helpers::test_helpers::TestProject;
+    use std::collections::HashMap;
+
+    fn import_rule(rule_type: RuleType, pattern: &str, except: Option<&str>) -> CompiledRule {
+        let mut metadata = HashMap::new();
+        metadata.insert("target".to_string(), "import".to_string());
+        if let Some(except) = except {
+            metadata.insert("except".to_string(), except.to_string());
+        }
+        CompiledRule::from_rule(
+            Rule::new("layering".to_string(), rule_type, pattern.to_string(), "Layer violation".to_string())
+                .with_metadata(metadata),
+        )
+    }
+
+    #[test]
+    fn test_extract_rust_imports() {
+        let lines = vec![
+            "use std::collections::HashMap;",
+            "pub use crate::infra::{Repo, Db};",
+            "use neo4rs::*;",
+            "extern crate serde;",
+            "fn main() {}",
+        ];
+        let imports = extract_imports(Path::new("src/lib.rs"), &lines);
+        let modules: Vec<&str> = imports.iter().map(|i| i.module.as_str()).collect();
+
+        assert_eq!(modules, vec!["std::collections::HashMap", "crate::infra::Repo", "crate::infra::Db", "neo4rs", "serde"]);
+        assert_eq!(imports[1].line, 2);
+        assert_eq!(imports[2].line, 2);
+    }
+
+    #[test]
+    fn test_extract_grouped_rust_imports() {
+        let lines = vec![
+            "use crate::{",
+            "    infra::{Db, self}, // storage",
+            "    domain::{user::User as Account, *},",
+            "};",
+            "use ::serde::{Deserialize};",
+            "fn main() {}",
+        ];
+        let imports = extract_imports(Path::new("src/lib.rs"), &lines);
+        let modules: Vec<&str> = imports.iter().map(|i| i.module.as_str()).collect();
+
+        assert_eq!(modules, vec![
+            "crate::infra::Db", "crate::infra", "crate::domain::user::User", "crate::domain", "serde::Deserialize",
+        ]);
+        assert!(imports[..4].iter().all(|import| import.line == 1));
+        assert_eq!(imports[4].line, 5);
+    }
+
+    #[test]
+    fn test_forbidden_grouped_import_by_path_glob() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("Cargo.toml"), "[package]\nname = \"p\"\n").unwrap();
+        let file = temp_dir.path().join("src/domain/service.rs");
+        let rule = import_rule(RuleType::Forbidden, "src/infra/**", None);
+
+        let lines = vec!["use crate::{infra::Db, domain::User};"];
+        let violations = check_import_rule(&file, &lines, &rule);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line_number, Some(1));
+
+        let lines = vec!["use crate::{", "    domain::User,", "};"];
+        assert!(check_import_rule(&file, &lines, &rule).is_empty());
+    }
+
+    #[test]
+    fn test_extract_python_and_script_imports() {
+        let python = vec!["import os.path as p, sys", "from ..infra import db"];
+        let modules: Vec<String> = extract_imports(Path::new("app/views.py"), &python)
+            .into_iter().map(|i| i.module).collect();
+        assert_eq!(modules, vec!["os.path", "sys", "..infra"]);
+
+        let script = vec![
+            "import { Repo } from '../infra/repo';",
+            "import './styles.css';",
+            "const fs = require('fs');",
+        ];
+        let modules: Vec<String> = extract_imports(Path::new("src/domain/user.ts"), &script)
+            .into_iter().map(|i| i.module).collect();
+        assert_eq!(modules, vec!["../infra/repo", "./styles.css", "fs"]);
+    }
+
+    #[test]
+    fn test_resolve_relative_imports() {
+        assert_eq!(
+            resolve_import(Path::new("/p/src/domain/user.ts"), "../infra/repo"),
+            Some(PathBuf::from("/p/src/infra/repo"))
+        );
+        assert_eq!(
+            resolve_import(Path::new("/p/src/domain/user.rs"), "super::super::infra::Repo"),
+            Some(PathBuf::from("/p/src/infra/Repo"))
+        );
+        assert_eq!(resolve_import(Path::new("/p/src/main.rs"), "neo4rs"), None);
+    }
+
+    #[test]
+    fn test_forbidden_import_by_path_glob() {
+        let rule = import_rule(RuleType::Forbidden, "src/infra/**", None);
+        let lines = vec!["import { Repo } from '../infra/repo';", "import { User } from './user';"];
+
+        let violations = check_import_rule(Path::new("/p/src/domain/service.ts"), &lines, &rule);
+
+        assert!(matches!(rule.matcher, PatternMatcher::Glob(_)));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line_number, Some(1));
+    }
+
+    #[test]
+    fn test_forbidden_import_with_except() {
+        let rule = import_rule(RuleType::Forbidden, "neo4rs", Some("src/db/**"));
+        let lines = vec!["use neo4rs::Graph;"];
+
+        assert_eq!(check_import_rule(Path::new("/p/src/api/handler.rs"), &lines, &rule).len(), 1);
+        assert!(check_import_rule(Path::new("/p/src/db/pool.rs"), &lines, &rule).is_empty());
+    }
+
+    #[test]
+    fn test_required_import_allow_list() {
+        let rule = import_rule(RuleType::Required, r"^(std|core|crate::domain)\b", None);
+        let lines = vec!["use std::fmt;", "use crate::domain::User;", "use reqwest::Client;"];
+
+        let violations = check_import_rule(Path::new("/p/src/domain/user.rs"), &lines, &rule);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line_number, Some(3));
+    }
+
+    #[test]
+    fn test_project_imports_skip_ignored_files() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md",
+            "---\nmcp: synapse\n---\nFORBIDDEN[import]: `neo4rs` - Only the db layer talks to Neo4j\n").unwrap();
+        project.add_file(".gitignore", "generated/\n").unwrap();
+        project.add_file("src/api.rs", "use neo4rs::Graph;\n").unwrap();
+        project.add_file("generated/schema.rs", "use neo4rs::Graph;\n").unwrap();
+        project.add_file("src/blob.rs", b"use neo4rs::Graph;\n\x00").unwrap();
+
+        let rule_graph = RuleGraph::from_project(&project.root().to_path_buf()).unwrap();
+        let violations = check_project_imports(project.root(), &rule_graph).unwrap();
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].file_path.ends_with("src/api.rs"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Gradual rollout of rules
+//!
+//! Three settings, given as rule attributes or in a rule file's frontmatter,
+//! decide how a rule's violations are reported:
+//!
+//! * `enforce_from: YYYY-MM-DD` - before that date violations are warnings
+//!   that don't fail a check
+//! * `expires: YYYY-MM-DD` - from that date on the rule is no longer checked
+//! * `mode: shadow` - the rule is evaluated, but its hits are only logged and
+//!   counted (see [`ShadowCounter`]) instead of being reported
+//!
+//! Dates are compared against the local date.
+
+use crate::models::{CompiledRule, Rule};
+use crate::{Result, SynapseError};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Metadata keys holding the rollout settings
+pub const ROLLOUT_KEYS: [&str; 3] = ["enforce_from", "expires", "mode"];
+
+/// How a rule's violations are reported on a given day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RolloutStage {
+    Enforced,
+    /// Before `enforce_from`: violations are reported as warnings
+    Warning,
+    /// `mode: shadow`: violations are logged and counted, not reported
+    Shadow,
+    /// On or after `expires`: the rule is skipped
+    Expired,
+}
+
+impl RolloutStage {
+    pub fn of(rule: &Rule, today: NaiveDate) -> Self {
+        if rule.expires().is_some_and(|expires| today >= expires) {
+            RolloutStage::Expired
+        } else if rule.is_shadow() {
+            RolloutStage::Shadow
+        } else if rule.enforce_from().is_some_and(|from| today < from) {
+            RolloutStage::Warning
+        } else {
+            RolloutStage::Enforced
+        }
+    }
+}
+
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Parse an `enforce_from`/`expires` date (`YYYY-MM-DD`)
+pub fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| SynapseError::Parse(format!("Invalid date '{}' (expected YYYY-MM-DD)", value)))
+}
+
+/// Validate a `mode` value
+pub fn parse_mode(value: &str) -> Result<String> {
+    let mode = value.trim().to_lowercase();
+    match mode.as_str() {
+        "enforce" | "shadow" => Ok(mode),
+        _ => Err(SynapseError::Parse(format!("Unknown mode '{}' (expected enforce or shadow)", value))),
+    }
+}
+
+/// A copy of the rule without rollout settings, so it is always enforced
+///
+/// Rule examples use this: they describe what the rule matches, regardless
+/// of whether it is enforced yet.
+pub fn without_rollout(compiled_rule: &CompiledRule) -> CompiledRule {
+    let mut enforced = compiled_rule.clone();
+    let rule = Arc::make_mut(&mut enforced.rule);
+    for key in ROLLOUT_KEYS {
+        rule.metadata.remove(key);
+    }
+    enforced
+}
+
+/// Shadow rule hits counted by a [`ShadowCounter`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShadowHits {
+    pub total: u64,
+    /// Hits per rule name
+    pub by_rule: BTreeMap<String, u64>,
+}
+
+/// Hits of shadow rules, counted per rule
+///
+/// A [`RuleGraph`](crate::RuleGraph) owns one for the checks run against it,
+/// so the counts cover the lifetime of the graph rather than the process.
+#[derive(Debug, Default)]
+pub struct ShadowCounter {
+    counts: Mutex<BTreeMap<String, u64>>,
+}
+
+impl ShadowCounter {
+    /// Log and count the hits of a shadow rule in one file
+    pub fn record(&self, file_path: &Path, rule: &Rule, hits: usize) {
+        if hits == 0 {
+            return;
+        }
+        info!(rule = %rule.name, file = %file_path.display(), hits, "Shadow rule matched");
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        *counts.entry(rule.name.clone()).or_default() += hits as u64;
+    }
+
+    /// Current hit counts
+    pub fn hits(&self) -> ShadowHits {
+        let counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        ShadowHits {
+            total: counts.values().sum(),
+            by_rule: counts.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_rules;
+    use crate::enforcement::check_rules_with;
+    use crate::enforcement::prefilter::RulePrefilter;
+    use crate::models::RuleType;
+
+    fn rule(name: &str, settings: &[(&str, &str)]) -> CompiledRule {
+        let mut rule = Rule::new(name.to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string());
+        for (key, value) in settings {
+            rule.metadata.insert(key.to_string(), value.to_string());
+        }
+        CompiledRule::from_rule(rule)
+    }
+
+    #[test]
+    fn test_rollout_stages() {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let stage = |settings: &[(&str, &str)]| RolloutStage::of(&rule("r", settings).rule, today);
+
+        assert_eq!(stage(&[]), RolloutStage::Enforced);
+        assert_eq!(stage(&[("enforce_from", "2026-07-01")]), RolloutStage::Warning);
+        assert_eq!(stage(&[("enforce_from", "2026-06-01")]), RolloutStage::Enforced);
+        assert_eq!(stage(&[("expires", "2026-06-01")]), RolloutStage::Expired);
+        assert_eq!(stage(&[("expires", "2026-06-02"), ("mode", "shadow")]), RolloutStage::Shadow);
+        assert_eq!(stage(&[("mode", "shadow"), ("enforce_from", "2026-07-01")]), RolloutStage::Shadow);
+        assert!(parse_date("2026-13-01").is_err());
+        assert!(parse_mode("loud").is_err());
+    }
+
+    #[test]
+    fn test_rollout_in_checks() {
+        let rules = vec![
+            rule("lifecycle-warn", &[("enforce_from", "2999-01-01")]),
+            rule("lifecycle-expired", &[("expires", "2000-01-01")]),
+            rule("lifecycle-shadow", &[("mode", "shadow")]),
+        ];
+        let shadow_hits = ShadowCounter::default();
+
+        let violations = check_rules_with(Path::new("lib.rs"), "// TODO\n// TODO\n", &rules, &RulePrefilter::default(), &shadow_hits).unwrap();
+
+        let reported: Vec<(&str, bool)> = violations.iter().map(|v| (v.rule.name.as_str(), v.warning)).collect();
+        assert_eq!(reported, vec![("lifecycle-warn", true), ("lifecycle-warn", true)]);
+        assert_eq!(shadow_hits.hits(), ShadowHits { total: 2, by_rule: BTreeMap::from([("lifecycle-shadow".to_string(), 2)]) });
+
+        let examples = check_rules(Path::new("lib.rs"), "// TODO\n", &[without_rollout(&rules[1])]).unwrap();
+        assert_eq!(examples.len(), 1);
+    }
+
+    #[test]
+    fn test_without_rollout_keeps_the_files_base() {
+        let mut rule = Rule::new("todo".to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string());
+        rule.metadata.insert("files".to_string(), "src/*.rs".to_string());
+        rule.metadata.insert("mode".to_string(), "shadow".to_string());
+        let base = std::env::current_dir().unwrap().join("crate");
+        let compiled = CompiledRule::from_rule(rule).with_files_base(base.clone());
+
+        let enforced = without_rollout(&compiled);
+        assert!(!enforced.rule.is_shadow());
+        assert_eq!(enforced.base, Some(base));
+        // `src/*.rs` is relative to the base, not to the working directory
+        assert_eq!(check_rules(Path::new("crate/src/lib.rs"), "// TODO\n", &[enforced]).unwrap().len(), 1);
+    }
+}
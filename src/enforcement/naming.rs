@@ -0,0 +1,319 @@
+//! File and path naming convention rules
+//!
+//! Rules tagged `[path]` are evaluated against the checked file's path instead
+//! of its content. The pattern is either a named style (`snake_case`,
+//! `kebab-case`, `PascalCase`, `camelCase`, `SCREAMING_SNAKE_CASE`), which is
+//! applied to the file stem, or a regex matched against the path relative to
+//! the directory the rule's file governs, with `/` separators.
+//! An optional `files: <glob>` attribute limits which files the rule targets.
+
+use crate::models::{CompiledRule, PatternMatcher, Rule, RuleType, Violation};
+use globset::{Glob, GlobMatcher};
+use std::cell::OnceCell;
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+/// Named case styles for file stems
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamingStyle {
+    SnakeCase,
+    KebabCase,
+    PascalCase,
+    CamelCase,
+    ScreamingSnakeCase,
+}
+
+impl NamingStyle {
+    /// Look up a style by the name used in rule files
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake_case" => Some(NamingStyle::SnakeCase),
+            "kebab-case" => Some(NamingStyle::KebabCase),
+            "PascalCase" => Some(NamingStyle::PascalCase),
+            "camelCase" => Some(NamingStyle::CamelCase),
+            "SCREAMING_SNAKE_CASE" => Some(NamingStyle::ScreamingSnakeCase),
+            _ => None,
+        }
+    }
+
+    /// Whether a file stem follows this style
+    pub fn matches(&self, stem: &str) -> bool {
+        let mut chars = stem.chars();
+        let Some(first) = chars.next() else {
+            return false;
+        };
+
+        match self {
+            NamingStyle::SnakeCase => {
+                first.is_ascii_lowercase()
+                    && stem.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            }
+            NamingStyle::KebabCase => {
+                first.is_ascii_lowercase()
+                    && stem.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            }
+            NamingStyle::PascalCase => {
+                first.is_ascii_uppercase() && chars.all(|c| c.is_ascii_alphanumeric())
+            }
+            NamingStyle::CamelCase => {
+                first.is_ascii_lowercase() && chars.all(|c| c.is_ascii_alphanumeric())
+            }
+            NamingStyle::ScreamingSnakeCase => {
+                first.is_ascii_uppercase()
+                    && stem.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            }
+        }
+    }
+}
+
+/// Whether a rule targets the file path rather than its content
+pub fn is_path_rule(compiled_rule: &CompiledRule) -> bool {
+    compiled_rule.rule.metadata.get("target").map(String::as_str) == Some("path")
+}
+
+/// A rule's `files:` glob, compiled once with the rule
+///
+/// The glob is matched against the file name and against the path relative
+/// to `base`, the directory the rule's file governs. Files outside `base`
+/// (rules reached through `inherits:` or `extends:`) and rules built without
+/// one use the path relative to the working directory, so `./src/a.rs`,
+/// `src/a.rs` and the absolute path select the same rules.
+#[derive(Debug, Clone)]
+pub struct FileGlob {
+    /// `None` when the glob is invalid; the rule then targets no file
+    matcher: Option<GlobMatcher>,
+    base: Option<PathBuf>,
+}
+
+impl FileGlob {
+    /// Compile the `files:` glob of a rule, if it has one
+    pub fn of(rule: &Rule) -> Option<Self> {
+        let files = rule.metadata.get("files")?;
+        let matcher = match Glob::new(files) {
+            Ok(glob) => Some(glob.compile_matcher()),
+            Err(e) => {
+                debug!("Rule '{}' targets no file: invalid files glob '{}': {}", rule.name, files, e);
+                None
+            }
+        };
+        Some(Self { matcher, base: None })
+    }
+
+    /// Match relative to `dir`, a canonical directory
+    pub fn with_base(mut self, dir: PathBuf) -> Self {
+        self.base = Some(dir);
+        self
+    }
+
+    /// Whether the glob matches the file name or the relative path
+    pub fn is_match(&self, file_path: &CheckedPath) -> bool {
+        self.matcher.as_ref().is_some_and(|matcher| {
+            file_path.path.file_name().is_some_and(|name| matcher.is_match(name))
+                || matcher.is_match(file_path.relative_to(self.base.as_deref()))
+        })
+    }
+}
+
+/// A checked file's path as given, resolved on first use
+///
+/// Resolving canonicalizes the path, so it is done once per file rather
+/// than once per rule.
+#[derive(Debug)]
+pub struct CheckedPath<'a> {
+    path: &'a Path,
+    resolved: OnceCell<ResolvedPath>,
+}
+
+#[derive(Debug)]
+struct ResolvedPath {
+    absolute: PathBuf,
+    /// Relative to the working directory, or the path without `.`
+    /// components when it lies outside
+    from_cwd: PathBuf,
+}
+
+impl<'a> CheckedPath<'a> {
+    pub fn new(path: &'a Path) -> Self {
+        Self { path, resolved: OnceCell::new() }
+    }
+
+    /// The path as given
+    pub fn path(&self) -> &'a Path {
+        self.path
+    }
+
+    fn relative_to(&self, base: Option<&Path>) -> &Path {
+        let resolved = self.resolved.get_or_init(|| ResolvedPath::of(self.path));
+        base.and_then(|base| resolved.absolute.strip_prefix(base).ok())
+            .unwrap_or(&resolved.from_cwd)
+    }
+}
+
+impl ResolvedPath {
+    fn of(path: &Path) -> Self {
+        let normalized: PathBuf = path.components().filter(|c| *c != Component::CurDir).collect();
+        let cwd = std::env::current_dir().ok().map(|dir| dir.canonicalize().unwrap_or(dir));
+        let absolute = match &cwd {
+            Some(cwd) if normalized.is_relative() => cwd.join(&normalized),
+            _ => normalized.clone(),
+        };
+        // The file itself may not exist yet, as when content is checked before writing
+        let absolute = absolute.canonicalize().ok()
+            .or_else(|| Some(absolute.parent()?.canonicalize().ok()?.join(absolute.file_name()?)))
+            .unwrap_or(absolute);
+        let from_cwd = cwd.as_deref()
+            .and_then(|cwd| absolute.strip_prefix(cwd).ok())
+            .map_or(normalized, Path::to_path_buf);
+        Self { absolute, from_cwd }
+    }
+}
+
+/// Whether a rule's `files:` glob selects a file
+///
+/// Rules without `files:` target every file.
+pub fn targets_file(file_path: &CheckedPath, compiled_rule: &CompiledRule) -> bool {
+    compiled_rule.files.as_ref().is_none_or(|files| files.is_match(file_path))
+}
+
+/// Check a `[path]` rule against a file's path
+///
+/// FORBIDDEN rules report paths that match the pattern; REQUIRED and
+/// CONVENTION rules report paths that don't. The violation has no line number.
+/// The rule's `files:` glob is applied by `check_rules`.
+pub fn check_path_rule(file_path: &CheckedPath, compiled_rule: &CompiledRule) -> Option<Violation> {
+    let rule = &compiled_rule.rule;
+    let file_name = file_path.path.file_name()?.to_string_lossy();
+    let relative = || {
+        file_path.relative_to(compiled_rule.base.as_deref()).components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+
+    let matched = match NamingStyle::from_name(&rule.pattern) {
+        Some(style) => {
+            // Styles apply to the stem, so `my_module.test.rs` checks `my_module`
+            let stem = file_name.split('.').next().unwrap_or_default();
+            style.matches(stem)
+        }
+        None => match &compiled_rule.matcher {
+            PatternMatcher::Regex(regex) => regex.is_match(&relative()),
+            PatternMatcher::Literal(pattern) => relative().contains(pattern.as_str()),
+            PatternMatcher::Structural(_) | PatternMatcher::Threshold(_) | PatternMatcher::Glob(_) | PatternMatcher::Header(_) => return None,
+        },
+    };
+
+    let violated = match rule.rule_type {
+        RuleType::Forbidden => matched,
+        RuleType::Required | RuleType::Convention => !matched,
+        RuleType::Standard => false,
+    };
+
+    violated.then(|| Violation::from_compiled_rule(file_path.path.to_path_buf(), compiled_rule, None, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Rule;
+    use crate::test_helpers::test_helpers::TestProject;
+    use crate::RuleGraph;
+    use std::collections::HashMap;
+
+    fn path_rule(rule_type: RuleType, pattern: &str, files: Option<&str>) -> CompiledRule {
+        let mut metadata = HashMap::new();
+        metadata.insert("target".to_string(), "path".to_string());
+        if let Some(files) = files {
+            metadata.insert("files".to_string(), files.to_string());
+        }
+        CompiledRule::from_rule(
+            Rule::new("naming".to_string(), rule_type, pattern.to_string(), "Bad file name".to_string())
+                .with_metadata(metadata),
+        )
+    }
+
+    #[test]
+    fn test_naming_styles() {
+        assert!(NamingStyle::SnakeCase.matches("rule_graph"));
+        assert!(!NamingStyle::SnakeCase.matches("RuleGraph"));
+        assert!(NamingStyle::KebabCase.matches("rule-graph"));
+        assert!(NamingStyle::PascalCase.matches("RuleGraph"));
+        assert!(NamingStyle::CamelCase.matches("ruleGraph"));
+        assert!(NamingStyle::ScreamingSnakeCase.matches("README"));
+        assert!(!NamingStyle::CamelCase.matches(""));
+    }
+
+    #[test]
+    fn test_convention_style_rule() {
+        let rule = path_rule(RuleType::Convention, "snake_case", Some("*.rs"));
+
+        assert!(check_path_rule(&CheckedPath::new(Path::new("src/rule_graph.rs")), &rule).is_none());
+        assert!(check_path_rule(&CheckedPath::new(Path::new("src/RuleGraph.rs")), &rule).is_some());
+        // Not selected by the files glob
+        assert!(crate::check_rules(Path::new("src/README.md"), "", &[rule]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_required_and_forbidden_regex_rules() {
+        let required = path_rule(RuleType::Required, r"_test\.py$", Some("*.py"));
+        assert!(check_path_rule(&CheckedPath::new(Path::new("tests/parser_test.py")), &required).is_none());
+        assert!(check_path_rule(&CheckedPath::new(Path::new("tests/parser.py")), &required).is_some());
+
+        let forbidden = path_rule(RuleType::Forbidden, r"\.orig$", None);
+        assert!(check_path_rule(&CheckedPath::new(Path::new("src/main.rs.orig")), &forbidden).is_some());
+        assert!(check_path_rule(&CheckedPath::new(Path::new("src/main.rs")), &forbidden).is_none());
+    }
+
+    #[test]
+    fn test_regex_rules_match_directory_names() {
+        let forbidden = path_rule(RuleType::Forbidden, r"(^|/)[A-Z][^/]*/", None);
+        assert!(check_path_rule(&CheckedPath::new(Path::new("src/Legacy/parser.rs")), &forbidden).is_some());
+        assert!(check_path_rule(&CheckedPath::new(Path::new("./src/legacy/Parser.rs")), &forbidden).is_none());
+    }
+
+    #[test]
+    fn test_regex_rules_are_relative_to_the_rule_dir() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file("lib/.synapse/rules.md",
+            "---\nmcp: synapse\n---\nFORBIDDEN[path]: `^src/tmp/` - Don't commit scratch files\n").unwrap();
+        project.add_file("lib/src/tmp/scratch.rs", "").unwrap();
+        project.add_file("lib/tmp/src/tmp.rs", "").unwrap();
+
+        let graph = RuleGraph::from_project(&project.root().to_path_buf()).unwrap();
+        let check = |path: PathBuf| graph.resolved_for(&path).check(&path, "").unwrap();
+
+        assert_eq!(check(project.path("lib/src/tmp/scratch.rs")).len(), 1);
+        assert!(check(project.path("lib/tmp/src/tmp.rs")).is_empty());
+    }
+
+    #[test]
+    fn test_files_glob_ignores_how_the_path_is_spelled() {
+        let rule = path_rule(RuleType::Convention, "snake_case", Some("src/**/*.rs"));
+        let absolute = std::env::current_dir().unwrap().join("src/enforcement/naming.rs");
+
+        for path in [Path::new("src/enforcement/naming.rs"), Path::new("./src/enforcement/naming.rs"), absolute.as_path()] {
+            assert!(targets_file(&CheckedPath::new(path), &rule), "{}", path.display());
+        }
+        assert!(!targets_file(&CheckedPath::new(Path::new("./benches/naming.rs")), &rule));
+    }
+
+    #[test]
+    fn test_files_glob_is_relative_to_the_rule_dir() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md",
+            "---\nmcp: synapse\n---\nCONVENTION[path, files: src/**/*.rs]: `snake_case` - Use snake_case\n").unwrap();
+        project.add_rule_file("lib/.synapse/rules.md", "---\nmcp: synapse\n---\nFORBIDDEN[files: *.rs]: `TODO` - No TODOs\n").unwrap();
+        project.add_file("src/a/BadName.rs", "").unwrap();
+        project.add_file("lib/src/Util.rs", "// TODO\n").unwrap();
+
+        let graph = RuleGraph::from_project(&project.root().to_path_buf()).unwrap();
+        let check = |path: PathBuf| graph.resolved_for(&path).check(&path, &std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(check(project.path("src/a/BadName.rs")).len(), 1);
+        assert_eq!(check(project.root().join("./src/./a/BadName.rs")).len(), 1);
+        // `src/**` is relative to the project root, not to lib/
+        let lib_violations = check(project.path("lib/src/Util.rs"));
+        assert_eq!(lib_violations.len(), 1);
+        assert_eq!(lib_violations[0].rule.pattern, "TODO");
+    }
+}
@@ -0,0 +1,217 @@
+//! Single-pass candidate selection for pattern rules
+//!
+//! Matching every rule against every line costs rules × lines, which adds up
+//! once rule packs bring in hundreds of rules. The prefilter combines the
+//! literal patterns of a rule list into one Aho-Corasick automaton and the
+//! regex patterns into one `RegexSet`. A single scan of the content tells which
+//! rules can match at all; only those candidates go on to full matching in
+//! `check_rules`, the rest are known to have no occurrences.
+//!
+//! Regexes are scanned line by line, as in full matching, so anchors such as
+//! `^` and `$` keep their meaning. Literals are found in the whole content;
+//! a literal on a line is also in the content, so no candidate is missed.
+
+use crate::models::{CompiledRule, PatternMatcher, RuleType};
+use aho_corasick::AhoCorasick;
+use regex::RegexSet;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Rule lists shorter than this are matched directly by `check_rules`;
+/// building the automata would cost more than it saves
+pub const PREFILTER_MIN_RULES: usize = 16;
+
+/// Combined matchers for the pattern rules of a rule list
+///
+/// Built once per rule list (the directory index keeps one per resolved
+/// directory) and used with the same list in `check_rules_with`.
+#[derive(Debug, Clone, Default)]
+pub struct RulePrefilter {
+    rule_count: usize,
+    literals: Option<AhoCorasick>,
+    /// Rules using each automaton pattern; equal patterns share one entry
+    literal_rules: Vec<Vec<usize>>,
+    regexes: Option<RegexSet>,
+    regex_rules: Vec<Vec<usize>>,
+}
+
+/// Rules that may match a file, by position in the rule list
+#[derive(Debug, Clone)]
+pub struct Candidates(Vec<bool>);
+
+impl Candidates {
+    /// Whether the rule at `index` needs full matching
+    ///
+    /// Rules the prefilter doesn't cover (path, import, structural and
+    /// threshold rules) are always candidates.
+    pub fn contains(&self, index: usize) -> bool {
+        self.0.get(index).copied().unwrap_or(true)
+    }
+}
+
+impl RulePrefilter {
+    /// Build the combined matchers for a rule list
+    ///
+    /// If a combined matcher can't be built (e.g. the `RegexSet` exceeds the
+    /// regex size limit) its rules stay unfiltered.
+    pub fn new<R: Borrow<CompiledRule>>(rules: &[R]) -> Self {
+        let mut literal_patterns: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut regex_sources: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (index, compiled_rule) in rules.iter().enumerate() {
+            let compiled_rule: &CompiledRule = compiled_rule.borrow();
+            if !Self::covers(compiled_rule) {
+                continue;
+            }
+            match &compiled_rule.matcher {
+                PatternMatcher::Literal(pattern) => {
+                    literal_patterns.entry(pattern.as_str()).or_default().push(index);
+                }
+                PatternMatcher::Regex(regex) => {
+                    // `as_str` drops the builder's case flag, so restore it inline
+                    let source = if compiled_rule.rule.ignores_case() {
+                        format!("(?i:{})", regex.as_str())
+                    } else {
+                        regex.as_str().to_string()
+                    };
+                    regex_sources.entry(source).or_default().push(index);
+                }
+                PatternMatcher::Structural(_) | PatternMatcher::Threshold(_) | PatternMatcher::Glob(_) | PatternMatcher::Header(_) => {}
+            }
+        }
+
+        let mut prefilter = Self { rule_count: rules.len(), ..Self::default() };
+
+        let (patterns, literal_rules): (Vec<&str>, Vec<Vec<usize>>) = literal_patterns.into_iter().unzip();
+        if !patterns.is_empty() {
+            match AhoCorasick::new(&patterns) {
+                Ok(automaton) => {
+                    prefilter.literals = Some(automaton);
+                    prefilter.literal_rules = literal_rules;
+                }
+                Err(e) => debug!("Literal prefilter disabled: {}", e),
+            }
+        }
+
+        let (sources, regex_rules): (Vec<String>, Vec<Vec<usize>>) = regex_sources.into_iter().unzip();
+        if !sources.is_empty() {
+            match RegexSet::new(&sources) {
+                Ok(set) => {
+                    prefilter.regexes = Some(set);
+                    prefilter.regex_rules = regex_rules;
+                }
+                Err(e) => debug!("Regex prefilter disabled: {}", e),
+            }
+        }
+
+        prefilter
+    }
+
+    /// Whether a rule's pattern is matched against file content line by line
+    ///
+    /// Rules with a `target` (paths, imports, dependencies, secrets, headers,
+    /// commit messages) are evaluated by their own checks.
+    fn covers(compiled_rule: &CompiledRule) -> bool {
+        matches!(compiled_rule.rule.rule_type, RuleType::Forbidden | RuleType::Required)
+            && !compiled_rule.rule.metadata.contains_key("target")
+    }
+
+    /// Number of rules filtered by the combined matchers
+    pub fn filtered_rules(&self) -> usize {
+        self.literal_rules.iter().chain(&self.regex_rules).map(Vec::len).sum()
+    }
+
+    /// Scan the content once and select the rules that may match
+    pub fn candidates(&self, content: &str, lines: &[&str]) -> Candidates {
+        let mut candidates = vec![true; self.rule_count];
+        for &index in self.literal_rules.iter().chain(&self.regex_rules).flatten() {
+            candidates[index] = false;
+        }
+
+        if let Some(automaton) = &self.literals {
+            let mut remaining = self.literal_rules.len();
+            let mut found = vec![false; remaining];
+            for hit in automaton.find_overlapping_iter(content) {
+                let pattern = hit.pattern().as_usize();
+                if !found[pattern] {
+                    found[pattern] = true;
+                    for &index in &self.literal_rules[pattern] {
+                        candidates[index] = true;
+                    }
+                    remaining -= 1;
+                    if remaining == 0 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(set) = &self.regexes {
+            let mut remaining = self.regex_rules.len();
+            let mut found = vec![false; remaining];
+            for line in lines {
+                for pattern in set.matches(line).iter() {
+                    if !found[pattern] {
+                        found[pattern] = true;
+                        for &index in &self.regex_rules[pattern] {
+                            candidates[index] = true;
+                        }
+                        remaining -= 1;
+                    }
+                }
+                if remaining == 0 {
+                    break;
+                }
+            }
+        }
+
+        Candidates(candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Rule;
+    use std::collections::HashMap;
+
+    fn compiled(rule_type: RuleType, pattern: &str, metadata: &[(&str, &str)]) -> CompiledRule {
+        CompiledRule::from_rule(Rule {
+            id: pattern.to_string(),
+            name: pattern.to_string(),
+            rule_type,
+            pattern: pattern.to_string(),
+            message: String::new(),
+            tags: vec![],
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>(),
+            owners: vec![],
+        })
+    }
+
+    #[test]
+    fn test_candidates() {
+        let rules = vec![
+            compiled(RuleType::Forbidden, "TODO", &[]),
+            compiled(RuleType::Forbidden, "dbg!", &[]),
+            compiled(RuleType::Forbidden, r"^\s*unsafe\b", &[]),
+            compiled(RuleType::Required, "copyright", &[("ignore_case", "true")]),
+            compiled(RuleType::Forbidden, "TODO", &[("target", "path")]),
+            compiled(RuleType::Forbidden, r"fn\s+main", &[]),
+        ];
+        let prefilter = RulePrefilter::new(&rules);
+        assert_eq!(prefilter.filtered_rules(), 5);
+
+        let content = "// Copyright 2024\n  unsafe { x }\n// TODO later\n";
+        let lines: Vec<&str> = content.lines().collect();
+        let candidates = prefilter.candidates(content, &lines);
+        assert!(candidates.contains(0));
+        assert!(!candidates.contains(1));
+        // `^` anchors to each line, not only the start of the content
+        assert!(candidates.contains(2));
+        assert!(candidates.contains(3));
+        // Path rules are never filtered
+        assert!(candidates.contains(4));
+        assert!(!candidates.contains(5));
+    }
+}
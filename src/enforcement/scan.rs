@@ -0,0 +1,220 @@
+//! Whole-tree scanning for `synapse check --all`
+//!
+//! Files are enumerated with the `ignore` walker, which honors `.gitignore`
+//! (inside or outside a git repository) and `.synapseignore`. Hidden files
+//! such as `.env` and `.github/workflows` are scanned, since they hold
+//! configuration that rules commonly target; `.git`, `.synapse` (which also
+//! holds rule statistics outside git checkouts) and any other configured rule
+//! directories are not. Each file is checked on the rayon
+//! pool as soon as the walker yields it, and its result is handed to a
+//! callback, so callers can report files while the scan is still running.
+//! Binary and non-UTF-8 files are skipped rather than treated as errors.
+
+use crate::models::Violation;
+use crate::rule_graph::RuleGraph;
+use crate::rule_graph::index::ResolvedRules;
+use crate::rules::discovery::RuleDiscovery;
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Ignore file for paths that Synapse should skip but git should track
+pub const IGNORE_FILE: &str = ".synapseignore";
+
+/// Synapse's own directory, skipped even when `rule_dirs` doesn't list it
+const SYNAPSE_DIR: &str = ".synapse";
+
+/// Bytes inspected for NUL when deciding whether a file is binary (as git does)
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Why a file was not checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    Binary,
+    NotUtf8,
+    Unreadable(String),
+    /// The file was read but checking it against its rules failed
+    CheckFailed(String),
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binary file"),
+            SkipReason::NotUtf8 => write!(f, "not valid UTF-8"),
+            SkipReason::Unreadable(e) => write!(f, "unreadable: {}", e),
+            SkipReason::CheckFailed(e) => write!(f, "check failed: {}", e),
+        }
+    }
+}
+
+/// Outcome of scanning one file
+#[derive(Debug, Clone)]
+pub enum FileScan {
+    Checked {
+        path: PathBuf,
+        /// The rules that apply to the file
+        rules: Arc<ResolvedRules>,
+        violations: Vec<Violation>,
+    },
+    Skipped {
+        path: PathBuf,
+        reason: SkipReason,
+    },
+}
+
+impl FileScan {
+    pub fn path(&self) -> &Path {
+        match self {
+            FileScan::Checked { path, .. } | FileScan::Skipped { path, .. } => path,
+        }
+    }
+}
+
+/// Files under `root` that a full scan covers
+pub fn project_files(root: &Path, discovery: &RuleDiscovery) -> impl Iterator<Item = PathBuf> {
+    let rule_dirs = discovery.config().rule_dirs.clone();
+    WalkBuilder::new(root)
+        .require_git(false)
+        .hidden(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let name = entry.file_name();
+            !is_dir || (name != ".git" && name != SYNAPSE_DIR && !rule_dirs.iter().any(|dir| name == dir.as_str()))
+        })
+        .build()
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                debug!("Skipping walk entry: {}", e);
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(ignore::DirEntry::into_path)
+}
+
+/// Read a file for checking, or tell why it is skipped
+pub fn read_text(path: &Path) -> Result<String, SkipReason> {
+    let bytes = std::fs::read(path).map_err(|e| SkipReason::Unreadable(e.to_string()))?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+        return Err(SkipReason::Binary);
+    }
+    String::from_utf8(bytes).map_err(|_| SkipReason::NotUtf8)
+}
+
+/// Check every file under `root` in parallel
+///
+/// `on_file` is called from the worker threads, once per file, in completion
+/// order. Returns the number of files visited.
+pub fn scan_project<F>(root: &Path, rule_graph: &RuleGraph, on_file: F) -> usize
+where
+    F: Fn(FileScan) + Send + Sync,
+{
+    project_files(root, rule_graph.discovery())
+        .par_bridge()
+        .map(|path| on_file(scan_file(&path, rule_graph)))
+        .count()
+}
+
+/// Check a single file against the rules that apply to it
+///
+/// Files without applicable rules are reported as checked without being read.
+pub fn scan_file(path: &Path, rule_graph: &RuleGraph) -> FileScan {
+    let resolved = rule_graph.resolved_for(path);
+    let checked = |violations| FileScan::Checked {
+        path: path.to_path_buf(),
+        rules: resolved.clone(),
+        violations,
+    };
+    let skipped = |reason| FileScan::Skipped { path: path.to_path_buf(), reason };
+
+    if resolved.compiled.is_empty() {
+        return checked(Vec::new());
+    }
+
+    let content = match read_text(path) {
+        Ok(content) => content,
+        Err(reason) => return skipped(reason),
+    };
+
+    match resolved.check(path, &content) {
+        Ok(violations) => checked(violations),
+        Err(e) => skipped(SkipReason::CheckFailed(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::test_helpers::test_helpers::TestProject;
+
+    #[test]
+    fn test_scan_project() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file(".synapse/rules.md", b"---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\n").unwrap();
+        project.add_file(".gitignore", b"build/\n").unwrap();
+        project.add_file(IGNORE_FILE, b"fixtures/\n").unwrap();
+        project.add_file("src/lib.rs", b"// TODO: tidy\n").unwrap();
+        project.add_file("src/clean.rs", b"fn main() {}\n").unwrap();
+        project.add_file("build/out.rs", b"// TODO\n").unwrap();
+        project.add_file("fixtures/sample.rs", b"// TODO\n").unwrap();
+        project.add_file("assets/logo.png", b"\x89PNG\x00\x00TODO").unwrap();
+        project.add_file("data/latin1.txt", b"caf\xe9 TODO").unwrap();
+        project.add_file(".env", b"# TODO: rotate\n").unwrap();
+        project.add_file(".github/workflows/ci.yml", b"# TODO\n").unwrap();
+        project.add_file(".git/HEAD", b"# TODO\n").unwrap();
+
+        let rule_graph = RuleGraph::from_project(&root.to_path_buf()).unwrap();
+        let scans = Mutex::new(Vec::new());
+        let visited = scan_project(root, &rule_graph, |scan| scans.lock().unwrap().push(scan));
+
+        let mut scans = scans.into_inner().unwrap();
+        scans.sort_by(|a, b| a.path().cmp(b.path()));
+        let summary: Vec<(String, String)> = scans.iter()
+            .map(|scan| {
+                let name = scan.path().strip_prefix(root).unwrap().display().to_string();
+                let outcome = match scan {
+                    FileScan::Checked { violations, .. } => format!("{} violation(s)", violations.len()),
+                    FileScan::Skipped { reason, .. } => reason.to_string(),
+                };
+                (name, outcome)
+            })
+            .collect();
+
+        assert_eq!(visited, 8);
+        assert_eq!(summary, vec![
+            (".env".to_string(), "1 violation(s)".to_string()),
+            (".github/workflows/ci.yml".to_string(), "1 violation(s)".to_string()),
+            (".gitignore".to_string(), "0 violation(s)".to_string()),
+            (IGNORE_FILE.to_string(), "0 violation(s)".to_string()),
+            ("assets/logo.png".to_string(), "binary file".to_string()),
+            ("data/latin1.txt".to_string(), "not valid UTF-8".to_string()),
+            ("src/clean.rs".to_string(), "0 violation(s)".to_string()),
+            ("src/lib.rs".to_string(), "1 violation(s)".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_scan_skips_the_synapse_dir_with_other_rule_dirs() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_file("config.toml", "[discovery]\nrule_dirs = [\".rules\"]\n").unwrap();
+        project.add_rule_file(".rules/rules.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\n").unwrap();
+        project.add_file(".synapse/rule-stats.json", "{\"TODO\": 1}\n").unwrap();
+        project.add_file("src/lib.rs", "// TODO\n").unwrap();
+
+        let rule_graph = RuleGraph::from_project(&root.to_path_buf()).unwrap();
+        let paths: Vec<PathBuf> = project_files(root, rule_graph.discovery())
+            .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+            .filter(|path| path != Path::new("config.toml"))
+            .collect();
+
+        assert_eq!(paths, vec![PathBuf::from("src/lib.rs")]);
+    }
+}
@@ -0,0 +1,371 @@
+//! Item extraction for scoped REQUIRED rules
+//!
+//! A REQUIRED rule with `scope: item` or `scope: function` is checked once per
+//! declaration instead of once per file. Items are located with lightweight,
+//! per-language line patterns; an item's text runs from its attached comments
+//! and attributes down to the line before the next item starts, or to the end
+//! of its body.
+//!
+//! In brace languages (Rust, TypeScript, JavaScript) braces are tracked, so
+//! declarations are only items at module level or directly inside `impl`,
+//! `trait`, `mod` and class bodies; a `const` in a function body is part of
+//! the function. Python items are found at any indentation.
+
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Granularity at which a REQUIRED rule is evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleScope {
+    /// Pattern must appear somewhere in the file (default)
+    File,
+    /// Pattern must appear in every declaration (functions, types, modules, ...)
+    Item,
+    /// Pattern must appear in every function
+    Function,
+}
+
+impl RuleScope {
+    /// Parse a scope from its rule file spelling
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(RuleScope::File),
+            "item" => Some(RuleScope::Item),
+            "function" => Some(RuleScope::Function),
+            _ => None,
+        }
+    }
+
+    /// Name used for this scope in rule files
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleScope::File => "file",
+            RuleScope::Item => "item",
+            RuleScope::Function => "function",
+        }
+    }
+}
+
+/// A declaration found in a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceItem {
+    /// Kind of declaration (`fn`, `struct`, `class`, ...)
+    pub kind: String,
+    /// Declared name
+    pub name: String,
+    /// 1-based line of the declaration itself
+    pub line: usize,
+    /// 0-based index of the first line belonging to the item (attached comments included)
+    pub start: usize,
+    /// 0-based index one past the last line belonging to the item
+    pub end: usize,
+}
+
+impl SourceItem {
+    /// Whether this item is a function or method
+    pub fn is_function(&self) -> bool {
+        matches!(self.kind.as_str(), "fn" | "def" | "function")
+    }
+
+    /// Whether declarations in this item's body are items too
+    fn is_container(&self) -> bool {
+        matches!(self.kind.as_str(), "impl" | "trait" | "mod" | "class")
+    }
+}
+
+struct ItemPattern {
+    regex: Regex,
+    /// Fixed kind, or None to take the kind from the `kind` capture group
+    kind: Option<&'static str>,
+}
+
+fn item_patterns(extension: &str) -> &'static [ItemPattern] {
+    static RUST: OnceLock<Vec<ItemPattern>> = OnceLock::new();
+    static PYTHON: OnceLock<Vec<ItemPattern>> = OnceLock::new();
+    static SCRIPT: OnceLock<Vec<ItemPattern>> = OnceLock::new();
+
+    match extension {
+        "rs" => RUST.get_or_init(|| vec![
+            ItemPattern {
+                regex: Regex::new(r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:default\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+"[^"]*"\s+)?fn\s+(?P<name>\w+)"#).unwrap(),
+                kind: Some("fn"),
+            },
+            ItemPattern {
+                regex: Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:unsafe\s+)?(?P<kind>struct|enum|union|trait|type|const|static|mod)\s+(?P<name>\w+)").unwrap(),
+                kind: None,
+            },
+            // Named after the implementing type: `impl<T> Display for Point<T>` is `Point`
+            ItemPattern {
+                regex: Regex::new(r"^\s*(?:unsafe\s+)?impl\b(?:\s*<.*?>)?\s+(?:[\w:]+(?:<.*?>)?\s+for\s+)?(?P<name>[\w:]+)").unwrap(),
+                kind: Some("impl"),
+            },
+        ]),
+        "py" => PYTHON.get_or_init(|| vec![
+            ItemPattern {
+                regex: Regex::new(r"^\s*(?:async\s+)?def\s+(?P<name>\w+)").unwrap(),
+                kind: Some("def"),
+            },
+            ItemPattern {
+                regex: Regex::new(r"^\s*class\s+(?P<name>\w+)").unwrap(),
+                kind: Some("class"),
+            },
+        ]),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => SCRIPT.get_or_init(|| vec![
+            ItemPattern {
+                regex: Regex::new(r"^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*(?P<name>\w+)").unwrap(),
+                kind: Some("function"),
+            },
+            ItemPattern {
+                regex: Regex::new(r"^\s*(?:export\s+)?(?:default\s+)?(?:abstract\s+)?(?P<kind>class|interface|enum)\s+(?P<name>\w+)").unwrap(),
+                kind: None,
+            },
+            ItemPattern {
+                regex: Regex::new(r"^\s*(?:export\s+)?type\s+(?P<name>\w+)\s*(?:<[^=]*>)?\s*=").unwrap(),
+                kind: Some("type"),
+            },
+        ]),
+        _ => &[],
+    }
+}
+
+/// Lines that attach to the declaration below them (docs, comments, attributes, decorators)
+fn is_attached_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("//")
+        || trimmed.starts_with("#[")
+        || trimmed.starts_with("/*")
+        || trimmed.starts_with('*')
+        || trimmed.starts_with('@')
+        || (trimmed.starts_with('#') && !trimmed.starts_with("#!"))
+}
+
+/// Finds the braces of a brace language outside strings and comments
+#[derive(Default)]
+struct BraceScanner {
+    /// `'` starts a character literal or a lifetime (Rust), not a string
+    rust: bool,
+    /// Quote of a string that continues on the next line
+    quote: Option<char>,
+    /// Inside a block comment that continues on the next line
+    comment: bool,
+}
+
+impl BraceScanner {
+    /// Whether the next line starts inside a string or block comment
+    fn in_literal(&self) -> bool {
+        self.quote.is_some() || self.comment
+    }
+
+    /// The braces of a line, in order
+    fn braces(&mut self, line: &str) -> Vec<char> {
+        let mut braces = Vec::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if self.comment {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    self.comment = false;
+                }
+                continue;
+            }
+            if let Some(quote) = self.quote {
+                if c == '\\' {
+                    chars.next();
+                } else if c == quote {
+                    self.quote = None;
+                }
+                continue;
+            }
+            match c {
+                '/' if chars.peek() == Some(&'/') => break,
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    self.comment = true;
+                }
+                '"' | '`' => self.quote = Some(c),
+                '\'' if !self.rust => self.quote = Some(c),
+                // A character literal ('{', '\''), not a lifetime ('a)
+                '\'' => {
+                    let mut ahead = chars.clone();
+                    match (ahead.next(), ahead.next()) {
+                        (Some('\\'), _) => self.quote = Some(c),
+                        (Some(_), Some('\'')) => {
+                            chars.next();
+                            chars.next();
+                        }
+                        _ => {}
+                    }
+                }
+                '{' | '}' => braces.push(c),
+                _ => {}
+            }
+        }
+        braces
+    }
+}
+
+/// Locate the declarations in a source file
+///
+/// Returns an empty list for languages without item patterns
+/// (anything other than Rust, Python, TypeScript and JavaScript).
+pub fn extract_items(file_path: &Path, lines: &[&str]) -> Vec<SourceItem> {
+    let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let patterns = item_patterns(extension);
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    let uses_braces = extension != "py";
+    let mut scanner = BraceScanner { rust: extension == "rs", ..Default::default() };
+    // One entry per open brace: whether declarations directly inside it are items
+    let mut blocks: Vec<bool> = Vec::new();
+    // Number of open braces where each item was declared
+    let mut depths: Vec<usize> = Vec::new();
+    // The last item's body hasn't opened yet, and declares items
+    let mut opens_container = false;
+
+    let mut items: Vec<SourceItem> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let at_item_level = !scanner.in_literal() && blocks.iter().all(|container| *container);
+        let declared = at_item_level.then(|| patterns.iter().find_map(|pattern| {
+            pattern.regex.captures(line).map(|captures| {
+                let kind = pattern.kind
+                    .map(str::to_string)
+                    .or_else(|| captures.name("kind").map(|m| m.as_str().to_string()))
+                    .unwrap_or_default();
+                (kind, captures["name"].to_string())
+            })
+        })).flatten();
+
+        if let Some((kind, name)) = declared {
+            let mut start = index;
+            while start > 0 && is_attached_line(lines[start - 1]) {
+                start -= 1;
+            }
+            // Never reach back into the previous declaration
+            if let Some(previous) = items.last() {
+                start = start.max(previous.line);
+            }
+
+            if let Some(previous) = items.last_mut()
+                && previous.end == lines.len()
+            {
+                previous.end = start;
+            }
+
+            let item = SourceItem {
+                kind,
+                name,
+                line: index + 1,
+                start,
+                end: lines.len(),
+            };
+            opens_container = uses_braces && item.is_container();
+            depths.push(blocks.len());
+            items.push(item);
+        }
+
+        if !uses_braces {
+            continue;
+        }
+        for brace in scanner.braces(line) {
+            if brace == '{' {
+                blocks.push(std::mem::take(&mut opens_container));
+                continue;
+            }
+            blocks.pop();
+            // An open item ends with its own body, or with the block it was declared in
+            if let (Some(item), Some(&depth)) = (items.last_mut(), depths.last())
+                && item.end == lines.len()
+            {
+                if blocks.len() == depth {
+                    item.end = index + 1;
+                } else if blocks.len() < depth {
+                    item.end = index;
+                }
+            }
+        }
+        // `mod tests;` has no body
+        if line.trim_end().ends_with(';') {
+            opens_container = false;
+        }
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_rust_items_with_docs() {
+        let content = "use std::fmt;\n\n/// Adds numbers\n#[inline]\npub fn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n\npub struct Point;\n";
+        let lines: Vec<&str> = content.lines().collect();
+
+        let items = extract_items(Path::new("lib.rs"), &lines);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].kind, "fn");
+        assert_eq!(items[0].name, "add");
+        assert_eq!(items[0].line, 5);
+        assert_eq!(items[0].start, 2);
+        assert_eq!(items[1].kind, "struct");
+        assert_eq!(items[1].start, 8);
+        // The function ends with its body
+        assert_eq!(items[0].end, 7);
+    }
+
+    #[test]
+    fn test_rust_items_are_module_and_impl_level_declarations() {
+        let content = "pub fn load() -> u32 {\n    const LIMIT: u32 = 3;\n    static NAME: &str = \"}\";\n    type Alias = u32;\n    LIMIT\n}\n\n/// A point\nstruct Point;\n\nimpl fmt::Display for Point {\n    /// Formats the point\n    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {\n        let brace = '{';\n        write!(f, \"{}\", brace)\n    }\n}\n\nmod tests;\n";
+        let lines: Vec<&str> = content.lines().collect();
+
+        let items = extract_items(Path::new("lib.rs"), &lines);
+        let found: Vec<(&str, &str, usize, usize, usize)> = items
+            .iter()
+            .map(|i| (i.kind.as_str(), i.name.as_str(), i.line, i.start, i.end))
+            .collect();
+
+        // The const, static and type in `load` belong to the function
+        assert_eq!(found, vec![
+            ("fn", "load", 1, 0, 6),
+            ("struct", "Point", 9, 7, 10),
+            ("impl", "Point", 11, 10, 11),
+            ("fn", "fmt", 13, 11, 16),
+            ("mod", "tests", 19, 18, 19),
+        ]);
+    }
+
+    #[test]
+    fn test_nested_script_functions_belong_to_their_function() {
+        let content = "function outer() {\n  const text = `}`;\n  function inner() {}\n}\nexport function next() {}\n";
+        let lines: Vec<&str> = content.lines().collect();
+
+        let items = extract_items(Path::new("app.ts"), &lines);
+        let names: Vec<(&str, usize)> = items.iter().map(|i| (i.name.as_str(), i.end)).collect();
+
+        assert_eq!(names, vec![("outer", 4), ("next", 5)]);
+    }
+
+    #[test]
+    fn test_extract_python_items() {
+        let content = "@decorator\ndef handler():\n    \"\"\"Docstring\"\"\"\n\nclass Model:\n    def save(self):\n        pass\n";
+        let lines: Vec<&str> = content.lines().collect();
+
+        let items = extract_items(Path::new("app.py"), &lines);
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+
+        assert_eq!(names, vec!["handler", "Model", "save"]);
+        assert_eq!(items[0].start, 0);
+        assert!(items[0].is_function());
+        assert!(!items[1].is_function());
+    }
+
+    #[test]
+    fn test_unknown_language_has_no_items() {
+        let lines = vec!["fn main() {}"];
+        assert!(extract_items(Path::new("notes.txt"), &lines).is_empty());
+    }
+}
@@ -6,6 +6,7 @@
 use crate::{Result, SynapseError, graph::Graph};
 use crate::db::pool::PoolStats;
 use crate::cache::RuleCache;
+use crate::enforcement::lifecycle::ShadowHits;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, warn, instrument};
@@ -78,6 +79,10 @@ pub struct ServiceStatus {
     pub dependencies: DependencyStatus,
     pub system: SystemHealth,
     pub timestamp: u64, // Unix timestamp
+    /// Hits of `mode: shadow` rules in the server's checks, filled in from
+    /// its enforcer; empty without one
+    #[serde(default)]
+    pub shadow_hits: ShadowHits,
 }
 
 /// All dependency health statuses
@@ -485,6 +490,7 @@ impl HealthService {
             },
             system: system_health,
             timestamp,
+            shadow_hits: ShadowHits::default(),
         })
     }
     
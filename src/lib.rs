@@ -15,24 +15,32 @@ pub mod ast_analysis;
 pub mod cache;
 pub mod db;
 pub mod health;
+pub mod rule_stats;
 
 #[cfg(any(test, feature = "test-helpers"))]
 pub mod test_helpers;
 
 
-pub use models::{Node, Edge, NodeType, EdgeType, Rule, RuleSet, RuleNode, CompositeRules, RuleType, CompiledRule, PatternMatcher, Violation};
+pub use models::{Node, Edge, NodeType, EdgeType, Rule, RuleSet, RuleModification, RuleNode, CompositeRules, RuleType, CompiledRule, PatternMatcher, PatternKind, Threshold, Violation};
 pub use error::{SynapseError, Result};
 pub use cache::{CacheStats, RuleCache, CacheKey};
+pub use rule_stats::{RuleMetrics, RuleStatsRecorder, RuleStatsStore, RuleStatsReport};
 pub use config::CacheConfig;
 pub use rule_graph::{RuleGraph, RuleGraphStats};
+pub use rule_graph::diff::{RuleDiff, RuleChange, RuleChangeKind, EffectiveChange};
+pub use rule_graph::export::{GraphFormat, RuleGraphExport};
+pub use rule_graph::docs::{DocsFormat, RuleCatalog};
 pub use indexer::parse_markdown_file;
-pub use rules::{RuleSystem};
+pub use rules::{RuleSystem, ExampleExpectation, ExampleResult, LintIssue, LintSeverity, RuleResolution, RuleSourceKind};
 pub use mcp_server::{PatternEnforcer};
-pub use enforcement::check_rules;
+pub use enforcement::{check_rules, check_rules_with, group_by_owner};
+pub use enforcement::commit_msg::check_commit_message;
+pub use enforcement::prefilter::RulePrefilter;
+pub use enforcement::imports::check_project_imports;
 pub use api_models::{
     ApiRequest, ApiResponse, CheckRequest, CheckResponse, ContextRequest, ContextResponse,
     RulesForPathRequest, RulesForPathResponse, PreWriteRequest, PreWriteResponse, 
-    RuleViolationDto, RuleContextInfo, AutoFix,
+    RuleViolationDto, OwnerViolations, RuleContextInfo, AutoFix,
     CheckData, CheckResultData, ContextData, ContextResultData, RulesForPathData, RulesForPathResultData, 
     PreWriteData, PreWriteResultData
 };
@@ -40,12 +48,12 @@ pub use formatting::{
     OutputFormatter, Formattable, MarkdownFormatter, JsonFormatter, PlainFormatter,
     get_formatter, FormattableContext
 };
-pub use config::{Config, Neo4jConfig, ServerConfig, RuntimeConfig, LoggingConfig, PoolConfig};
+pub use config::{Config, Neo4jConfig, ServerConfig, RuntimeConfig, LoggingConfig, PoolConfig, DiscoveryConfig};
 pub use db::{ConnectionPool, PoolStats, PoolError, Neo4jConnectionManager};
 pub use graph::Graph;
 pub use graph_pooled::PooledGraph;
 pub use auth::{AuthMiddleware, extract_bearer_token};
-pub use ast_analysis::{AstAnalysisError, AstResult, ast_fixes_available};
+pub use ast_analysis::{AstAnalysisError, AstResult, ast_fixes_available, StructuralCheck, StructuralFinding};
 pub use health::{
     HealthService, HealthStatus, ServiceStatus, DependencyStatus, SystemHealth, 
     Neo4jHealth, CacheHealth, HealthChecker
@@ -227,11 +227,11 @@ fn build_cli() -> Command {
         .subcommand(
             Command::new("check")
                 .about("Check files against synapse rules (Write Hook)")
-                .long_about("Enforces FORBIDDEN and REQUIRED rules against specified files. Used by pre-commit hooks.")
+                .long_about("Enforces FORBIDDEN and REQUIRED rules against specified files. Used by pre-commit hooks.\n\nWith --all, checks every file under the given directories (default: current directory), honoring .gitignore and .synapseignore.")
                 .arg(
                     Arg::new("files")
-                        .help("Files to check against rules")
-                        .required(true)
+                        .help("Files to check against rules (directories to scan with --all)")
+                        .required_unless_present_any(["imports", "all"])
                         .num_args(1..)
                         .value_parser(clap::value_parser!(PathBuf))
                 )
@@ -248,6 +248,214 @@ fn build_cli() -> Command {
                         .help("Parse and check files but don't enforce (exit 0)")
                         .action(clap::ArgAction::SetTrue)
                 )
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Insert or update the license headers required by header rules")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("dry-run")
+                )
+                .arg(
+                    Arg::new("imports")
+                        .long("imports")
+                        .help("Check import/layering rules across every source file in the project")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(
+                    Arg::new("all")
+                        .long("all")
+                        .help("Check every file in the tree in parallel, skipping ignored, binary and non-UTF-8 files")
+                        .conflicts_with("imports")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(
+                    Arg::new("group-by")
+                        .long("group-by")
+                        .help("Group reported violations by file or by the owners of the violated rules")
+                        .value_parser(["file", "owner"])
+                        .default_value("file")
+                )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .help("Output format")
+                        .value_parser(["text", "json"])
+                        .default_value("text")
+                )
+        )
+        .subcommand(
+            Command::new("hook")
+                .about("Run synapse from git hooks")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("commit-msg")
+                        .about("Check a commit message against commit message rules")
+                        .long_about("Evaluates the rules marked `target: commit-message` that apply at the project root. Exits non-zero on violations; install it as git's commit-msg hook.")
+                        .arg(
+                            Arg::new("file")
+                                .help("File holding the commit message (git passes .git/COMMIT_EDITMSG)")
+                                .required(true)
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                )
+        )
+        .subcommand(
+            Command::new("explain")
+                .about("Show why rules apply to a file")
+                .long_about("Prints the rule resolution trace for a path: directories walked, contributing rule files, inherited and overridden rules, and the final precedence order.")
+                .arg(
+                    Arg::new("path")
+                        .help("File path to explain")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf))
+                )
+                .arg(
+                    Arg::new("rule")
+                        .long("rule")
+                        .help("Only explain the rule with this ID")
+                )
+        )
+        .subcommand(
+            Command::new("rules")
+                .about("Inspect and validate synapse rule files")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("lint")
+                        .about("Report problems in rule files with file and line numbers")
+                        .arg(
+                            Arg::new("path")
+                                .help("Project root to search for rule files")
+                                .default_value(".")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                )
+                .subcommand(
+                    Command::new("test")
+                        .about("Run the pass/fail examples embedded in rule files")
+                        .arg(
+                            Arg::new("path")
+                                .help("Project root to search for rule files")
+                                .default_value(".")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                )
+                .subcommand(
+                    Command::new("graph")
+                        .about("Render rule files and their relationships as a graph")
+                        .arg(
+                            Arg::new("path")
+                                .help("Project root to search for rule files")
+                                .default_value(".")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                        .arg(
+                            Arg::new("format")
+                                .short('f')
+                                .long("format")
+                                .help("Output format")
+                                .value_parser(["dot", "mermaid", "json"])
+                                .default_value("dot")
+                        )
+                )
+                .subcommand(
+                    Command::new("docs")
+                        .about("Generate a browsable catalog of every rule")
+                        .long_about("Writes an index page and one page per rule with its type, pattern, message, the directories it applies to, and related inheritance and overrides.")
+                        .arg(
+                            Arg::new("path")
+                                .help("Project root to search for rule files")
+                                .default_value(".")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                        .arg(
+                            Arg::new("out")
+                                .short('o')
+                                .long("out")
+                                .help("Directory to write the pages to")
+                                .default_value("site")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                        .arg(
+                            Arg::new("format")
+                                .short('f')
+                                .long("format")
+                                .help("Page format")
+                                .value_parser(["html", "markdown"])
+                                .default_value("html")
+                        )
+                )
+                .subcommand(
+                    Command::new("stats")
+                        .about("Report rules that never fire and rules that fire the most")
+                        .long_about("Reads the per-rule hit counts that `synapse check` and the server record in .git/synapse/rule-stats.json (.synapse/rule-stats.json outside git checkouts).")
+                        .arg(
+                            Arg::new("path")
+                                .help("Project root holding the .synapse directory")
+                                .default_value(".")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                        .arg(
+                            Arg::new("dead-after")
+                                .long("dead-after")
+                                .help("Report rules that haven't matched in this many runs")
+                                .default_value("20")
+                                .value_parser(clap::value_parser!(u64))
+                        )
+                        .arg(
+                            Arg::new("top")
+                                .long("top")
+                                .help("Number of rules with the most hits to report")
+                                .default_value("10")
+                                .value_parser(clap::value_parser!(usize))
+                        )
+                        .arg(
+                            Arg::new("format")
+                                .short('f')
+                                .long("format")
+                                .help("Output format")
+                                .value_parser(["text", "json"])
+                                .default_value("text")
+                        )
+                )
+                .subcommand(
+                    Command::new("diff")
+                        .about("Compare the rules of two git revisions")
+                        .long_about("Reads rule files from each revision without checking it out and reports rules added, removed or changed, plus changes to the rules that apply to sample paths.")
+                        .arg(
+                            Arg::new("rev-a")
+                                .help("Base revision")
+                                .required(true)
+                        )
+                        .arg(
+                            Arg::new("rev-b")
+                                .help("Revision to compare against the base")
+                                .required(true)
+                        )
+                        .arg(
+                            Arg::new("path")
+                                .short('p')
+                                .long("path")
+                                .help("Sample path to compare effective rules for, relative to the repository root (repeatable; defaults to every directory with rule files)")
+                                .action(clap::ArgAction::Append)
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                        .arg(
+                            Arg::new("repo")
+                                .long("repo")
+                                .help("Path inside the git repository")
+                                .default_value(".")
+                                .value_parser(clap::value_parser!(PathBuf))
+                        )
+                        .arg(
+                            Arg::new("format")
+                                .short('f')
+                                .long("format")
+                                .help("Output format")
+                                .value_parser(["text", "json"])
+                                .default_value("text")
+                        )
+                )
         )
         .subcommand(
             Command::new("enforce-context")
@@ -339,7 +547,7 @@ async fn run_command(matches: clap::ArgMatches) -> anyhow::Result<()> {
 
     // Check if we need to load RuleGraph for enforcement commands
     let rule_graph = match matches.subcommand() {
-        Some(("check", _)) | Some(("enforce-context", _)) => {
+        Some(("check", _)) | Some(("enforce-context", _)) | Some(("explain", _)) | Some(("hook", _)) => {
             let current_dir = std::env::current_dir()?;
             match synapse_mcp::RuleGraph::from_project(&current_dir) {
                 Ok(graph) => Some(graph),
@@ -415,6 +623,15 @@ async fn run_command(matches: clap::ArgMatches) -> anyhow::Result<()> {
         Some(("check", sub_matches)) => {
             cli::commands::check::handle_check(sub_matches, rule_graph.as_ref()).await?
         }
+        Some(("hook", sub_matches)) => {
+            cli::commands::hook::handle_hook(sub_matches, rule_graph.as_ref()).await?
+        }
+        Some(("explain", sub_matches)) => {
+            cli::commands::explain::handle_explain(sub_matches, rule_graph.as_ref()).await?
+        }
+        Some(("rules", sub_matches)) => {
+            cli::commands::rules::handle_rules(sub_matches).await?
+        }
         Some(("enforce-context", sub_matches)) => {
             cli::commands::enforce_context::handle_enforce_context(sub_matches, rule_graph.as_ref()).await?
         }
@@ -496,3 +713,17 @@ async fn run_demo() {
     println!("  • Connect your AI coding assistant via MCP protocol");
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_fix_conflicts_with_dry_run() {
+        assert!(build_cli().try_get_matches_from(["synapse", "check", "--fix", "src/main.rs"]).is_ok());
+        let error = build_cli()
+            .try_get_matches_from(["synapse", "check", "--fix", "--dry-run", "src/main.rs"])
+            .unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+}
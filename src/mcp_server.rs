@@ -486,7 +486,7 @@ async fn handle_health_check(State(state): State<ServerState>) -> Result<&'stati
 /// and is designed for monitoring and alerting systems.
 #[instrument(skip(state))]
 async fn handle_status_check(State(state): State<ServerState>) -> Result<Json<ServiceStatus>> {
-    let status = state.health_service.get_detailed_status().await
+    let mut status = state.health_service.get_detailed_status().await
         .unwrap_or_else(|e| {
             error!("Failed to get detailed status: {}", e);
             // Return a fallback status when health service fails
@@ -518,9 +518,14 @@ async fn handle_status_check(State(state): State<ServerState>) -> Result<Json<Se
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or(std::time::Duration::ZERO)
                     .as_secs(),
+                shadow_hits: Default::default(),
             }
         });
 
+    if let Some(enforcer) = &state.enforcer {
+        status.shadow_hits = enforcer.rule_graph().shadow_hits();
+    }
+
     // Log status based on health
     match status.status {
         crate::health::HealthStatus::Healthy => {
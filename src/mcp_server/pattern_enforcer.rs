@@ -1,7 +1,8 @@
-use crate::{RuleGraph, RuleType, Result, SynapseError, CompiledRule, check_rules, CheckRequest, CheckResponse, ContextRequest, ContextResponse, RulesForPathRequest, RulesForPathResponse, PreWriteRequest, PreWriteResponse, PreWriteResultData, RuleViolationDto, RuleContextInfo, CheckResultData, ContextResultData, RulesForPathResultData, AutoFix, get_formatter, Violation, RuleCache, CacheStats, CacheConfig};
+use crate::{RuleGraph, RuleType, Result, SynapseError, CheckRequest, CheckResponse, ContextRequest, ContextResponse, RulesForPathRequest, RulesForPathResponse, PreWriteRequest, PreWriteResponse, PreWriteResultData, RuleViolationDto, RuleContextInfo, CheckResultData, ContextResultData, RulesForPathResultData, AutoFix, get_formatter, Violation, RuleCache, CacheStats, CacheConfig, RuleMetrics, RuleStatsRecorder};
 
 #[cfg(feature = "ast-fixes")]
 use crate::safely_replace_unwrap;
+use crate::enforcement::header::fix_headers;
 
 use std::path::PathBuf;
 use std::time::Duration;
@@ -130,6 +131,8 @@ fn apply_auto_fixes(content: &str, fixes: &[AutoFix]) -> Option<String> {
 pub struct PatternEnforcer {
     rule_graph: RuleGraph,
     cache: Option<RuleCache>,
+    /// Per-rule hit statistics of the project the enforcer was loaded from
+    rule_stats: Option<RuleStatsRecorder>,
 }
 
 
@@ -140,6 +143,7 @@ impl PatternEnforcer {
         Ok(Self { 
             rule_graph,
             cache: None,
+            rule_stats: Some(RuleStatsRecorder::new(project_root.clone())),
         })
     }
     
@@ -156,6 +160,7 @@ impl PatternEnforcer {
         Ok(Self {
             rule_graph,
             cache: Some(cache),
+            rule_stats: Some(RuleStatsRecorder::new(project_root.clone())),
         })
     }
     
@@ -171,12 +176,14 @@ impl PatternEnforcer {
             Ok(Self {
                 rule_graph,
                 cache: Some(cache),
+                rule_stats: Some(RuleStatsRecorder::new(project_root.clone())),
             })
         } else {
             info!("Created PatternEnforcer without cache (disabled in config)");
             Ok(Self {
                 rule_graph,
                 cache: None,
+                rule_stats: Some(RuleStatsRecorder::new(project_root.clone())),
             })
         }
     }
@@ -186,6 +193,7 @@ impl PatternEnforcer {
         Self { 
             rule_graph,
             cache: None,
+            rule_stats: None,
         }
     }
     
@@ -194,6 +202,7 @@ impl PatternEnforcer {
         Self {
             rule_graph,
             cache: Some(cache),
+            rule_stats: None,
         }
     }
     
@@ -237,44 +246,46 @@ impl PatternEnforcer {
         self.cache.is_some()
     }
     
+    /// Add a request's rule hits to the project's statistics, if it has a root
+    ///
+    /// Hits are kept in memory and saved periodically and at shutdown.
+    fn record_rule_stats(&self, metrics: &RuleMetrics) {
+        if let Some(ref rule_stats) = self.rule_stats {
+            rule_stats.add(metrics);
+        }
+    }
+    
     /// Check files against rules (implements Write Hook functionality)
     pub fn check_files(&self, request: CheckRequest) -> Result<CheckResponse> {
         let mut all_violations = Vec::new();
         let mut total_rules_applied = 0;
         let dry_run = request.data.dry_run.unwrap_or(false);
+        let metrics = RuleMetrics::default();
         
         for file_path in &request.data.files {
             if !file_path.exists() {
                 continue;
             }
             
-            // Get applicable rules for this file (with optional caching)
-            let composite_rules = if let Some(ref _cache) = self.cache {
-                // For sync methods, we fall back to direct resolution for now
-                // Async cache methods are available via get_rules_for_path_cached()
-                self.rule_graph.rules_for(file_path)?
-            } else {
-                self.rule_graph.rules_for(file_path)?
-            };
-            total_rules_applied += composite_rules.applicable_rules.len();
+            // Get applicable rules for this file from the directory index
+            let resolved = self.rule_graph.resolved_for(file_path);
+            total_rules_applied += resolved.compiled.len();
             
             // Read file content
             let content = std::fs::read_to_string(file_path)
                 .map_err(|e| SynapseError::Io(e))?;
             
-            // Convert rules to CompiledRule format for enforcement
-            let compiled_rules: Vec<CompiledRule> = composite_rules.applicable_rules
-                .iter()
-                .map(|rule| CompiledRule::from_rule(rule.clone()))
-                .collect();
-            
             // Check file against rules using unified enforcement
-            let violations = check_rules(file_path, &content, &compiled_rules)?;
+            let violations = resolved.check(file_path, &content)?;
+            metrics.record_file(&resolved.compiled, &violations);
             let violation_dtos: Vec<RuleViolationDto> = violations.iter().map(|v| v.into()).collect();
             all_violations.extend(violation_dtos);
         }
+        if !dry_run {
+            self.record_rule_stats(&metrics);
+        }
         
-        let success = dry_run || all_violations.is_empty();
+        let success = dry_run || all_violations.iter().all(|v| v.warning);
         let data = CheckResultData {
             violations: all_violations,
             files_checked: request.data.files.len(),
@@ -364,16 +375,13 @@ impl PatternEnforcer {
         let content = &request.data.content;
         
         // Get applicable rules for this file path
-        let composite_rules = self.rule_graph.rules_for(file_path)?;
-        
-        // Convert rules to CompiledRule format for enforcement
-        let compiled_rules: Vec<CompiledRule> = composite_rules.applicable_rules
-            .iter()
-            .map(|rule| CompiledRule::from_rule(rule.clone()))
-            .collect();
+        let resolved = self.rule_graph.resolved_for(file_path);
         
         // Check content against rules
-        let violations = check_rules(file_path, content, &compiled_rules)?;
+        let violations = resolved.check(file_path, content)?;
+        let metrics = RuleMetrics::default();
+        metrics.record_file(&resolved.compiled, &violations);
+        self.record_rule_stats(&metrics);
         
         // Generate auto-fix suggestions for violations
         let auto_fixes = if !violations.is_empty() {
@@ -388,8 +396,11 @@ impl PatternEnforcer {
         } else {
             None
         };
+        // Header fixes rewrite the top of the file rather than replace a pattern
+        let fixed_content = fix_headers(file_path, fixed_content.as_deref().unwrap_or(content), &violations, &resolved.compiled)
+            .or(fixed_content);
         
-        let is_valid = violations.is_empty();
+        let is_valid = violations.iter().all(|v| v.warning);
         let violation_dtos = violations.iter().map(RuleViolationDto::from).collect();
         
         Ok(PreWriteResponse::success(PreWriteResultData {
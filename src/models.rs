@@ -3,7 +3,15 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use uuid::Uuid;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use globset::GlobMatcher;
+use crate::ast_analysis::StructuralCheck;
+use crate::enforcement::lifecycle;
+use crate::enforcement::header::HeaderTemplate;
+use crate::enforcement::imports::project_glob;
+use crate::enforcement::naming::FileGlob;
+use crate::enforcement::secrets::SecretDetector;
+use chrono::NaiveDate;
 
 /// Node types in the Synapse knowledge graph
 /// 
@@ -150,6 +158,7 @@ pub enum RuleType {
 ///     message: "Use logging instead of println!".to_string(),
 ///     tags: vec!["logging".to_string()],
 ///     metadata: std::collections::HashMap::new(),
+///     owners: vec![],
 /// };
 /// ```
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -161,15 +170,57 @@ pub struct Rule {
     pub message: String,
     pub tags: Vec<String>,
     pub metadata: HashMap<String, String>,
+    /// Owners of the rule file the rule was defined in (`owners:` frontmatter)
+    ///
+    /// Provenance only: owners don't change what a rule checks, so they are
+    /// left out when rules are compared.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RuleSet {
     pub path: PathBuf,
     pub inherits: Vec<PathBuf>,
+    /// `extends:` entries naming rule packs (`builtin:<name>`, `pack:<dir>`)
+    #[serde(default)]
+    pub extends: Vec<String>,
     pub overrides: Vec<String>,  // Rule IDs to override
+    /// Overrides that change a rule's type or message instead of removing it
+    #[serde(default)]
+    pub modifications: Vec<RuleModification>,
     pub rules: Vec<Rule>,
     pub metadata: HashMap<String, String>,
+    /// Directory the rules apply to when it doesn't follow from the file's
+    /// location, as for org-wide policies loaded from outside the project
+    #[serde(default)]
+    pub governs: Option<PathBuf>,
+    /// Teams or people responsible for the rules (`owners:` frontmatter)
+    #[serde(default)]
+    pub owners: Vec<String>,
+}
+
+/// A modifying override (`overrides: [{rule: <id>, severity: standard}]`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RuleModification {
+    /// ID or generated name of the rule to modify
+    pub rule_id: String,
+    pub rule_type: Option<RuleType>,
+    pub message: Option<String>,
+}
+
+impl RuleModification {
+    /// Apply the modification to a copy of a rule
+    pub fn apply(&self, rule: &Rule) -> Rule {
+        let mut modified = rule.clone();
+        if let Some(rule_type) = &self.rule_type {
+            modified.rule_type = rule_type.clone();
+        }
+        if let Some(message) = &self.message {
+            modified.message = message.clone();
+        }
+        modified
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -186,12 +237,78 @@ pub struct RuleNode {
 pub enum PatternMatcher {
     Regex(Regex),
     Literal(String),
+    /// Built-in syntax tree check selected with `FORBIDDEN[ast]: <check-name>`
+    Structural(StructuralCheck),
+    /// File size or line length limit (`MAX_LINES: 500`, `MAX_LINE_LENGTH: 120`)
+    Threshold(Threshold),
+    /// Project path glob of an `[import]` rule (`src/infra/**`)
+    Glob(GlobMatcher),
+    /// Parsed template of a `REQUIRED[header]` rule
+    Header(Arc<HeaderTemplate>),
+}
+
+/// Size limits that are checked without a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threshold {
+    /// Maximum number of lines in a file
+    MaxLines(usize),
+    /// Maximum number of characters in a single line
+    MaxLineLength(usize),
+}
+
+impl Threshold {
+    /// Parse a threshold from its rule keyword and limit
+    pub fn from_keyword(keyword: &str, limit: usize) -> Option<Self> {
+        match keyword {
+            "max_lines" => Some(Threshold::MaxLines(limit)),
+            "max_line_length" => Some(Threshold::MaxLineLength(limit)),
+            _ => None,
+        }
+    }
+}
+
+/// How a rule's pattern is interpreted, chosen with a `regex:`, `literal:`
+/// or `word:` prefix in the rule file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternKind {
+    /// Regular expression (the default for patterns that are valid regexes)
+    Regex,
+    /// Exact substring
+    Literal,
+    /// Exact text on word boundaries
+    Word,
+}
+
+impl PatternKind {
+    /// Look up a kind by its prefix name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "regex" => Some(PatternKind::Regex),
+            "literal" => Some(PatternKind::Literal),
+            "word" => Some(PatternKind::Word),
+            _ => None,
+        }
+    }
+
+    /// Name used as the pattern prefix and in rule metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatternKind::Regex => "regex",
+            PatternKind::Literal => "literal",
+            PatternKind::Word => "word",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct CompiledRule {
     pub rule: Arc<Rule>,
     pub matcher: PatternMatcher,
+    /// Compiled `files:` glob, if the rule has one
+    pub files: Option<FileGlob>,
+    /// Canonical directory the rule's file governs; `files:` globs and
+    /// `[path]` patterns are matched relative to it
+    pub base: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -200,6 +317,9 @@ pub struct Violation {
     pub rule: Arc<Rule>,
     pub line_number: Option<usize>,
     pub line_content: Option<String>,
+    /// Reported but not failing, because the rule is not enforced yet
+    /// (`enforce_from` in the future)
+    pub warning: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -282,6 +402,7 @@ impl Rule {
             message,
             tags: Vec::new(),
             metadata: HashMap::new(),
+            owners: Vec::new(),
         }
     }
 
@@ -290,11 +411,74 @@ impl Rule {
         self
     }
 
+    pub fn with_owners(mut self, owners: Vec<String>) -> Self {
+        self.owners = owners;
+        self
+    }
+
     pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
         self.metadata = metadata;
         self
     }
 
+    /// Whether this rule is evaluated on the syntax tree (`FORBIDDEN[ast]: ...`)
+    pub fn is_structural(&self) -> bool {
+        self.metadata.get("engine").map(String::as_str) == Some("ast")
+    }
+
+    /// Whether this rule runs a secret detector (`FORBIDDEN[secret]: ...`)
+    pub fn is_secret(&self) -> bool {
+        self.metadata.get("target").map(String::as_str) == Some("secret")
+    }
+
+    /// Whether this rule checks a file header template (`REQUIRED[header]: ...`)
+    pub fn is_header(&self) -> bool {
+        self.metadata.get("target").map(String::as_str) == Some("header")
+    }
+
+    /// How the pattern is interpreted (`kind` metadata)
+    ///
+    /// Unprefixed patterns are regexes, or literal text when they aren't a
+    /// valid regex; an explicit `regex:` must be valid.
+    pub fn pattern_kind(&self) -> PatternKind {
+        match self.metadata.get("kind").and_then(|kind| PatternKind::from_name(kind)) {
+            Some(kind) => kind,
+            None if self.is_literal_fallback() => PatternKind::Literal,
+            None => PatternKind::Regex,
+        }
+    }
+
+    /// Whether the unprefixed pattern is matched as literal text because it
+    /// isn't a valid regex (`println!(`)
+    pub fn is_literal_fallback(&self) -> bool {
+        !self.metadata.contains_key("kind") && Regex::new(&self.pattern).is_err()
+    }
+
+    /// Whether the pattern matches case-insensitively (`[ignore-case]`)
+    pub fn ignores_case(&self) -> bool {
+        self.metadata.get("ignore_case").map(String::as_str) == Some("true")
+    }
+
+    /// Date from which violations fail checks (`enforce_from` metadata)
+    pub fn enforce_from(&self) -> Option<NaiveDate> {
+        self.metadata.get("enforce_from").and_then(|date| lifecycle::parse_date(date).ok())
+    }
+
+    /// Date from which the rule is no longer checked (`expires` metadata)
+    pub fn expires(&self) -> Option<NaiveDate> {
+        self.metadata.get("expires").and_then(|date| lifecycle::parse_date(date).ok())
+    }
+
+    /// Whether hits are only logged and counted (`mode: shadow`)
+    pub fn is_shadow(&self) -> bool {
+        self.metadata.get("mode").map(String::as_str) == Some("shadow")
+    }
+
+    /// Owners of the rule file the rule was defined in
+    pub fn owners(&self) -> Vec<&str> {
+        self.owners.iter().map(String::as_str).collect()
+    }
+
     pub fn validate(&self) -> crate::Result<()> {
         if self.name.trim().is_empty() {
             return Err(crate::SynapseError::Validation("Rule name cannot be empty".to_string()));
@@ -305,6 +489,20 @@ impl Rule {
         if self.message.trim().is_empty() {
             return Err(crate::SynapseError::Validation("Rule message cannot be empty".to_string()));
         }
+        if self.is_structural() && StructuralCheck::from_name(&self.pattern).is_none() {
+            return Err(crate::SynapseError::Validation(format!(
+                "Unknown structural check '{}'", self.pattern
+            )));
+        }
+        if self.is_secret() && SecretDetector::from_name(&self.pattern).is_none() {
+            return Err(crate::SynapseError::Validation(format!(
+                "Unknown secret detector '{}'", self.pattern
+            )));
+        }
+        if self.is_header() {
+            HeaderTemplate::parse(&self.pattern)
+                .map_err(|e| crate::SynapseError::Validation(e.to_string()))?;
+        }
         Ok(())
     }
 }
@@ -314,9 +512,13 @@ impl RuleSet {
         Self {
             path,
             inherits: Vec::new(),
+            extends: Vec::new(),
             overrides: Vec::new(),
+            modifications: Vec::new(),
             rules: Vec::new(),
             metadata: HashMap::new(),
+            governs: None,
+            owners: Vec::new(),
         }
     }
 
@@ -325,11 +527,31 @@ impl RuleSet {
         self
     }
 
+    pub fn with_extends(mut self, extends: Vec<String>) -> Self {
+        self.extends = extends;
+        self
+    }
+
+    pub fn with_governs(mut self, governs: PathBuf) -> Self {
+        self.governs = Some(governs);
+        self
+    }
+
+    pub fn with_owners(mut self, owners: Vec<String>) -> Self {
+        self.owners = owners;
+        self
+    }
+
     pub fn with_overrides(mut self, overrides: Vec<String>) -> Self {
         self.overrides = overrides;
         self
     }
 
+    pub fn with_modifications(mut self, modifications: Vec<RuleModification>) -> Self {
+        self.modifications = modifications;
+        self
+    }
+
     pub fn add_rule(mut self, rule: Rule) -> Self {
         self.rules.push(rule);
         self
@@ -340,6 +562,11 @@ impl RuleSet {
         self
     }
 
+    /// Whether the frontmatter sets `root: true`, which stops the walk up the tree
+    pub fn is_root(&self) -> bool {
+        self.metadata.get("root").map(String::as_str) == Some("true")
+    }
+
     pub fn validate(&self) -> crate::Result<()> {
         for rule in &self.rules {
             rule.validate()?;
@@ -405,21 +632,112 @@ impl Default for CompositeRules {
 impl CompiledRule {
     pub fn new(rule: Rule, matcher: PatternMatcher) -> Self {
         Self {
+            files: FileGlob::of(&rule),
             rule: Arc::new(rule),
             matcher,
+            base: None,
         }
     }
 
+    /// Match the rule's `files:` glob and `[path]` pattern relative to
+    /// `dir`, the canonical directory its rule file governs
+    pub fn with_files_base(mut self, dir: PathBuf) -> Self {
+        self.files = self.files.map(|files| files.with_base(dir.clone()));
+        self.base = Some(dir);
+        self
+    }
+
+    /// Compile a rule whose pattern is known to be valid
+    ///
+    /// Rules loaded from rule files are validated with [`CompiledRule::try_from_rule`]
+    /// at parse time; use it for any rule that may hold an invalid `regex:` pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rule's explicit `regex:` pattern is invalid.
     pub fn from_rule(rule: Rule) -> Self {
-        let pattern = rule.pattern.clone(); // Clone once upfront
-        let matcher = match Regex::new(&pattern) {
-            Ok(regex) => PatternMatcher::Regex(regex),
-            Err(_) => PatternMatcher::Literal(pattern), // Move instead of clone
+        Self::try_from_rule(rule).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Compile a rule, reporting an invalid `regex:` pattern instead of degrading it
+    pub fn try_from_rule(rule: Rule) -> crate::Result<Self> {
+        let matcher = Self::build_matcher(&rule).map_err(|e| crate::SynapseError::Parse(format!(
+            "Invalid regex `{}` in rule '{}' (use `literal:` instead of `regex:` to match it exactly): {}",
+            rule.pattern, rule.name, e
+        )))?;
+        Ok(Self::new(rule, matcher))
+    }
+
+    fn build_matcher(rule: &Rule) -> Result<PatternMatcher, regex::Error> {
+        let check = rule.is_structural()
+            .then(|| StructuralCheck::from_name(&rule.pattern))
+            .flatten();
+        if let Some(check) = check {
+            return Ok(PatternMatcher::Structural(check));
+        }
+
+        let threshold = rule.metadata.get("threshold")
+            .zip(rule.pattern.parse().ok())
+            .and_then(|(keyword, limit)| Threshold::from_keyword(keyword, limit));
+        if let Some(threshold) = threshold {
+            return Ok(PatternMatcher::Threshold(threshold));
+        }
+
+        // Secret rules name a built-in detector
+        let detector = rule.is_secret()
+            .then(|| SecretDetector::from_name(&rule.pattern))
+            .flatten();
+        if let Some(detector) = detector {
+            return Ok(PatternMatcher::Regex(detector.regex().clone()));
+        }
+
+        // Header rules hold a template, matched by the header module; an
+        // invalid template stays a literal, which no header matches
+        if rule.is_header() {
+            return Ok(HeaderTemplate::from_rule(rule)
+                .map_or_else(|| PatternMatcher::Literal(rule.pattern.clone()), |template| PatternMatcher::Header(Arc::new(template))));
+        }
+
+        // Import rules match path patterns as globs, not regexes
+        let is_import_glob = rule.metadata.get("target").map(String::as_str) == Some("import")
+            && rule.pattern.contains('/');
+        if is_import_glob {
+            // An invalid glob stays a literal, which matches no import path
+            return Ok(project_glob(&rule.pattern)
+                .map_or_else(|| PatternMatcher::Literal(rule.pattern.clone()), PatternMatcher::Glob));
+        }
+
+        let source = match rule.pattern_kind() {
+            PatternKind::Literal if !rule.ignores_case() => {
+                return Ok(PatternMatcher::Literal(rule.pattern.clone()));
+            }
+            PatternKind::Literal => regex::escape(&rule.pattern),
+            PatternKind::Word => word_regex(&rule.pattern),
+            PatternKind::Regex => rule.pattern.clone(),
         };
-        Self::new(rule, matcher)
+        // Dependency rules match whole package names
+        let source = if rule.metadata.get("target").map(String::as_str) == Some("dependency") {
+            format!("^(?:{})$", source)
+        } else {
+            source
+        };
+        RegexBuilder::new(&source)
+            .case_insensitive(rule.ignores_case())
+            .build()
+            .map(PatternMatcher::Regex)
     }
 }
 
+/// Regex for a `word:` pattern
+///
+/// Word boundaries are only added on the sides that are word characters:
+/// `\b` never matches next to `!` in `dbg!` when a space or `(` follows.
+fn word_regex(text: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let boundary = |c| if is_word(c) { r"\b" } else { "" };
+    format!("{}{}{}", boundary(text.chars().next()), regex::escape(text), boundary(text.chars().last()))
+}
+
 impl Violation {
     pub fn new(
         file_path: PathBuf,
@@ -432,9 +750,15 @@ impl Violation {
             rule,
             line_number,
             line_content,
+            warning: false,
         }
     }
 
+    pub fn into_warning(mut self) -> Self {
+        self.warning = true;
+        self
+    }
+
     pub fn from_compiled_rule(
         file_path: PathBuf,
         compiled_rule: &CompiledRule,
@@ -602,6 +926,10 @@ mod tests {
         match compiled_rule.matcher {
             PatternMatcher::Regex(_) => {}, // Success
             PatternMatcher::Literal(_) => panic!("Expected regex, got literal"),
+            PatternMatcher::Structural(_) => panic!("Expected regex, got structural"),
+            PatternMatcher::Threshold(_) => panic!("Expected regex, got threshold"),
+            PatternMatcher::Glob(_) => panic!("Expected regex, got glob"),
+            PatternMatcher::Header(_) => panic!("Expected regex, got header"),
         }
     }
     
@@ -621,9 +949,88 @@ mod tests {
                 assert_eq!(pattern, "[invalid regex");
             },
             PatternMatcher::Regex(_) => panic!("Expected literal fallback, got regex"),
+            PatternMatcher::Structural(_) => panic!("Expected literal fallback, got structural"),
+            PatternMatcher::Threshold(_) => panic!("Expected literal fallback, got threshold"),
+            PatternMatcher::Glob(_) => panic!("Expected literal fallback, got glob"),
+            PatternMatcher::Header(_) => panic!("Expected literal fallback, got header"),
         }
     }
     
+    #[test]
+    fn test_try_from_rule_rejects_invalid_explicit_regex() {
+        let rule = Rule::new(
+            "bad-pattern".to_string(),
+            RuleType::Forbidden,
+            "[invalid regex".to_string(),
+            "This has a bad pattern".to_string(),
+        );
+        assert!(CompiledRule::try_from_rule(rule.clone()).is_ok());
+
+        let mut metadata = HashMap::new();
+        metadata.insert("kind".to_string(), "regex".to_string());
+        assert!(CompiledRule::try_from_rule(rule.with_metadata(metadata)).is_err());
+    }
+
+    #[test]
+    fn test_explicit_pattern_kinds() {
+        let rule_with = |pattern: &str, kind: &str, ignore_case: bool| {
+            let mut metadata = HashMap::new();
+            metadata.insert("kind".to_string(), kind.to_string());
+            if ignore_case {
+                metadata.insert("ignore_case".to_string(), "true".to_string());
+            }
+            let rule = Rule::new("kinds".to_string(), RuleType::Forbidden, pattern.to_string(), "Kinds".to_string())
+                .with_metadata(metadata);
+            CompiledRule::try_from_rule(rule).unwrap()
+        };
+
+        let literal = rule_with("println!(", "literal", false);
+        assert!(matches!(literal.matcher, PatternMatcher::Literal(ref p) if p == "println!("));
+
+        let PatternMatcher::Regex(word) = rule_with("unwrap", "word", false).matcher else {
+            panic!("Expected word pattern to compile to a regex");
+        };
+        assert!(word.is_match("x.unwrap()"));
+        assert!(!word.is_match("x.unwrap_or(1)"));
+
+        // Boundaries only apply next to word characters
+        let PatternMatcher::Regex(macro_call) = rule_with("dbg!", "word", false).matcher else {
+            panic!("Expected word pattern to compile to a regex");
+        };
+        assert!(macro_call.is_match("let y = dbg!(x);"));
+        assert!(macro_call.is_match("dbg! (x)"));
+        assert!(!macro_call.is_match("my_dbg!(x)"));
+        let PatternMatcher::Regex(method) = rule_with(".unwrap()", "word", false).matcher else {
+            panic!("Expected word pattern to compile to a regex");
+        };
+        assert!(method.is_match("x.unwrap();"));
+
+        let PatternMatcher::Regex(insensitive) = rule_with("todo", "literal", true).matcher else {
+            panic!("Expected case-insensitive literal to compile to a regex");
+        };
+        assert!(insensitive.is_match("// TODO: fix"));
+    }
+
+    #[test]
+    fn test_compiled_rule_with_structural_check() {
+        let mut metadata = HashMap::new();
+        metadata.insert("engine".to_string(), "ast".to_string());
+        let rule = Rule::new(
+            "no-bare-unsafe".to_string(),
+            RuleType::Forbidden,
+            "unsafe-without-safety-comment".to_string(),
+            "Document unsafe blocks".to_string(),
+        )
+        .with_metadata(metadata);
+
+        let compiled_rule = CompiledRule::from_rule(rule);
+
+        assert!(matches!(
+            compiled_rule.matcher,
+            PatternMatcher::Structural(StructuralCheck::UnsafeWithoutSafetyComment)
+        ));
+    }
+
     #[test]
     fn test_violation_creation() {
         let rule = Rule::new(
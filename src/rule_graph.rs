@@ -1,6 +1,17 @@
 use crate::{RuleSet, CompositeRules, RuleSystem, Rule, Result};
+use crate::rules::RuleResolution;
+use crate::rules::discovery::RuleDiscovery;
+use crate::enforcement::lifecycle::{ShadowCounter, ShadowHits};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub mod diff;
+pub mod docs;
+pub mod export;
+pub mod index;
+
+use index::{ResolvedRules, RuleIndex};
 
 /// In-memory graph representing rule relationships for fast lookups
 /// 
@@ -12,19 +23,24 @@ use std::path::PathBuf;
 /// 
 /// The graph stores rule sets by directory path and uses directory traversal
 /// to build inheritance chains. Rules from parent directories are inherited
-/// by children, with explicit override support.
+/// by children, with explicit override support. The resolved rules of every
+/// directory with rule files are precomputed in a [`RuleIndex`].
 /// 
 /// # Performance Characteristics
 /// 
 /// * Construction: O(n * m) where n = number of directories, m = average rules per directory
-/// * Rule lookup: O(d * r) where d = directory depth, r = rules per directory  
-/// * Memory usage: O(total rules) - rules are shared via Arc when possible
+/// * Rule lookup: O(d) where d = directory depth, plus one `canonicalize()` call
+/// * Memory usage: O(total rules) - compiled rules are shared via Arc
 #[derive(Debug)]
 pub struct RuleGraph {
     /// Maps file paths to their RuleSet
     rule_sets: HashMap<PathBuf, RuleSet>,
     /// Rule discovery and parsing system
     rule_system: RuleSystem,
+    /// Resolved rules by directory, rebuilt whenever `rule_sets` changes
+    index: RuleIndex,
+    /// Hits of shadow rules in checks against this graph
+    shadow_hits: Arc<ShadowCounter>,
 }
 
 impl RuleGraph {
@@ -33,6 +49,8 @@ impl RuleGraph {
         Self {
             rule_sets: HashMap::new(),
             rule_system: RuleSystem::new(),
+            index: RuleIndex::default(),
+            shadow_hits: Arc::default(),
         }
     }
 
@@ -40,6 +58,8 @@ impl RuleGraph {
     /// 
     /// Discovers all `.synapse.md` files recursively and parses them,
     /// building a complete graph of rule relationships with inheritance.
+    /// Discovery follows the `[discovery]` section of the project's
+    /// config.toml (rule directory names, excludes, external policies).
     /// 
     /// # Arguments
     /// 
@@ -75,28 +95,55 @@ impl RuleGraph {
     /// # Ok::<(), synapse_mcp::SynapseError>(())
     /// ```
     pub fn from_project(root: &PathBuf) -> Result<Self> {
-        let rule_system = RuleSystem::new();
+        let rule_system = RuleSystem::for_project(root);
         let rule_sets = rule_system.load_rules(root)?;
         
-        // Build map of file paths to rule sets for fast lookup
-        let mut rule_sets_map = HashMap::new();
-        for rule_set in rule_sets {
-            rule_sets_map.insert(rule_set.path.clone(), rule_set);
-        }
-        
-        Ok(Self {
-            rule_sets: rule_sets_map,
+        let mut graph = Self {
+            rule_sets: HashMap::new(),
             rule_system,
-        })
+            index: RuleIndex::default(),
+            shadow_hits: Arc::default(),
+        };
+        graph.add_rule_sets(rule_sets);
+        Ok(graph)
     }
 
     /// Get all applicable rules for a given file path
     /// 
-    /// This walks up the directory tree from the target path, collecting
-    /// rules from each level and applying inheritance and override logic.
-    pub fn rules_for(&self, path: &PathBuf) -> Result<CompositeRules> {
+    /// The rules come from the directory index: inheritance and override
+    /// logic was applied when the graph was built.
+    pub fn rules_for(&self, path: &Path) -> Result<CompositeRules> {
+        Ok(self.index.lookup(path).composite.clone())
+    }
+
+    /// Applicable rules for a file path with their compiled matchers
+    ///
+    /// Prefer this over [`rules_for`](Self::rules_for) when checking content:
+    /// nothing is cloned or compiled per call.
+    pub fn resolved_for(&self, path: &Path) -> Arc<ResolvedRules> {
+        self.index.lookup(path)
+    }
+
+    /// Shadow rule hits counted by checks against this graph
+    pub fn shadow_hits(&self) -> ShadowHits {
+        self.shadow_hits.hits()
+    }
+
+    /// Rebuild the directory index from the current rule sets
+    fn reindex(&mut self) {
+        let rule_sets: Vec<RuleSet> = self.rule_sets.values().cloned().collect();
+        self.index = RuleIndex::build(&self.rule_system, &rule_sets, &self.shadow_hits);
+    }
+
+    /// Trace how the rules for a path are resolved, for `synapse explain`
+    pub fn explain(&self, path: &Path) -> Result<RuleResolution> {
         let rule_sets: Vec<RuleSet> = self.rule_sets.values().cloned().collect();
-        Ok(self.rule_system.rules_for_path(path, &rule_sets))
+        Ok(self.rule_system.resolve_path(path, &rule_sets))
+    }
+
+    /// Rule discovery settings the graph was loaded with
+    pub fn discovery(&self) -> &RuleDiscovery {
+        self.rule_system.discovery()
     }
 
     /// Get the number of rule nodes in the graph
@@ -116,15 +163,33 @@ impl RuleGraph {
 
     /// Add a new rule set to the graph
     /// 
-    /// This is useful for testing or dynamic rule loading
+    /// This is useful for testing or dynamic rule loading. Each call
+    /// rebuilds the directory index; use [`add_rule_sets`](Self::add_rule_sets)
+    /// to load many rule sets at once.
     pub fn add_rule_set(&mut self, rule_set: RuleSet) {
-        let path = rule_set.path.clone();
-        self.rule_sets.insert(path, rule_set);
+        self.add_rule_sets(std::iter::once(rule_set));
+    }
+
+    /// Add several rule sets, rebuilding the directory index once
+    pub fn add_rule_sets(&mut self, rule_sets: impl IntoIterator<Item = RuleSet>) {
+        for rule_set in rule_sets {
+            self.rule_sets.insert(rule_set.path.clone(), rule_set);
+        }
+        self.reindex();
     }
 
     /// Remove a rule set from the graph
     pub fn remove_rule_set(&mut self, path: &PathBuf) -> Option<RuleSet> {
-        self.rule_sets.remove(path)
+        self.remove_rule_sets(std::slice::from_ref(path)).pop()
+    }
+
+    /// Remove several rule sets, rebuilding the directory index once
+    ///
+    /// Returns the rule sets that were in the graph.
+    pub fn remove_rule_sets(&mut self, paths: &[PathBuf]) -> Vec<RuleSet> {
+        let removed = paths.iter().filter_map(|path| self.rule_sets.remove(path)).collect();
+        self.reindex();
+        removed
     }
 
     /// Check if a rule set exists at the given path
@@ -156,24 +221,14 @@ impl RuleGraph {
 
     /// Get statistics about the rule graph
     pub fn stats(&self) -> RuleGraphStats {
-        let total_rules = self.rule_sets.values()
-            .map(|rs| rs.rules.len())
-            .sum();
-            
-        let total_inheritance_relationships = self.rule_sets.values()
-            .map(|rs| rs.inherits.len())
-            .sum();
-            
-        let total_overrides = self.rule_sets.values()
-            .map(|rs| rs.overrides.len())
-            .sum();
-
-        RuleGraphStats {
-            rule_files: self.rule_sets.len(),
-            total_rules,
-            inheritance_relationships: total_inheritance_relationships,
-            override_relationships: total_overrides,
-        }
+        self.rule_sets.values()
+            .map(RuleGraphStats::for_rule_set)
+            .fold(RuleGraphStats::default(), |total, stats| RuleGraphStats {
+                rule_files: total.rule_files + stats.rule_files,
+                total_rules: total.total_rules + stats.total_rules,
+                inheritance_relationships: total.inheritance_relationships + stats.inheritance_relationships,
+                override_relationships: total.override_relationships + stats.override_relationships,
+            })
     }
 }
 
@@ -184,7 +239,7 @@ impl Default for RuleGraph {
 }
 
 /// Statistics about a RuleGraph
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
 pub struct RuleGraphStats {
     pub rule_files: usize,
     pub total_rules: usize,
@@ -337,6 +392,47 @@ mod tests {
         assert!(paths.contains(&&path2));
     }
 
+    #[test]
+    fn test_add_remove_rule_sets_in_batch() {
+        let project = TestProject::new().unwrap();
+        project.add_file("src/main.rs", "fn main() {}").unwrap();
+        let rule = |name: &str| Rule::new(name.to_string(), RuleType::Forbidden, name.to_string(), "message".to_string());
+        let nested = project.path("src/.synapse.md");
+
+        let mut graph = RuleGraph::new();
+        graph.add_rule_sets(vec![
+            RuleSet::new(project.path(".synapse.md")).add_rule(rule("root-rule")),
+            RuleSet::new(nested.clone()).add_rule(rule("src-rule")),
+        ]);
+        assert_eq!(graph.node_count(), 2);
+        let rules = graph.rules_for(&project.path("src/main.rs")).unwrap();
+        assert_eq!(rules.applicable_rules.len(), 2);
+
+        let removed = graph.remove_rule_sets(&[nested, project.path("missing/.synapse.md")]);
+        assert_eq!(removed.len(), 1);
+        let rules = graph.rules_for(&project.path("src/main.rs")).unwrap();
+        assert_eq!(rules.applicable_rules.len(), 1);
+        assert_eq!(rules.applicable_rules[0].name, "root-rule");
+    }
+
+    #[test]
+    fn test_shadow_hits_are_counted_per_graph() {
+        let project = TestProject::new().unwrap();
+        let mut rule = Rule::new("shadow-todo".to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string());
+        rule.metadata.insert("mode".to_string(), "shadow".to_string());
+        let mut graph = RuleGraph::new();
+        graph.add_rule_sets(vec![RuleSet::new(project.path(".synapse.md")).add_rule(rule)]);
+
+        let path = project.path("src/lib.rs");
+        assert!(graph.resolved_for(&path).check(&path, "// TODO\n// TODO\n").unwrap().is_empty());
+        // Reindexing keeps the counts
+        graph.add_rule_sets(vec![RuleSet::new(project.path("docs/.synapse.md"))]);
+        graph.resolved_for(&path).check(&path, "// TODO\n").unwrap();
+
+        assert_eq!(graph.shadow_hits().by_rule["shadow-todo"], 3);
+        assert_eq!(RuleGraph::new().shadow_hits().total, 0);
+    }
+
     // Integration test with file system
     #[test]
     fn test_from_project_empty_directory() {
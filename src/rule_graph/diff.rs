@@ -0,0 +1,380 @@
+//! Rule changes between two git revisions (`synapse rules diff`)
+//!
+//! Each revision's rule files are read from the git object store with
+//! `git ls-tree` and `git cat-file --batch`, without touching the working
+//! tree, and written to a scratch directory that a `RuleGraph` is built from.
+//! Every markdown file and `config.toml` is materialized, which covers rule
+//! files, local packs and discovery settings.
+//!
+//! Rules are matched across revisions by rule file and pattern, so a rule whose
+//! severity or message changed is reported as changed rather than as a removal
+//! plus an addition. Effective rules are compared for a set of sample paths:
+//! every directory with rule files in either revision, unless paths are given.
+
+use super::RuleGraph;
+use crate::models::{Rule, RuleType};
+use crate::{Result, SynapseError};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A revision's rule files, materialized in a scratch directory that is
+/// removed on drop
+#[derive(Debug)]
+pub struct RevisionTree {
+    pub rev: String,
+    pub root: PathBuf,
+}
+
+impl RevisionTree {
+    /// Write the markdown files and `config.toml` of `rev` to a scratch directory
+    pub fn materialize(repo: &Path, rev: &str) -> Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "synapse-rules-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&root)?;
+        let tree = Self { rev: rev.to_string(), root: root.canonicalize()? };
+
+        let listing = git(repo, &["ls-tree", "-r", "-z", "--full-tree", rev], None)?;
+        let blobs: Vec<(String, String)> = listing
+            .split(|byte| *byte == 0)
+            .filter_map(|entry| {
+                let entry = std::str::from_utf8(entry).ok()?;
+                let (meta, path) = entry.split_once('\t')?;
+                let mut fields = meta.split(' ');
+                let (mode, kind, oid) = (fields.next()?, fields.next()?, fields.next()?);
+                let wanted = kind == "blob"
+                    && mode != "120000"
+                    && (path.ends_with(".md") || path == "config.toml");
+                wanted.then(|| (oid.to_string(), path.to_string()))
+            })
+            .collect();
+        if blobs.is_empty() {
+            return Ok(tree);
+        }
+
+        let request: String = blobs.iter().map(|(oid, _)| format!("{}\n", oid)).collect();
+        let contents = git(repo, &["cat-file", "--batch"], Some(request.into_bytes()))?;
+        let mut reader = BufReader::new(contents.as_slice());
+        for (_, path) in &blobs {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            let size: usize = header.split_whitespace()
+                .nth(2)
+                .and_then(|size| size.parse().ok())
+                .ok_or_else(|| SynapseError::Internal(format!("Unexpected git cat-file output: {}", header.trim())))?;
+            let mut content = vec![0; size + 1];
+            reader.read_exact(&mut content)?;
+            content.truncate(size);
+
+            let target = tree.root.join(path);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(target, content)?;
+        }
+
+        Ok(tree)
+    }
+}
+
+impl Drop for RevisionTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Run git in `repo`, optionally feeding `input` on stdin
+fn git(repo: &Path, args: &[&str], input: Option<Vec<u8>>) -> Result<Vec<u8>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Feed stdin from another thread so large outputs can't deadlock
+    let writer = input.zip(child.stdin.take()).map(|(input, mut stdin)| {
+        std::thread::spawn(move || stdin.write_all(&input))
+    });
+    let output = child.wait_with_output()?;
+    if let Some(writer) = writer {
+        writer.join().map_err(|_| SynapseError::Internal("git stdin writer panicked".to_string()))??;
+    }
+
+    if !output.status.success() {
+        return Err(SynapseError::BadRequest(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// How a rule differs between the two revisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A rule added, removed or changed in a rule file
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleChange {
+    /// Rule file, relative to the repository root
+    pub file: PathBuf,
+    pub kind: RuleChangeKind,
+    pub before: Option<Rule>,
+    pub after: Option<Rule>,
+}
+
+/// Difference in the rules that apply to a sample path
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveChange {
+    pub path: PathBuf,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Rule changes between two revisions
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleDiff {
+    pub from: String,
+    pub to: String,
+    pub rules: Vec<RuleChange>,
+    pub effective: Vec<EffectiveChange>,
+}
+
+impl RuleDiff {
+    /// Compare the rules of two revisions of the repository at `repo`
+    ///
+    /// `sample_paths` are relative to the repository root; when empty, every
+    /// directory with rule files in either revision is sampled.
+    pub fn between(repo: &Path, from: &str, to: &str, sample_paths: &[PathBuf]) -> Result<Self> {
+        let toplevel = git(repo, &["rev-parse", "--show-toplevel"], None)?;
+        let repo = PathBuf::from(String::from_utf8_lossy(&toplevel).trim());
+
+        let before_tree = RevisionTree::materialize(&repo, from)?;
+        let after_tree = RevisionTree::materialize(&repo, to)?;
+        let before = RuleGraph::from_project(&before_tree.root)?;
+        let after = RuleGraph::from_project(&after_tree.root)?;
+
+        let rules = diff_rules(&rules_by_file(&before, &before_tree.root), &rules_by_file(&after, &after_tree.root));
+
+        let samples: Vec<PathBuf> = if sample_paths.is_empty() {
+            let mut dirs: BTreeSet<PathBuf> = governed_dirs(&before, &before_tree.root);
+            dirs.extend(governed_dirs(&after, &after_tree.root));
+            dirs.into_iter().collect()
+        } else {
+            sample_paths.to_vec()
+        };
+        let effective = samples.into_iter()
+            .filter_map(|path| {
+                let before_rules = effective_rules(&before, &before_tree.root, &path);
+                let after_rules = effective_rules(&after, &after_tree.root, &path);
+                let added: Vec<String> = after_rules.difference(&before_rules).cloned().collect();
+                let removed: Vec<String> = before_rules.difference(&after_rules).cloned().collect();
+                (!added.is_empty() || !removed.is_empty()).then_some(EffectiveChange { path, added, removed })
+            })
+            .collect();
+
+        Ok(Self { from: from.to_string(), to: to.to_string(), rules, effective })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.effective.is_empty()
+    }
+
+    /// Human-readable report
+    pub fn to_text(&self) -> String {
+        let mut output = format!("Rule changes {}..{}\n", self.from, self.to);
+        if self.is_empty() {
+            output.push_str("\nNo rule changes\n");
+            return output;
+        }
+
+        if !self.rules.is_empty() {
+            output.push_str("\nRule files:\n");
+            for change in &self.rules {
+                let file = change.file.display();
+                match (change.kind, &change.before, &change.after) {
+                    (RuleChangeKind::Added, _, Some(rule)) => {
+                        output.push_str(&format!("  + {}: {}\n", file, describe(rule)));
+                    }
+                    (RuleChangeKind::Removed, Some(rule), _) => {
+                        output.push_str(&format!("  - {}: {}\n", file, describe(rule)));
+                    }
+                    (_, Some(before), Some(after)) => {
+                        output.push_str(&format!("  ~ {}: {}\n", file, describe(before)));
+                        output.push_str(&format!("      now {}\n", describe(after)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !self.effective.is_empty() {
+            output.push_str("\nEffective rules:\n");
+            for change in &self.effective {
+                output.push_str(&format!("  {} (+{} -{})\n", change.path.display(), change.added.len(), change.removed.len()));
+                for rule in &change.added {
+                    output.push_str(&format!("    + {}\n", rule));
+                }
+                for rule in &change.removed {
+                    output.push_str(&format!("    - {}\n", rule));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// One-line rule description: ``FORBIDDEN `TODO` - No TODOs``
+fn describe(rule: &Rule) -> String {
+    let keyword = match rule.rule_type {
+        RuleType::Forbidden => "FORBIDDEN",
+        RuleType::Required => "REQUIRED",
+        RuleType::Standard => "STANDARD",
+        RuleType::Convention => "CONVENTION",
+    };
+    format!("{} `{}` - {}", keyword, rule.pattern, rule.message)
+}
+
+/// Path relative to a materialized tree; pack paths such as `builtin:x` are kept
+fn relative(path: &Path, root: &Path) -> PathBuf {
+    path.strip_prefix(root).map(Path::to_path_buf).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Rules keyed by (rule file, pattern, occurrence of that pattern in the file)
+fn rules_by_file(graph: &RuleGraph, root: &Path) -> BTreeMap<(PathBuf, String, usize), Rule> {
+    let mut rules = BTreeMap::new();
+    for (path, rule_set) in &graph.rule_sets {
+        let file = relative(path, root);
+        let mut occurrences: HashMap<&str, usize> = HashMap::new();
+        for rule in &rule_set.rules {
+            let occurrence = occurrences.entry(rule.pattern.as_str()).or_default();
+            rules.insert((file.clone(), rule.pattern.clone(), *occurrence), rule.clone());
+            *occurrence += 1;
+        }
+    }
+    rules
+}
+
+fn diff_rules(
+    before: &BTreeMap<(PathBuf, String, usize), Rule>,
+    after: &BTreeMap<(PathBuf, String, usize), Rule>,
+) -> Vec<RuleChange> {
+    let keys: BTreeSet<&(PathBuf, String, usize)> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (old, new) = (before.get(key), after.get(key));
+            let kind = match (old, new) {
+                (None, Some(_)) => RuleChangeKind::Added,
+                (Some(_), None) => RuleChangeKind::Removed,
+                (Some(old), Some(new)) if rule_differs(old, new) => RuleChangeKind::Changed,
+                _ => return None,
+            };
+            Some(RuleChange { file: key.0.clone(), kind, before: old.cloned(), after: new.cloned() })
+        })
+        .collect()
+}
+
+/// Whether a rule's behavior changed; generated IDs shift with rule order
+/// and are ignored
+fn rule_differs(old: &Rule, new: &Rule) -> bool {
+    old.rule_type != new.rule_type
+        || old.message != new.message
+        || old.tags != new.tags
+        || old.metadata != new.metadata
+}
+
+/// Directories with rule files, relative to the tree root (`.` for the root)
+fn governed_dirs(graph: &RuleGraph, root: &Path) -> BTreeSet<PathBuf> {
+    graph.rule_sets.values()
+        .filter_map(|rule_set| graph.rule_system.governed_dir(rule_set))
+        .filter_map(|dir| dir.strip_prefix(root).ok().map(Path::to_path_buf))
+        .map(|dir| if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir })
+        .collect()
+}
+
+/// Descriptions of the rules that apply to `path` in a materialized tree
+fn effective_rules(graph: &RuleGraph, root: &Path, path: &Path) -> BTreeSet<String> {
+    // A directory sample stands for any file directly inside it
+    let target = if path.extension().is_none() { root.join(path).join("__synapse_diff__") } else { root.join(path) };
+    graph.resolved_for(&target)
+        .composite
+        .applicable_rules
+        .iter()
+        .map(describe)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::TestProject;
+
+    fn git_in(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com", "-C"])
+            .arg(repo)
+            .args(args)
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_diff_between_revisions() {
+        let project = TestProject::new().unwrap();
+        let repo = project.root();
+        git_in(repo, &["init", "-q"]);
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\nREQUIRED: `SPDX` - License header\n").unwrap();
+        project.add_file("src/main.rs", "fn main() {}\n").unwrap();
+        git_in(repo, &["add", "-A"]);
+        git_in(repo, &["commit", "-q", "-m", "base"]);
+
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - Track work in issues\n").unwrap();
+        project.add_rule_file("src/.synapse/src.md", "---\nmcp: synapse\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        git_in(repo, &["add", "-A"]);
+        git_in(repo, &["commit", "-q", "-m", "change"]);
+        // Uncommitted edits are not part of either revision
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\n---\n").unwrap();
+
+        let diff = RuleDiff::between(repo, "HEAD~1", "HEAD", &[]).unwrap();
+
+        let changes: Vec<_> = diff.rules.iter()
+            .map(|c| (c.file.display().to_string(), c.kind, c.after.as_ref().or(c.before.as_ref()).unwrap().pattern.clone()))
+            .collect();
+        assert_eq!(changes, vec![
+            (".synapse/base.md".to_string(), RuleChangeKind::Removed, "SPDX".to_string()),
+            (".synapse/base.md".to_string(), RuleChangeKind::Changed, "TODO".to_string()),
+            ("src/.synapse/src.md".to_string(), RuleChangeKind::Added, "dbg!".to_string()),
+        ]);
+
+        let src = diff.effective.iter().find(|c| c.path == Path::new("src")).unwrap();
+        assert!(src.added.contains(&"FORBIDDEN `dbg!` - No dbg!".to_string()));
+        assert!(src.removed.contains(&"REQUIRED `SPDX` - License header".to_string()));
+        assert!(diff.to_text().contains("~ .synapse/base.md: FORBIDDEN `TODO` - No TODOs"));
+
+        assert!(RuleDiff::between(repo, "HEAD", "no-such-rev", &[]).is_err());
+    }
+
+    #[test]
+    fn test_owners_are_not_rule_changes() {
+        let rule = Rule::new("forbidden-0-TODO".to_string(), RuleType::Forbidden, "TODO".to_string(), "No TODOs".to_string());
+        assert!(!rule_differs(&rule, &rule.clone().with_owners(vec!["platform".to_string()])));
+    }
+}
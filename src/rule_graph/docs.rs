@@ -0,0 +1,447 @@
+//! Rule catalog documentation (`synapse rules docs`)
+//!
+//! Every rule in the graph gets a page with its definition, the directories
+//! it applies to after inheritance and overrides, and the rule files related
+//! to the file that defines it. An index page lists all rules; the HTML index
+//! filters them as you type, without any external assets.
+
+use super::RuleGraph;
+use super::export::GraphEdgeKind;
+use crate::models::{Rule, RuleType};
+use crate::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Output format of `synapse rules docs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsFormat {
+    Html,
+    Markdown,
+}
+
+impl DocsFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "html" => Some(DocsFormat::Html),
+            "markdown" | "md" => Some(DocsFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            DocsFormat::Html => "html",
+            DocsFormat::Markdown => "md",
+        }
+    }
+}
+
+/// A directory where a rule takes effect
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RuleUsage {
+    /// Directory relative to the project root (`.` for the root)
+    pub dir: String,
+    /// Rule file whose modifying override changed the rule there
+    pub modified_by: Option<String>,
+}
+
+/// A directory where a rule is dropped
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RuleExclusion {
+    pub dir: String,
+    /// Rule file that overrides or shadows the rule
+    pub by: String,
+    /// `overridden` or `shadowed`
+    pub reason: String,
+}
+
+/// Documentation of a single rule
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    /// File name of the rule's page, without extension
+    pub slug: String,
+    pub rule: Rule,
+    /// Defining rule file, relative to the project root
+    pub file: String,
+    pub applies_to: Vec<RuleUsage>,
+    pub excluded_from: Vec<RuleExclusion>,
+    /// Files the defining file inherits from or extends
+    pub inherits: Vec<String>,
+    /// Files that inherit from or extend the defining file
+    pub inherited_by: Vec<String>,
+}
+
+/// Every rule in a project, ready to render
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleCatalog {
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl RuleGraph {
+    /// Document every rule in the graph; paths are shown relative to `root`
+    pub fn catalog(&self, root: &Path) -> RuleCatalog {
+        let export = self.export(root);
+        let labels: HashMap<&str, &str> = export.nodes.iter().map(|node| (node.id.as_str(), node.label.as_str())).collect();
+        let mut inherits: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        let mut inherited_by: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for edge in &export.edges {
+            if matches!(edge.kind, GraphEdgeKind::Inherits | GraphEdgeKind::Extends) {
+                inherits.entry(labels[edge.from.as_str()]).or_default().push(labels[edge.to.as_str()].to_string());
+                inherited_by.entry(labels[edge.to.as_str()]).or_default().push(labels[edge.from.as_str()].to_string());
+            }
+        }
+
+        // Resolve each directory with rule files once and note where every rule ends up
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let rule_sets: Vec<_> = self.rule_sets.values().cloned().collect();
+        let mut dirs: Vec<PathBuf> = rule_sets.iter().filter_map(|rule_set| self.rule_system.governed_dir(rule_set)).collect();
+        dirs.sort();
+        dirs.dedup();
+        let mut usages: HashMap<(PathBuf, String), Vec<RuleUsage>> = HashMap::new();
+        let mut exclusions: HashMap<(PathBuf, String), Vec<RuleExclusion>> = HashMap::new();
+        for dir in &dirs {
+            let dir_label = dir_label(dir, &canonical_root);
+            let resolution = self.rule_system.resolve_path(&dir.join("__synapse_docs__"), &rule_sets);
+            for applied in resolution.applied {
+                usages.entry((applied.source, applied.rule.name)).or_default().push(RuleUsage {
+                    dir: dir_label.clone(),
+                    modified_by: applied.modified_by.map(|by| file_label(&by, root)),
+                });
+            }
+            let overridden = resolution.overridden.into_iter().map(|o| (o.source, o.rule, o.overridden_by, "overridden"));
+            let shadowed = resolution.shadowed.into_iter().map(|s| (s.source, s.rule, s.shadowed_by, "shadowed"));
+            for (source, rule, by, reason) in overridden.chain(shadowed) {
+                exclusions.entry((source, rule.name)).or_default().push(RuleExclusion {
+                    dir: dir_label.clone(),
+                    by: file_label(&by, root),
+                    reason: reason.to_string(),
+                });
+            }
+        }
+
+        let mut paths: Vec<&PathBuf> = self.rule_sets.keys().collect();
+        paths.sort();
+        let mut slugs: HashMap<String, usize> = HashMap::new();
+        let mut entries = Vec::new();
+        for path in paths {
+            let file = file_label(path, root);
+            for rule in &self.rule_sets[path].rules {
+                let key = (path.clone(), rule.name.clone());
+                let mut applies_to = usages.remove(&key).unwrap_or_default();
+                let mut excluded_from = exclusions.remove(&key).unwrap_or_default();
+                applies_to.sort();
+                applies_to.dedup();
+                excluded_from.sort();
+                excluded_from.dedup();
+
+                let base = slugify(&format!("{}-{}", file, rule.name));
+                let count = slugs.entry(base.clone()).or_default();
+                *count += 1;
+                let slug = if *count == 1 { base } else { format!("{}-{}", base, count) };
+
+                entries.push(CatalogEntry {
+                    slug,
+                    rule: rule.clone(),
+                    file: file.clone(),
+                    applies_to,
+                    excluded_from,
+                    inherits: inherits.get(file.as_str()).cloned().unwrap_or_default(),
+                    inherited_by: inherited_by.get(file.as_str()).cloned().unwrap_or_default(),
+                });
+            }
+        }
+
+        RuleCatalog { entries }
+    }
+}
+
+impl RuleCatalog {
+    /// Write the index and one page per rule to `out`, returning the number
+    /// of files written
+    pub fn write_site(&self, out: &Path, format: DocsFormat) -> Result<usize> {
+        let pages = out.join("rules");
+        std::fs::create_dir_all(&pages)?;
+        let extension = format.extension();
+        for entry in &self.entries {
+            let page = match format {
+                DocsFormat::Html => entry.to_html(),
+                DocsFormat::Markdown => entry.to_markdown(),
+            };
+            std::fs::write(pages.join(format!("{}.{}", entry.slug, extension)), page)?;
+        }
+        let index = match format {
+            DocsFormat::Html => self.index_html(),
+            DocsFormat::Markdown => self.index_markdown(),
+        };
+        std::fs::write(out.join(format!("index.{}", extension)), index)?;
+        Ok(self.entries.len() + 1)
+    }
+
+    /// Index page with a search box that filters rules by any of their fields
+    pub fn index_html(&self) -> String {
+        let mut rows = String::new();
+        for entry in &self.entries {
+            let rule = &entry.rule;
+            let search = format!("{} {} {} {} {} {}", rule.name, keyword(&rule.rule_type), rule.pattern, rule.message, entry.file, rule.owners().join(" "));
+            let _ = writeln!(
+                rows,
+                "<tr data-search=\"{}\"><td><a href=\"rules/{}.html\">{}</a></td><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                html_escape(&search.to_lowercase()),
+                entry.slug,
+                html_escape(&rule.name),
+                keyword(&rule.rule_type),
+                html_escape(&rule.pattern),
+                html_escape(&rule.message),
+                html_escape(&entry.file),
+            );
+        }
+        let body = format!(
+            "<h1>Rule catalog</h1>\n<p>{} rules</p>\n\
+             <input id=\"search\" type=\"search\" placeholder=\"Search rules\" autofocus>\n\
+             <table id=\"rules\">\n<thead><tr><th>Rule</th><th>Type</th><th>Pattern</th><th>Message</th><th>File</th></tr></thead>\n<tbody>\n{}</tbody>\n</table>\n\
+             <script>\n\
+             document.getElementById('search').addEventListener('input', function (event) {{\n\
+             \x20 var terms = event.target.value.toLowerCase().split(/\\s+/).filter(Boolean);\n\
+             \x20 document.querySelectorAll('#rules tbody tr').forEach(function (row) {{\n\
+             \x20   var text = row.getAttribute('data-search');\n\
+             \x20   row.hidden = !terms.every(function (term) {{ return text.indexOf(term) !== -1; }});\n\
+             \x20 }});\n\
+             }});\n\
+             </script>\n",
+            self.entries.len(),
+            rows
+        );
+        html_page("Rule catalog", &body)
+    }
+
+    pub fn index_markdown(&self) -> String {
+        let mut out = format!("# Rule catalog\n\n{} rules\n\n| Rule | Type | Pattern | Message | File |\n| --- | --- | --- | --- | --- |\n", self.entries.len());
+        for entry in &self.entries {
+            let rule = &entry.rule;
+            let _ = writeln!(
+                out,
+                "| [{}](rules/{}.md) | {} | `{}` | {} | {} |",
+                table_escape(&rule.name),
+                entry.slug,
+                keyword(&rule.rule_type),
+                table_escape(&rule.pattern),
+                table_escape(&rule.message),
+                table_escape(&entry.file),
+            );
+        }
+        out
+    }
+}
+
+impl CatalogEntry {
+    /// Rule settings from attributes and frontmatter, e.g. `scope: function`
+    fn settings(&self) -> Vec<(&str, &str)> {
+        let mut settings: Vec<(&str, &str)> = self.rule.metadata.iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        settings.sort();
+        settings
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let rule = &self.rule;
+        let mut out = format!("# {}\n\n[All rules](../index.md)\n\n", rule.name);
+        let _ = writeln!(out, "| | |\n| --- | --- |");
+        let _ = writeln!(out, "| Type | {} |", keyword(&rule.rule_type));
+        let _ = writeln!(out, "| Pattern | `{}` |", table_escape(&rule.pattern));
+        let _ = writeln!(out, "| Message | {} |", table_escape(&rule.message));
+        let _ = writeln!(out, "| Defined in | `{}` |", table_escape(&self.file));
+        if !rule.owners().is_empty() {
+            let _ = writeln!(out, "| Owners | {} |", table_escape(&rule.owners().join(", ")));
+        }
+        if !rule.tags.is_empty() {
+            let _ = writeln!(out, "| Tags | {} |", table_escape(&rule.tags.join(", ")));
+        }
+        for (key, value) in self.settings() {
+            let _ = writeln!(out, "| {} | {} |", table_escape(key), table_escape(value));
+        }
+
+        out.push_str("\n## Applies to\n\n");
+        if self.applies_to.is_empty() {
+            out.push_str("No directory.\n");
+        }
+        for usage in &self.applies_to {
+            match &usage.modified_by {
+                Some(by) => { let _ = writeln!(out, "- `{}` (modified by `{}`)", usage.dir, by); }
+                None => { let _ = writeln!(out, "- `{}`", usage.dir); }
+            }
+        }
+        if !self.excluded_from.is_empty() {
+            out.push_str("\n## Not applied in\n\n");
+            for exclusion in &self.excluded_from {
+                let _ = writeln!(out, "- `{}` ({} by `{}`)", exclusion.dir, exclusion.reason, exclusion.by);
+            }
+        }
+        if !self.inherits.is_empty() || !self.inherited_by.is_empty() {
+            out.push_str("\n## Inheritance\n\n");
+            for file in &self.inherits {
+                let _ = writeln!(out, "- `{}` inherits from `{}`", self.file, file);
+            }
+            for file in &self.inherited_by {
+                let _ = writeln!(out, "- `{}` inherits from `{}`", file, self.file);
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let rule = &self.rule;
+        let mut body = format!("<p><a href=\"../index.html\">All rules</a></p>\n<h1>{}</h1>\n<table>\n", html_escape(&rule.name));
+        let mut row = |name: &str, value: String| {
+            let _ = writeln!(body, "<tr><th>{}</th><td>{}</td></tr>", html_escape(name), value);
+        };
+        row("Type", keyword(&rule.rule_type).to_string());
+        row("Pattern", format!("<code>{}</code>", html_escape(&rule.pattern)));
+        row("Message", html_escape(&rule.message));
+        row("Defined in", format!("<code>{}</code>", html_escape(&self.file)));
+        if !rule.owners().is_empty() {
+            row("Owners", html_escape(&rule.owners().join(", ")));
+        }
+        if !rule.tags.is_empty() {
+            row("Tags", html_escape(&rule.tags.join(", ")));
+        }
+        for (key, value) in self.settings() {
+            row(key, html_escape(value));
+        }
+        body.push_str("</table>\n<h2>Applies to</h2>\n<ul>\n");
+        if self.applies_to.is_empty() {
+            body.push_str("<li>No directory</li>\n");
+        }
+        for usage in &self.applies_to {
+            let modified = usage.modified_by.as_ref()
+                .map(|by| format!(" (modified by <code>{}</code>)", html_escape(by)))
+                .unwrap_or_default();
+            let _ = writeln!(body, "<li><code>{}</code>{}</li>", html_escape(&usage.dir), modified);
+        }
+        body.push_str("</ul>\n");
+        if !self.excluded_from.is_empty() {
+            body.push_str("<h2>Not applied in</h2>\n<ul>\n");
+            for exclusion in &self.excluded_from {
+                let _ = writeln!(
+                    body,
+                    "<li><code>{}</code> ({} by <code>{}</code>)</li>",
+                    html_escape(&exclusion.dir), exclusion.reason, html_escape(&exclusion.by)
+                );
+            }
+            body.push_str("</ul>\n");
+        }
+        if !self.inherits.is_empty() || !self.inherited_by.is_empty() {
+            body.push_str("<h2>Inheritance</h2>\n<ul>\n");
+            for file in &self.inherits {
+                let _ = writeln!(body, "<li><code>{}</code> inherits from <code>{}</code></li>", html_escape(&self.file), html_escape(file));
+            }
+            for file in &self.inherited_by {
+                let _ = writeln!(body, "<li><code>{}</code> inherits from <code>{}</code></li>", html_escape(file), html_escape(&self.file));
+            }
+            body.push_str("</ul>\n");
+        }
+        html_page(&rule.name, &body)
+    }
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em auto; max-width: 70em; padding: 0 1em; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }}\n\
+         #search {{ width: 100%; padding: 0.5em; margin-bottom: 1em; }}\n\
+         </style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        html_escape(title),
+        body
+    )
+}
+
+fn keyword(rule_type: &RuleType) -> &'static str {
+    match rule_type {
+        RuleType::Forbidden => "FORBIDDEN",
+        RuleType::Required => "REQUIRED",
+        RuleType::Standard => "STANDARD",
+        RuleType::Convention => "CONVENTION",
+    }
+}
+
+/// Path relative to the project root, or the `builtin:` name of a pack
+fn file_label(path: &Path, root: &Path) -> String {
+    let canonical_root = root.canonicalize().ok();
+    let relative = path.strip_prefix(root).ok()
+        .or_else(|| path.strip_prefix(canonical_root.as_deref()?).ok());
+    relative.unwrap_or(path).display().to_string()
+}
+
+fn dir_label(dir: &Path, canonical_root: &Path) -> String {
+    match dir.strip_prefix(canonical_root) {
+        Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => dir.display().to_string(),
+    }
+}
+
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn table_escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::TestProject;
+
+    #[test]
+    fn test_catalog_and_site() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\nowners: platform\n---\nFORBIDDEN: `TODO` - No TODOs\nREQUIRED: `SPDX` - License <header>\n").unwrap();
+        project.add_rule_file("src/.synapse/src.md", "---\nmcp: synapse\noverrides: [\"forbidden-0-TODO\"]\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        project.add_rule_file("lib/.synapse/lib.md", "---\nmcp: synapse\ninherits: [\"../../src\"]\n---\n").unwrap();
+
+        let graph = RuleGraph::from_project(&root.to_path_buf()).unwrap();
+        let catalog = graph.catalog(root);
+
+        let todo = catalog.entries.iter().find(|e| e.rule.name == "forbidden-0-TODO").unwrap();
+        assert_eq!(todo.file, ".synapse/base.md");
+        assert_eq!(todo.slug, "synapse-base-md-forbidden-0-todo");
+        assert!(todo.applies_to.contains(&RuleUsage { dir: ".".to_string(), modified_by: None }));
+        assert!(todo.excluded_from.iter().any(|e| e.dir == "src" && e.by == "src/.synapse/src.md" && e.reason == "overridden"));
+
+        let dbg = catalog.entries.iter().find(|e| e.rule.name == "forbidden-0-dbg!").unwrap();
+        let dirs: Vec<&str> = dbg.applies_to.iter().map(|u| u.dir.as_str()).collect();
+        assert_eq!(dirs, vec!["lib", "src"]);
+        assert_eq!(dbg.inherited_by, vec!["lib/.synapse/lib.md".to_string()]);
+
+        let out = root.join("site");
+        assert_eq!(catalog.write_site(&out, DocsFormat::Html).unwrap(), catalog.entries.len() + 1);
+        let index = std::fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains("href=\"rules/synapse-base-md-forbidden-0-todo.html\""));
+        assert!(index.contains("id=\"search\""));
+        let page = std::fs::read_to_string(out.join("rules/synapse-base-md-required-1-spdx.html")).unwrap();
+        assert!(page.contains("License &lt;header&gt;"));
+        assert!(page.contains("<th>Owners</th><td>platform</td>"));
+
+        catalog.write_site(&out, DocsFormat::Markdown).unwrap();
+        let page = std::fs::read_to_string(out.join("rules/synapse-base-md-forbidden-0-todo.md")).unwrap();
+        assert!(page.contains("- `src` (overridden by `src/.synapse/src.md`)"));
+    }
+}
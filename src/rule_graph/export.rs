@@ -0,0 +1,338 @@
+//! Rendering of the whole rule graph (`synapse rules graph`)
+//!
+//! Nodes are rule files. Edges point from a rule file to the files it
+//! affects: the rule files of nested directories (`contains`), the files it
+//! `inherits` and `extends`, and the files whose rules its `overrides:`
+//! entries drop or modify.
+
+use super::{RuleGraph, RuleGraphStats};
+use crate::models::RuleType;
+use crate::rules::packs::{PackRef, pack_key_of};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Output format of `synapse rules graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+impl GraphFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dot" => Some(GraphFormat::Dot),
+            "mermaid" => Some(GraphFormat::Mermaid),
+            "json" => Some(GraphFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Relationship between two rule files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphEdgeKind {
+    /// The target governs a directory nested in the source's directory
+    Contains,
+    /// The source names the target in `inherits:`
+    Inherits,
+    /// The target belongs to a pack the source names in `extends:`
+    Extends,
+    /// The source's `overrides:` entries drop or modify rules of the target
+    Overrides,
+}
+
+impl GraphEdgeKind {
+    fn name(self) -> &'static str {
+        match self {
+            GraphEdgeKind::Contains => "contains",
+            GraphEdgeKind::Inherits => "inherits",
+            GraphEdgeKind::Extends => "extends",
+            GraphEdgeKind::Overrides => "overrides",
+        }
+    }
+}
+
+/// A rule file in the exported graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub path: PathBuf,
+    /// Path relative to the project root, or the `builtin:` name of a pack
+    pub label: String,
+    pub stats: RuleGraphStats,
+    /// Number of rules per rule type, e.g. `forbidden: 2`
+    pub rules_by_type: BTreeMap<String, usize>,
+}
+
+impl GraphNode {
+    /// One-line summary of the node's rules, e.g. `3 rules: 2 forbidden, 1 required`
+    fn summary(&self) -> String {
+        let by_type: Vec<String> = self.rules_by_type.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect();
+        match self.stats.total_rules {
+            0 => "no rules".to_string(),
+            1 => format!("1 rule: {}", by_type.join(", ")),
+            total => format!("{} rules: {}", total, by_type.join(", ")),
+        }
+    }
+}
+
+/// A relationship between two rule files
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: GraphEdgeKind,
+    /// Overridden rule IDs for `overrides` edges
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
+}
+
+impl GraphEdge {
+    fn label(&self) -> String {
+        if self.rules.is_empty() {
+            self.kind.name().to_string()
+        } else {
+            format!("{} {}", self.kind.name(), self.rules.join(", "))
+        }
+    }
+}
+
+/// Rule files and their relationships, ready to render
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleGraphExport {
+    pub stats: RuleGraphStats,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl RuleGraph {
+    /// Collect every rule file and relationship in the graph
+    ///
+    /// Node labels are relative to `root` where possible.
+    pub fn export(&self, root: &Path) -> RuleGraphExport {
+        let mut paths: Vec<&PathBuf> = self.rule_sets.keys().collect();
+        paths.sort();
+
+        let ids: HashMap<&PathBuf, String> = paths.iter()
+            .enumerate()
+            .map(|(index, path)| (*path, format!("n{}", index)))
+            .collect();
+
+        let nodes = paths.iter().map(|path| {
+            let rule_set = &self.rule_sets[*path];
+            let mut rules_by_type = BTreeMap::new();
+            for rule in &rule_set.rules {
+                *rules_by_type.entry(rule_type_name(&rule.rule_type).to_string()).or_insert(0) += 1;
+            }
+            GraphNode {
+                id: ids[path].clone(),
+                path: (*path).clone(),
+                label: relative_label(path, root),
+                stats: RuleGraphStats::for_rule_set(rule_set),
+                rules_by_type,
+            }
+        }).collect();
+
+        let mut files_by_dir: HashMap<PathBuf, Vec<&PathBuf>> = HashMap::new();
+        let mut files_by_pack: HashMap<PathBuf, Vec<&PathBuf>> = HashMap::new();
+        for path in &paths {
+            if let Some(dir) = self.rule_system.governed_dir(&self.rule_sets[*path]) {
+                files_by_dir.entry(dir).or_default().push(path);
+            }
+            if let Some(key) = pack_key_of(&self.rule_sets[*path]) {
+                files_by_pack.entry(key).or_default().push(path);
+            }
+        }
+
+        let mut edges: BTreeMap<(PathBuf, PathBuf, GraphEdgeKind), BTreeSet<String>> = BTreeMap::new();
+        for path in &paths {
+            let rule_set = &self.rule_sets[*path];
+            let mut targets: Vec<(&PathBuf, &PathBuf, GraphEdgeKind)> = Vec::new();
+
+            // Directory containment links each file to the nearest rule files above it
+            if let Some(dir) = self.rule_system.governed_dir(&self.rule_sets[*path]) {
+                let parent_files = dir.ancestors().skip(1).find_map(|ancestor| files_by_dir.get(ancestor));
+                targets.extend(parent_files.into_iter().flatten().map(|parent| (*parent, *path, GraphEdgeKind::Contains)));
+            }
+
+            for inherit_path in &rule_set.inherits {
+                let inherited = self.rule_system.inherited_dir(path, inherit_path).and_then(|dir| files_by_dir.get(&dir));
+                targets.extend(inherited.into_iter().flatten().map(|to| (*path, *to, GraphEdgeKind::Inherits)));
+            }
+
+            for entry in &rule_set.extends {
+                let pack_files = PackRef::parse(entry).ok()
+                    .and_then(|pack| pack.key(self.rule_system.discovery(), path))
+                    .and_then(|key| files_by_pack.get(&key));
+                targets.extend(pack_files.into_iter().flatten().map(|to| (*path, *to, GraphEdgeKind::Extends)));
+            }
+
+            for (from, to, kind) in targets {
+                if from != to {
+                    edges.entry((from.clone(), to.clone(), kind)).or_default();
+                }
+            }
+        }
+
+        // Overrides only reach rules along a resolution chain, so resolve the
+        // rules of each governed directory and record what was dropped or modified
+        let rule_sets: Vec<_> = self.rule_sets.values().cloned().collect();
+        for dir in files_by_dir.keys() {
+            let resolution = self.rule_system.resolve_path(&dir.join("__synapse_graph__"), &rule_sets);
+            let overridden = resolution.overridden.iter()
+                .map(|o| (&o.overridden_by, &o.source, &o.rule.name));
+            let modified = resolution.applied.iter()
+                .filter_map(|a| a.modified_by.as_ref().map(|by| (by, &a.source, &a.rule.name)));
+            for (from, to, rule_name) in overridden.chain(modified) {
+                if ids.contains_key(from) && ids.contains_key(to) {
+                    edges.entry((from.clone(), to.clone(), GraphEdgeKind::Overrides)).or_default().insert(rule_name.clone());
+                }
+            }
+        }
+
+        let edges = edges.into_iter()
+            .map(|((from, to, kind), rules)| GraphEdge {
+                from: ids[&from].clone(),
+                to: ids[&to].clone(),
+                kind,
+                rules: rules.into_iter().collect(),
+            })
+            .collect();
+
+        RuleGraphExport { stats: self.stats(), nodes, edges }
+    }
+}
+
+impl RuleGraphExport {
+    pub fn render(&self, format: GraphFormat) -> crate::Result<String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::Mermaid => Ok(self.to_mermaid()),
+            GraphFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+        }
+    }
+
+    /// Graphviz source, e.g. for `dot -Tsvg`
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph rules {{");
+        let _ = writeln!(out, "  label=\"{} rule files, {} rules\";", self.stats.rule_files, self.stats.total_rules);
+        let _ = writeln!(out, "  node [shape=box, fontname=\"monospace\"];");
+        for node in &self.nodes {
+            let _ = writeln!(out, "  {} [label=\"{}\\n{}\"];", node.id, dot_escape(&node.label), dot_escape(&node.summary()));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                GraphEdgeKind::Contains => "color=gray",
+                GraphEdgeKind::Inherits => "style=bold",
+                GraphEdgeKind::Extends => "style=dashed",
+                GraphEdgeKind::Overrides => "style=dotted, color=red",
+            };
+            let _ = writeln!(out, "  {} -> {} [label=\"{}\", {}];", edge.from, edge.to, dot_escape(&edge.label()), style);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart, e.g. for a Markdown code block
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph TD\n");
+        for node in &self.nodes {
+            let _ = writeln!(out, "  {}[\"{}<br/>{}\"]", node.id, mermaid_escape(&node.label), mermaid_escape(&node.summary()));
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                GraphEdgeKind::Contains => "-->",
+                GraphEdgeKind::Inherits => "==>",
+                GraphEdgeKind::Extends | GraphEdgeKind::Overrides => "-.->",
+            };
+            let _ = writeln!(out, "  {} {}|\"{}\"| {}", edge.from, arrow, mermaid_escape(&edge.label()), edge.to);
+        }
+        out
+    }
+}
+
+impl RuleGraphStats {
+    /// Statistics of a single rule file
+    pub fn for_rule_set(rule_set: &crate::RuleSet) -> Self {
+        Self {
+            rule_files: 1,
+            total_rules: rule_set.rules.len(),
+            inheritance_relationships: rule_set.inherits.len(),
+            override_relationships: rule_set.overrides.len(),
+        }
+    }
+}
+
+fn rule_type_name(rule_type: &RuleType) -> &'static str {
+    match rule_type {
+        RuleType::Forbidden => "forbidden",
+        RuleType::Required => "required",
+        RuleType::Standard => "standard",
+        RuleType::Convention => "convention",
+    }
+}
+
+fn relative_label(path: &Path, root: &Path) -> String {
+    let canonical_root = root.canonicalize().ok();
+    let relative = path.strip_prefix(root).ok()
+        .or_else(|| path.strip_prefix(canonical_root.as_deref()?).ok());
+    relative.unwrap_or(path).display().to_string()
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::TestProject;
+
+    #[test]
+    fn test_export_edges_and_formats() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\nREQUIRED: `SPDX` - License header\n").unwrap();
+        project.add_rule_file("shared/.synapse/shared.md", "---\nmcp: synapse\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        project.add_rule_file("src/.synapse/src.md",
+            "---\nmcp: synapse\ninherits: [\"../../shared\"]\nextends: [\"builtin:python\"]\noverrides: [\"forbidden-0-TODO\"]\n---\n").unwrap();
+
+        let graph = RuleGraph::from_project(&root.to_path_buf()).unwrap();
+        let export = graph.export(root);
+
+        let label = |id: &str| export.nodes.iter().find(|n| n.id == id).unwrap().label.clone();
+        let edges: Vec<(String, String, GraphEdgeKind)> = export.edges.iter()
+            .map(|e| (label(&e.from), label(&e.to), e.kind))
+            .collect();
+        let src = "src/.synapse/src.md".to_string();
+        let base = ".synapse/base.md".to_string();
+        assert!(edges.contains(&(base.clone(), src.clone(), GraphEdgeKind::Contains)));
+        assert!(edges.contains(&(src.clone(), "shared/.synapse/shared.md".to_string(), GraphEdgeKind::Inherits)));
+        assert!(edges.contains(&(src.clone(), "builtin:python".to_string(), GraphEdgeKind::Extends)));
+        let overrides = export.edges.iter().find(|e| e.kind == GraphEdgeKind::Overrides).unwrap();
+        assert_eq!((label(&overrides.from), label(&overrides.to)), (src, base));
+        assert_eq!(overrides.rules, vec!["forbidden-0-TODO".to_string()]);
+
+        let base_node = export.nodes.iter().find(|n| n.label == ".synapse/base.md").unwrap();
+        assert_eq!(base_node.summary(), "2 rules: 1 forbidden, 1 required");
+
+        let dot = export.render(GraphFormat::Dot).unwrap();
+        assert!(dot.starts_with("digraph rules {"));
+        assert!(dot.contains("style=dotted"));
+        let mermaid = export.render(GraphFormat::Mermaid).unwrap();
+        assert!(mermaid.starts_with("graph TD\n"));
+        let json: serde_json::Value = serde_json::from_str(&export.render(GraphFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), export.nodes.len());
+        assert_eq!(json["edges"][0]["kind"], "contains");
+    }
+}
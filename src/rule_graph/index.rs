@@ -0,0 +1,212 @@
+//! Directory index for fast rule lookups
+//!
+//! The rules that apply to a file depend only on the nearest directory with
+//! rule files above it: directories without rule files add nothing to the
+//! walk up the tree. The index resolves every such directory once, when the
+//! graph is built, and stores the result in a trie keyed by canonical path
+//! components. A lookup canonicalizes the file's directory and walks the trie
+//! down to the deepest resolved entry; no rule sets are cloned and no regex is
+//! compiled per request. Each entry also keeps the combined prefilter of its
+//! rules, so content checks scan a file once before full matching.
+
+use crate::enforcement::check_rules_with;
+use crate::enforcement::lifecycle::ShadowCounter;
+use crate::enforcement::prefilter::RulePrefilter;
+use crate::models::{CompiledRule, CompositeRules, Rule, RuleSet, Violation};
+use crate::rules::RuleSystem;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Rules that apply below a directory, resolved and compiled
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedRules {
+    pub composite: CompositeRules,
+    /// `composite.applicable_rules` compiled, in the same order
+    pub compiled: Vec<Arc<CompiledRule>>,
+    /// Combined literal and regex matchers of `compiled`
+    pub prefilter: RulePrefilter,
+    /// Shadow rule hits, shared by every directory of the graph
+    pub shadow_hits: Arc<ShadowCounter>,
+}
+
+impl ResolvedRules {
+    /// Check file content against these rules, counting shadow rule hits
+    /// in the graph's counter
+    pub fn check(&self, file_path: &Path, content: &str) -> crate::Result<Vec<Violation>> {
+        check_rules_with(file_path, content, &self.compiled, &self.prefilter, &self.shadow_hits)
+    }
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<OsString, TrieNode>,
+    rules: Option<Arc<ResolvedRules>>,
+}
+
+/// Trie from directories to their resolved rules
+#[derive(Debug, Default)]
+pub struct RuleIndex {
+    root: TrieNode,
+    /// Result for paths outside every governed directory
+    empty: Arc<ResolvedRules>,
+}
+
+impl RuleIndex {
+    /// Resolve and compile the rules of every directory that has rule files
+    pub fn build(rule_system: &RuleSystem, rule_sets: &[RuleSet], shadow_hits: &Arc<ShadowCounter>) -> Self {
+        let mut dirs: Vec<PathBuf> = rule_sets.iter().filter_map(|rule_set| rule_system.governed_dir(rule_set)).collect();
+        dirs.sort();
+        dirs.dedup();
+        // `files:` globs are relative to the directory their rule file governs
+        let governed: HashMap<&Path, PathBuf> = rule_sets.iter()
+            .filter_map(|rule_set| Some((rule_set.path.as_path(), rule_system.governed_dir(rule_set)?)))
+            .collect();
+
+        // A rule appears in the result of every directory below its file, so
+        // compile each distinct rule once and share it
+        let mut compiled_cache: HashMap<(PathBuf, RuleKey), Option<Arc<CompiledRule>>> = HashMap::new();
+        let mut index = Self::default();
+
+        for dir in dirs {
+            let resolution = rule_system.resolve_path(&dir.join("__synapse_index__"), rule_sets);
+            let compiled = resolution.applied.iter()
+                .filter_map(|applied| {
+                    compiled_cache.entry((applied.source.clone(), RuleKey::of(&applied.rule)))
+                        .or_insert_with(|| {
+                            // Rule files are validated when parsed; a rule built in
+                            // code with an invalid `regex:` pattern is left out, not degraded
+                            let compiled = match CompiledRule::try_from_rule(applied.rule.clone()) {
+                                Ok(compiled) => compiled,
+                                Err(e) => {
+                                    warn!("Skipping rule from {}: {}", applied.source.display(), e);
+                                    return None;
+                                }
+                            };
+                            Some(Arc::new(match governed.get(applied.source.as_path()) {
+                                Some(dir) => compiled.with_files_base(dir.clone()),
+                                None => compiled,
+                            }))
+                        })
+                        .clone()
+                })
+                .collect::<Vec<_>>();
+            let prefilter = RulePrefilter::new(&compiled);
+            let resolved = ResolvedRules { composite: resolution.into_composite(), compiled, prefilter, shadow_hits: shadow_hits.clone() };
+            index.insert(&dir, Arc::new(resolved));
+        }
+
+        index
+    }
+
+    fn insert(&mut self, dir: &Path, rules: Arc<ResolvedRules>) {
+        let mut node = &mut self.root;
+        for component in dir.components() {
+            node = node.children.entry(component.as_os_str().to_os_string()).or_default();
+        }
+        node.rules = Some(rules);
+    }
+
+    /// Rules that apply to a file
+    ///
+    /// The file itself doesn't need to exist; its nearest existing directory
+    /// is used, as a new file would be checked against those rules.
+    pub fn lookup(&self, file_path: &Path) -> Arc<ResolvedRules> {
+        let Some(dir) = file_path.parent()
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+            .find_map(|dir| dir.canonicalize().ok())
+        else {
+            return self.empty.clone();
+        };
+
+        let mut node = &self.root;
+        let mut deepest = node.rules.as_ref();
+        for component in dir.components() {
+            match node.children.get(component.as_os_str()) {
+                Some(child) => {
+                    node = child;
+                    deepest = node.rules.as_ref().or(deepest);
+                }
+                None => break,
+            }
+        }
+        deepest.cloned().unwrap_or_else(|| self.empty.clone())
+    }
+}
+
+/// Identity of a resolved rule within its source file
+///
+/// Modifying overrides change a rule's type or message, so the same source
+/// rule can resolve differently in different directories.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RuleKey {
+    name: String,
+    rule_type: String,
+    message: String,
+}
+
+impl RuleKey {
+    fn of(rule: &Rule) -> Self {
+        Self {
+            name: rule.name.clone(),
+            rule_type: format!("{:?}", rule.rule_type),
+            message: rule.message.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::TestProject;
+
+    #[test]
+    fn test_lookup_matches_resolution() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\n").unwrap();
+        project.add_rule_file("src/.synapse/src.md", "---\nmcp: synapse\noverrides: [\"forbidden-0-TODO\"]\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        project.add_file("src/api/handlers/mod.rs", "").unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+
+        let rule_system = RuleSystem::new();
+        let rule_sets = rule_system.load_rules(&root.to_path_buf()).unwrap();
+        let index = RuleIndex::build(&rule_system, &rule_sets, &Arc::default());
+
+        for path in ["src/api/handlers/mod.rs", "src/new_dir/new_file.rs", "docs/guide.md", "main.rs"] {
+            let target = root.join(path);
+            let expected = rule_system.rules_for_path(&target, &rule_sets);
+            let resolved = index.lookup(&target);
+            let names = |rules: &[Rule]| rules.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+            assert_eq!(names(&resolved.composite.applicable_rules), names(&expected.applicable_rules), "{}", path);
+            assert_eq!(resolved.composite.inheritance_chain, expected.inheritance_chain, "{}", path);
+            assert_eq!(resolved.compiled.len(), resolved.composite.applicable_rules.len());
+        }
+
+        // Directories below the same rule files share one result
+        assert!(Arc::ptr_eq(&index.lookup(&root.join("src/a.rs")), &index.lookup(&root.join("src/api/handlers/mod.rs"))));
+        assert!(index.lookup(Path::new("/nonexistent/file.rs")).compiled.is_empty());
+    }
+
+    #[test]
+    fn test_rules_with_invalid_explicit_regexes_are_left_out() {
+        let project = TestProject::new().unwrap();
+        let rule = |pattern: &str| Rule::new(pattern.to_string(), crate::RuleType::Forbidden, pattern.to_string(), "message".to_string());
+        let mut explicit = rule("println!(");
+        explicit.metadata.insert("kind".to_string(), "regex".to_string());
+        let rule_set = RuleSet::new(project.path(".synapse.md"))
+            .add_rule(explicit)
+            .add_rule(rule("TODO"));
+
+        let index = RuleIndex::build(&RuleSystem::new(), &[rule_set], &Arc::default());
+        let resolved = index.lookup(&project.path("main.rs"));
+
+        // Never degraded to a literal match
+        let names: Vec<&str> = resolved.compiled.iter().map(|c| c.rule.name.as_str()).collect();
+        assert_eq!(names, vec!["TODO"]);
+    }
+}
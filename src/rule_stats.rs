@@ -0,0 +1,395 @@
+//! Per-rule hit statistics and dead-rule detection
+//!
+//! Each `synapse check` run counts, per rule, the files it was evaluated
+//! against and the violations it produced in a [`RuleMetrics`]. The counts are
+//! merged into a [`RuleStatsStore`], which reports rules that haven't matched
+//! in many runs and rules that fire the most. The store is a local cache kept
+//! at `synapse/rule-stats.json` in the git directory (`.git/`, or the one a
+//! worktree's `.git` file points to), outside the tracked tree; projects that
+//! aren't git checkouts keep it at `.synapse/rule-stats.json`.
+//!
+//! The server counts the hits of its enforcement requests in memory with a
+//! [`RuleStatsRecorder`], which saves them as one run at most every
+//! [`FLUSH_INTERVAL`] and at shutdown.
+//!
+//! Rules are identified by name. Concurrent runs that save at the same moment
+//! may lose one run's counts; the statistics are a guide, not an audit log.
+
+use crate::models::{CompiledRule, Violation};
+use crate::enforcement::lifecycle::ShadowHits;
+use crate::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// File name of the store
+pub const STATS_FILE: &str = "rule-stats.json";
+
+/// How often a server saves the rule hits it has counted
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Default, Clone, Copy)]
+struct RuleCounters {
+    files: u64,
+    hits: u64,
+}
+
+/// Thread-safe per-rule counters for a single run
+#[derive(Debug, Default)]
+pub struct RuleMetrics {
+    rules: Mutex<HashMap<String, RuleCounters>>,
+}
+
+impl RuleMetrics {
+    /// Record one file checked against `rules` and the violations it produced
+    pub fn record_file<R: Borrow<CompiledRule>>(&self, rules: &[R], violations: &[Violation]) {
+        let mut counters = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        for rule in rules {
+            counters.entry(rule.borrow().rule.name.clone()).or_default().files += 1;
+        }
+        for violation in violations {
+            counters.entry(violation.rule.name.clone()).or_default().hits += 1;
+        }
+    }
+
+    /// Record violations found outside a per-file check, such as the import pass
+    pub fn record_hits(&self, violations: &[Violation]) {
+        self.record_file::<CompiledRule>(&[], violations);
+    }
+
+    /// Record the shadow rule hits counted since `before` was taken
+    pub fn record_shadow_hits(&self, before: &ShadowHits, after: &ShadowHits) {
+        let mut counters = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        for (rule, hits) in &after.by_rule {
+            let new_hits = hits - before.by_rule.get(rule).copied().unwrap_or(0);
+            if new_hits > 0 {
+                counters.entry(rule.clone()).or_default().hits += new_hits;
+            }
+        }
+    }
+
+    /// Add the counters of another run
+    pub fn merge(&self, other: &RuleMetrics) {
+        let other = other.rules.lock().unwrap_or_else(|e| e.into_inner());
+        let mut counters = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        for (rule, added) in other.iter() {
+            let counter = counters.entry(rule.clone()).or_default();
+            counter.files += added.files;
+            counter.hits += added.hits;
+        }
+    }
+
+    /// Move the counters out, leaving these empty
+    pub fn take(&self) -> RuleMetrics {
+        let mut counters = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        RuleMetrics { rules: Mutex::new(std::mem::take(&mut *counters)) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+    }
+}
+
+/// Accumulated statistics of one rule
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleStats {
+    /// Runs in which the rule was evaluated
+    pub runs: u64,
+    /// Runs since the rule last matched, or since it was first seen
+    pub runs_since_hit: u64,
+    pub files_checked: u64,
+    pub hits: u64,
+    pub last_hit: Option<DateTime<Utc>>,
+}
+
+/// A rule and its statistics, as reported by [`RuleStatsStore::report`]
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleStatsEntry {
+    pub rule: String,
+    #[serde(flatten)]
+    pub stats: RuleStats,
+}
+
+/// Dead and noisy rules
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleStatsReport {
+    pub runs: u64,
+    /// Rules that haven't matched in at least the requested number of runs
+    pub dead: Vec<RuleStatsEntry>,
+    /// Rules with the most hits
+    pub noisiest: Vec<RuleStatsEntry>,
+}
+
+/// Rule statistics persisted in the git directory, or under `.synapse/`
+/// outside git checkouts (see [`RuleStatsStore::path`])
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleStatsStore {
+    /// Runs recorded
+    pub runs: u64,
+    pub rules: BTreeMap<String, RuleStats>,
+}
+
+impl RuleStatsStore {
+    /// Location of the store of the project at `root`: inside the git
+    /// directory for git checkouts, so it's never committed, and under
+    /// `.synapse/` otherwise
+    ///
+    /// Worktrees share the store of their main checkout; submodules keep
+    /// their own in the superproject's `.git/modules/`.
+    pub fn path(root: &Path) -> PathBuf {
+        match git_common_dir(root) {
+            Some(git_dir) => git_dir.join("synapse").join(STATS_FILE),
+            None => root.join(".synapse").join(STATS_FILE),
+        }
+    }
+
+    /// Load the store of the project at `root`; a missing store is empty
+    pub fn load(root: &Path) -> Result<Self> {
+        match std::fs::read_to_string(Self::path(root)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the store, replacing the previous file atomically
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::path(root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension(format!("json.{}", std::process::id()));
+        std::fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    /// Merge the counters of one run
+    pub fn record_run(&mut self, metrics: &RuleMetrics) {
+        let counters = metrics.rules.lock().unwrap_or_else(|e| e.into_inner());
+        self.runs += 1;
+        let now = Utc::now();
+        for (rule, counters) in counters.iter() {
+            let stats = self.rules.entry(rule.clone()).or_default();
+            stats.runs += 1;
+            stats.files_checked += counters.files;
+            stats.hits += counters.hits;
+            if counters.hits > 0 {
+                stats.runs_since_hit = 0;
+                stats.last_hit = Some(now);
+            } else {
+                stats.runs_since_hit += 1;
+            }
+        }
+    }
+
+    /// Merge one run into the store of the project at `root`
+    ///
+    /// Runs that evaluated no rules are not recorded.
+    pub fn record(root: &Path, metrics: &RuleMetrics) -> Result<()> {
+        if metrics.is_empty() {
+            return Ok(());
+        }
+        let mut store = Self::load(root)?;
+        store.record_run(metrics);
+        store.save(root)?;
+        debug!("Recorded rule statistics for run {} in {}", store.runs, Self::path(root).display());
+        Ok(())
+    }
+
+    /// Rules that haven't matched in `dead_after` runs, and the `top` rules by hits
+    pub fn report(&self, dead_after: u64, top: usize) -> RuleStatsReport {
+        let entry = |(rule, stats): (&String, &RuleStats)| RuleStatsEntry { rule: rule.clone(), stats: stats.clone() };
+
+        let mut dead: Vec<RuleStatsEntry> = self.rules.iter()
+            .filter(|(_, stats)| stats.runs_since_hit >= dead_after)
+            .map(entry)
+            .collect();
+        dead.sort_by(|a, b| b.stats.runs_since_hit.cmp(&a.stats.runs_since_hit).then_with(|| a.rule.cmp(&b.rule)));
+
+        let mut noisiest: Vec<RuleStatsEntry> = self.rules.iter()
+            .filter(|(_, stats)| stats.hits > 0)
+            .map(entry)
+            .collect();
+        noisiest.sort_by(|a, b| b.stats.hits.cmp(&a.stats.hits).then_with(|| a.rule.cmp(&b.rule)));
+        noisiest.truncate(top);
+
+        RuleStatsReport { runs: self.runs, dead, noisiest }
+    }
+}
+
+/// Rule hits counted in memory by a long-running server
+///
+/// Requests add their counters with [`RuleStatsRecorder::add`]; the totals are
+/// saved as one run when [`FLUSH_INTERVAL`] has passed since the last save, and
+/// when the recorder is dropped at shutdown.
+#[derive(Debug)]
+pub struct RuleStatsRecorder {
+    root: PathBuf,
+    metrics: RuleMetrics,
+    last_flush: Mutex<Instant>,
+    interval: Duration,
+}
+
+impl RuleStatsRecorder {
+    pub fn new(root: PathBuf) -> Self {
+        Self::with_interval(root, FLUSH_INTERVAL)
+    }
+
+    pub fn with_interval(root: PathBuf, interval: Duration) -> Self {
+        Self { root, metrics: RuleMetrics::default(), last_flush: Mutex::new(Instant::now()), interval }
+    }
+
+    /// Add one request's counters, saving the totals if a flush is due
+    pub fn add(&self, metrics: &RuleMetrics) {
+        self.metrics.merge(metrics);
+        let due = {
+            let mut last_flush = self.last_flush.lock().unwrap_or_else(|e| e.into_inner());
+            let due = last_flush.elapsed() >= self.interval;
+            if due {
+                *last_flush = Instant::now();
+            }
+            due
+        };
+        if due {
+            self.flush();
+        }
+    }
+
+    /// Save the counters gathered since the last save as one run
+    pub fn flush(&self) {
+        if let Err(e) = RuleStatsStore::record(&self.root, &self.metrics.take()) {
+            warn!("Failed to save rule statistics: {}", e);
+        }
+    }
+}
+
+impl Drop for RuleStatsRecorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Git directory shared by all worktrees of the checkout at `root`
+///
+/// `.git` is a directory in a regular checkout. In worktrees and submodules
+/// it is a file with a `gitdir:` line, and a worktree's git directory names
+/// the shared one in its `commondir` file.
+fn git_common_dir(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = root.join(content.lines().find_map(|line| line.strip_prefix("gitdir:"))?.trim());
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => Some(git_dir.join(common.trim())),
+        Err(_) => Some(git_dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Rule, RuleType};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn compiled(name: &str) -> CompiledRule {
+        CompiledRule::from_rule(Rule::new(name.to_string(), RuleType::Forbidden, name.to_string(), "message".to_string()))
+    }
+
+    #[test]
+    fn test_record_runs_and_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let rules = vec![compiled("todo"), compiled("dbg")];
+        let todo = Violation::new(PathBuf::from("lib.rs"), rules[0].rule.clone(), Some(1), None);
+
+        for run in 0..3 {
+            let metrics = RuleMetrics::default();
+            metrics.record_file(&rules, &[]);
+            let violations = if run == 0 { vec![todo.clone(), todo.clone()] } else { Vec::new() };
+            metrics.record_file(&rules, &violations);
+            RuleStatsStore::record(root, &metrics).unwrap();
+        }
+        RuleStatsStore::record(root, &RuleMetrics::default()).unwrap();
+
+        let store = RuleStatsStore::load(root).unwrap();
+        assert_eq!(store.runs, 3);
+        assert_eq!(store.rules["todo"].hits, 2);
+        assert_eq!(store.rules["todo"].files_checked, 6);
+        assert_eq!(store.rules["todo"].runs_since_hit, 2);
+        assert!(store.rules["todo"].last_hit.is_some());
+        assert_eq!(store.rules["dbg"].runs_since_hit, 3);
+
+        let report = store.report(3, 10);
+        assert_eq!(report.dead.iter().map(|e| e.rule.as_str()).collect::<Vec<_>>(), vec!["dbg"]);
+        assert_eq!(report.noisiest.iter().map(|e| e.rule.as_str()).collect::<Vec<_>>(), vec!["todo"]);
+        assert_eq!(store.report(2, 10).dead.len(), 2);
+    }
+
+    #[test]
+    fn test_recorder_saves_one_run_per_flush() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        let rules = vec![compiled("todo")];
+
+        let recorder = RuleStatsRecorder::with_interval(root.to_path_buf(), Duration::from_secs(3600));
+        for _ in 0..5 {
+            let metrics = RuleMetrics::default();
+            metrics.record_file(&rules, &[]);
+            recorder.add(&metrics);
+        }
+        assert!(!RuleStatsStore::path(root).exists(), "nothing is saved before a flush is due");
+
+        drop(recorder);
+        assert_eq!(RuleStatsStore::path(root), root.join(".git/synapse/rule-stats.json"));
+        let store = RuleStatsStore::load(root).unwrap();
+        assert_eq!(store.runs, 1);
+        assert_eq!(store.rules["todo"].files_checked, 5);
+    }
+
+    #[test]
+    fn test_record_shadow_hits() {
+        let metrics = RuleMetrics::default();
+        let before = ShadowHits { total: 1, by_rule: BTreeMap::from([("shadow".to_string(), 1)]) };
+        let after = ShadowHits { total: 4, by_rule: BTreeMap::from([("shadow".to_string(), 4)]) };
+        metrics.record_shadow_hits(&before, &after);
+        metrics.record_hits(&[Violation::new(PathBuf::from("lib.rs"), Arc::new(compiled("todo").rule.as_ref().clone()), None, None)]);
+
+        let mut store = RuleStatsStore::default();
+        store.record_run(&metrics);
+        assert_eq!(store.rules["shadow"].hits, 3);
+        assert_eq!(store.rules["todo"].hits, 1);
+    }
+
+    #[test]
+    fn test_path_follows_gitdir_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let main = temp_dir.path().join("main");
+        std::fs::create_dir_all(main.join(".git/worktrees/feature")).unwrap();
+        std::fs::create_dir_all(main.join(".git/modules/vendor")).unwrap();
+        std::fs::write(main.join(".git/worktrees/feature/commondir"), "../..\n").unwrap();
+
+        // A worktree shares the main checkout's store
+        let worktree = temp_dir.path().join("feature");
+        std::fs::create_dir(&worktree).unwrap();
+        std::fs::write(worktree.join(".git"), format!("gitdir: {}\n", main.join(".git/worktrees/feature").display())).unwrap();
+        assert_eq!(RuleStatsStore::path(&worktree), main.join(".git/worktrees/feature/../../synapse").join(STATS_FILE));
+
+        // A submodule's relative gitdir points into the superproject
+        let submodule = main.join("vendor");
+        std::fs::create_dir(&submodule).unwrap();
+        std::fs::write(submodule.join(".git"), "gitdir: ../.git/modules/vendor\n").unwrap();
+        assert_eq!(RuleStatsStore::path(&submodule), submodule.join("../.git/modules/vendor/synapse").join(STATS_FILE));
+
+        assert_eq!(RuleStatsStore::path(temp_dir.path()), temp_dir.path().join(".synapse").join(STATS_FILE));
+    }
+}
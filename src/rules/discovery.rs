@@ -1,35 +1,94 @@
+use crate::config::DiscoveryConfig;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use tracing::warn;
 use walkdir::WalkDir;
 
+/// Directories that are never searched for rule files
+const ALWAYS_EXCLUDED: [&str; 3] = [".git", "target", "node_modules"];
+
 #[derive(Debug)]
-pub struct RuleDiscovery;
+pub struct RuleDiscovery {
+    config: DiscoveryConfig,
+    exclude: GlobSet,
+}
 
 impl RuleDiscovery {
     pub fn new() -> Self {
-        Self
+        Self::with_config(DiscoveryConfig::default())
+    }
+
+    /// Discovery using the `[discovery]` settings of config.toml
+    ///
+    /// Invalid exclude globs are ignored with a warning.
+    pub fn with_config(config: DiscoveryConfig) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &config.exclude {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => warn!("Ignoring invalid discovery exclude glob '{}': {}", pattern, e),
+            }
+        }
+        let exclude = builder.build().unwrap_or_else(|_| GlobSet::empty());
+        Self { config, exclude }
+    }
+
+    pub fn config(&self) -> &DiscoveryConfig {
+        &self.config
+    }
+
+    /// Whether a directory name is one of the configured rule directories
+    pub fn is_rule_dir_name(&self, name: &OsStr) -> bool {
+        self.config.rule_dirs.iter().any(|dir| OsStr::new(dir) == name)
+    }
+
+    /// Whether a path below `root_path` is skipped by the exclude settings
+    fn is_excluded(&self, root_path: &Path, path: &Path) -> bool {
+        if path.file_name().is_some_and(|name| ALWAYS_EXCLUDED.iter().any(|excluded| OsStr::new(excluded) == name)) {
+            return true;
+        }
+        let relative = path.strip_prefix(root_path).unwrap_or(path);
+        !relative.as_os_str().is_empty() && self.exclude.is_match(relative)
+    }
+
+    /// Depth of the project directory a path lies in or is governed by: rule
+    /// directories and their contents count as part of the directory that
+    /// holds them (`.synapse/rules.md` is at depth 0, `src/.synapse/x.md` at 1)
+    fn directory_depth(&self, root_path: &Path, path: &Path) -> usize {
+        path.strip_prefix(root_path)
+            .unwrap_or(path)
+            .components()
+            .take_while(|c| !self.is_rule_dir_name(c.as_os_str()))
+            .count()
+    }
+
+    /// Whether a directory is below the configured `max_depth`
+    fn is_too_deep(&self, root_path: &Path, entry: &walkdir::DirEntry) -> bool {
+        self.config.max_depth.is_some_and(|max_depth| {
+            entry.file_type().is_dir() && self.directory_depth(root_path, entry.path()) > max_depth
+        })
     }
 
-    /// Find all .md files inside .synapse/ directories in a directory tree
+    /// Find all .md files inside rule directories (`.synapse/` by default) in a directory tree
+    ///
+    /// `max_depth` limits how deep below the project root the directories
+    /// holding rule directories may be; the rule directory itself doesn't
+    /// count, so `max_depth = 0` still finds the root `.synapse/` files.
     pub fn find_rule_files(&self, root_path: &Path) -> crate::Result<Vec<PathBuf>> {
         let mut rule_files = Vec::new();
 
-        // Find all .synapse directories and their .md files
-        for entry in WalkDir::new(root_path)
+        let walker = WalkDir::new(root_path)
+            .follow_links(self.config.follow_symlinks);
+        for entry in walker
             .into_iter()
-            .filter_entry(|e| {
-                // Skip .git and other common ignore directories
-                e.file_name() != ".git" && 
-                e.file_name() != "target" && 
-                e.file_name() != "node_modules"
-            })
+            .filter_entry(|e| !self.is_excluded(root_path, e.path()) && !self.is_too_deep(root_path, e))
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
-            
-            // Check if this is a .md file inside a .synapse directory
-            if path.is_file() && 
-               path.extension() == Some("md".as_ref()) &&
-               path.components().any(|c| c.as_os_str() == ".synapse") {
+            if entry.file_type().is_file() && self.is_rule_file_path(path) {
                 rule_files.push(path.to_path_buf());
             }
         }
@@ -38,14 +97,65 @@ impl RuleDiscovery {
         Ok(rule_files)
     }
 
-    /// Check if a file is inside a .synapse directory and is a .md file
+    /// Rule files configured in `discovery.external`
+    ///
+    /// Relative entries are resolved against `root_path`. Directories
+    /// contribute every .md file below them; missing entries are skipped with a
+    /// warning.
+    pub fn find_external_rule_files(&self, root_path: &Path) -> Vec<PathBuf> {
+        let mut rule_files = Vec::new();
+        for entry in &self.config.external {
+            let path = root_path.join(entry);
+            if path.is_file() {
+                rule_files.push(path);
+            } else if path.is_dir() {
+                let mut dir_files: Vec<PathBuf> = WalkDir::new(&path)
+                    .follow_links(self.config.follow_symlinks)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file() && e.path().extension() == Some("md".as_ref()))
+                    .map(|e| e.into_path())
+                    .collect();
+                dir_files.sort();
+                rule_files.extend(dir_files);
+            } else {
+                warn!("External rule path {} does not exist", path.display());
+            }
+        }
+        rule_files
+    }
+
+    /// Check if a file is inside a rule directory and is a .md file
     pub fn is_rule_file(&self, path: &Path) -> bool {
-        path.is_file() && 
+        path.is_file() && self.is_rule_file_path(path)
+    }
+
+    fn is_rule_file_path(&self, path: &Path) -> bool {
         path.extension() == Some("md".as_ref()) &&
-        path.components().any(|c| c.as_os_str() == ".synapse")
+        path.components().any(|c| self.is_rule_dir_name(c.as_os_str()))
     }
 
-    /// Find the nearest parent .synapse directory and return all .md files in it
+    /// .md files directly inside the rule directories of `dir`, or `None` if
+    /// `dir` has no rule directory
+    fn rule_files_in(&self, dir: &Path) -> Option<Vec<PathBuf>> {
+        let mut rule_files = Vec::new();
+        let mut found = false;
+        for rule_dir in &self.config.rule_dirs {
+            if let Ok(entries) = std::fs::read_dir(dir.join(rule_dir)) {
+                found = true;
+                for entry in entries.filter_map(|e| e.ok()) {
+                    let path = entry.path();
+                    if path.is_file() && path.extension() == Some("md".as_ref()) {
+                        rule_files.push(path);
+                    }
+                }
+            }
+        }
+        rule_files.sort();
+        found.then_some(rule_files)
+    }
+
+    /// Find the nearest parent rule directory and return all .md files in it
     pub fn find_parent_rule_files(&self, target_path: &Path) -> Vec<PathBuf> {
         let mut current_dir = if target_path.is_dir() {
             Some(target_path)
@@ -54,18 +164,7 @@ impl RuleDiscovery {
         };
 
         while let Some(dir) = current_dir {
-            let synapse_dir = dir.join(".synapse");
-            if synapse_dir.exists() && synapse_dir.is_dir() {
-                let mut rule_files = Vec::new();
-                if let Ok(entries) = std::fs::read_dir(synapse_dir) {
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let path = entry.path();
-                        if path.is_file() && path.extension() == Some("md".as_ref()) {
-                            rule_files.push(path);
-                        }
-                    }
-                }
-                rule_files.sort();
+            if let Some(rule_files) = self.rule_files_in(dir) {
                 return rule_files;
             }
             current_dir = dir.parent();
@@ -74,7 +173,7 @@ impl RuleDiscovery {
         Vec::new()
     }
 
-    /// Find all parent .synapse directories and their .md files walking up the directory tree
+    /// Find all parent rule directories and their .md files walking up the directory tree
     pub fn find_inheritance_chain(&self, target_path: &Path) -> Vec<PathBuf> {
         let mut chain = Vec::new();
         let mut current_dir = if target_path.is_dir() {
@@ -84,20 +183,7 @@ impl RuleDiscovery {
         };
 
         while let Some(dir) = current_dir {
-            let synapse_dir = dir.join(".synapse");
-            if synapse_dir.exists() && synapse_dir.is_dir() {
-                if let Ok(entries) = std::fs::read_dir(synapse_dir) {
-                    let mut dir_rule_files = Vec::new();
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let path = entry.path();
-                        if path.is_file() && path.extension() == Some("md".as_ref()) {
-                            dir_rule_files.push(path);
-                        }
-                    }
-                    dir_rule_files.sort();
-                    chain.extend(dir_rule_files);
-                }
-            }
+            chain.extend(self.rule_files_in(dir).unwrap_or_default());
             current_dir = dir.parent();
         }
 
@@ -116,6 +202,7 @@ mod tests {
     use super::*;
     #[cfg(test)]
     use crate::test_helpers::test_helpers::{TestProject, create_rule_content};
+    use tempfile::TempDir;
 
     #[test]
     fn test_find_rule_files_empty_directory() {
@@ -131,7 +218,7 @@ mod tests {
         let project = TestProject::new().unwrap();
         let discovery = RuleDiscovery::new();
         
-        project.add_rule_file(".synapse/rules.md", &create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
+        project.add_rule_file(".synapse/rules.md", create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
         
         let result = discovery.find_rule_files(project.root()).unwrap();
         assert_eq!(result.len(), 1);
@@ -148,9 +235,9 @@ mod tests {
         project.create_nested_synapse(&["src", "src/utils"]).unwrap();
         
         // Create rule files at different levels
-        project.add_rule_file(".synapse/root.md", &create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
-        project.add_rule_file("src/.synapse/src_rules.md", &create_rule_content(&[("REQUIRED", "#[test]")])).unwrap();
-        project.add_rule_file("src/utils/.synapse/utils_rules.md", &create_rule_content(&[("STANDARD", "inline")])).unwrap();
+        project.add_rule_file(".synapse/root.md", create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
+        project.add_rule_file("src/.synapse/src_rules.md", create_rule_content(&[("REQUIRED", "#[test]")])).unwrap();
+        project.add_rule_file("src/utils/.synapse/utils_rules.md", create_rule_content(&[("STANDARD", "inline")])).unwrap();
         
         let result = discovery.find_rule_files(project.root()).unwrap();
         assert_eq!(result.len(), 3);
@@ -169,7 +256,7 @@ mod tests {
         // Create various files that should be ignored
         project.add_file("README.md", "# README").unwrap();
         project.add_file("rules.md", "# Not a rule file").unwrap();
-        project.add_rule_file(".synapse/actual_rule.md", &create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
+        project.add_rule_file(".synapse/actual_rule.md", create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
         
         let result = discovery.find_rule_files(project.root()).unwrap();
         assert_eq!(result.len(), 1);
@@ -181,7 +268,7 @@ mod tests {
         let project = TestProject::new().unwrap();
         let discovery = RuleDiscovery::new();
         
-        let rule_file = project.add_rule_file(".synapse/test_rule.md", &create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
+        let rule_file = project.add_rule_file(".synapse/test_rule.md", create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
         let other_file = project.add_file("other.md", "# Other file").unwrap();
         
         assert!(discovery.is_rule_file(&rule_file));
@@ -196,7 +283,7 @@ mod tests {
         
         // Create nested structure with rule file at root
         project.add_file("src/main.rs", "// main.rs").unwrap();
-        let root_rule_file = project.add_rule_file(".synapse/root_rules.md", &create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
+        let root_rule_file = project.add_rule_file(".synapse/root_rules.md", create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
         
         let file_path = project.path("src/main.rs");
         let result = discovery.find_parent_rule_files(&file_path);
@@ -226,8 +313,8 @@ mod tests {
         project.add_file("src/utils/deep/file.rs", "// deep file").unwrap();
         
         // Create rule files at root and src levels (skip utils level)
-        project.add_rule_file(".synapse/root_rules.md", &create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
-        project.add_rule_file("src/.synapse/src_rules.md", &create_rule_content(&[("REQUIRED", "#[test]")])).unwrap();
+        project.add_rule_file(".synapse/root_rules.md", create_rule_content(&[("FORBIDDEN", "TODO")])).unwrap();
+        project.add_rule_file("src/.synapse/src_rules.md", create_rule_content(&[("REQUIRED", "#[test]")])).unwrap();
         
         let target_file = project.path("src/utils/deep/file.rs");
         let chain = discovery.find_inheritance_chain(&target_file);
@@ -239,6 +326,75 @@ mod tests {
         assert!(!chain[1].to_string_lossy().contains("src/"));
     }
 
+    #[test]
+    fn test_find_rule_files_with_config() {
+        let project = TestProject::new().unwrap();
+        let rule = create_rule_content(&[("FORBIDDEN", "TODO")]);
+        project.add_rule_file(".synapse/root.md", &rule).unwrap();
+        project.add_rule_file("src/.rules/src.md", &rule).unwrap();
+        project.add_rule_file("vendor/lib/.rules/vendored.md", &rule).unwrap();
+        project.add_rule_file("a/b/c/d/.rules/deep.md", &rule).unwrap();
+        
+        let discovery = RuleDiscovery::with_config(DiscoveryConfig {
+            rule_dirs: vec![".rules".to_string()],
+            exclude: vec!["vendor/**".to_string()],
+            max_depth: Some(3),
+            ..DiscoveryConfig::default()
+        });
+        let result = discovery.find_rule_files(project.root()).unwrap();
+        
+        let names: Vec<_> = result.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(names, vec!["src.md"]);
+        assert!(discovery.is_rule_dir_name(OsStr::new(".rules")));
+        assert!(!discovery.is_rule_dir_name(OsStr::new(".synapse")));
+        
+        let file_path = project.path("src/main.rs");
+        assert_eq!(discovery.find_parent_rule_files(&file_path), result);
+    }
+
+    #[test]
+    fn test_max_depth_counts_directories_not_rule_dirs() {
+        let project = TestProject::new().unwrap();
+        let rule = create_rule_content(&[("FORBIDDEN", "TODO")]);
+        project.add_rule_file(".synapse/root.md", &rule).unwrap();
+        project.add_rule_file("src/.synapse/src.md", &rule).unwrap();
+        project.add_rule_file("src/utils/.synapse/utils.md", &rule).unwrap();
+
+        let names = |max_depth| {
+            let discovery = RuleDiscovery::with_config(DiscoveryConfig { max_depth: Some(max_depth), ..DiscoveryConfig::default() });
+            discovery.find_rule_files(project.root()).unwrap()
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(0), vec!["root.md"]);
+        assert_eq!(names(1), vec!["root.md", "src.md"]);
+        assert_eq!(names(2), vec!["root.md", "src.md", "utils.md"]);
+    }
+
+    #[test]
+    fn test_find_external_rule_files() {
+        let project = TestProject::new().unwrap();
+        let policies = TempDir::new().unwrap();
+        std::fs::create_dir_all(policies.path().join("security")).unwrap();
+        std::fs::write(policies.path().join("security/secrets.md"), "").unwrap();
+        std::fs::write(policies.path().join("style.md"), "").unwrap();
+        std::fs::write(policies.path().join("notes.txt"), "").unwrap();
+        project.add_file("org.md", "").unwrap();
+        
+        let discovery = RuleDiscovery::with_config(DiscoveryConfig {
+            external: vec![policies.path().to_path_buf(), PathBuf::from("org.md"), PathBuf::from("missing")],
+            ..DiscoveryConfig::default()
+        });
+        let result = discovery.find_external_rule_files(project.root());
+        
+        assert_eq!(result, vec![
+            policies.path().join("security/secrets.md"),
+            policies.path().join("style.md"),
+            project.root().join("org.md"),
+        ]);
+    }
+
     #[test]
     fn test_find_inheritance_chain_empty() {
         let project = TestProject::new().unwrap();
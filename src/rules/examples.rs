@@ -0,0 +1,246 @@
+//! Embedded rule examples (`synapse rules test`)
+//!
+//! A fenced code block tagged `pass` or `fail` belongs to the rule line above
+//! it and declares how that rule should treat the code:
+//!
+//! ````markdown
+//! FORBIDDEN: `literal:println!(` - Use the logger
+//!
+//! ```rust fail
+//! println!("debugging");
+//! ```
+//!
+//! ```rust pass file=src/logging.rs
+//! tracing::info!("ready");
+//! ```
+//! ````
+//!
+//! The language tag picks the extension of the pretend file the example is
+//! checked as; `file=` sets the whole path, which path and import rules need.
+
+use super::{RuleSystem, is_unmarked_file_error};
+use crate::check_rules;
+use crate::enforcement::lifecycle::without_rollout;
+use crate::models::CompiledRule;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What an example declares about its rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleExpectation {
+    /// The rule must report no violations
+    Pass,
+    /// The rule must report at least one violation
+    Fail,
+}
+
+/// Parsed info string of a ```` ``` ```` fence opening an example block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleFence {
+    pub expectation: ExampleExpectation,
+    pub file_path: PathBuf,
+}
+
+/// Parse a fence line such as ```` ```rust fail file=src/lib.rs ````
+///
+/// Returns `None` for lines that don't open a `pass`/`fail` example.
+pub fn parse_example_fence(line: &str) -> Option<ExampleFence> {
+    let info = line.trim().strip_prefix("```")?;
+    let mut expectation = None;
+    let mut language = None;
+    let mut file_path = None;
+
+    for token in info.split_whitespace() {
+        match token {
+            "pass" => expectation = Some(ExampleExpectation::Pass),
+            "fail" => expectation = Some(ExampleExpectation::Fail),
+            _ => match token.split_once('=') {
+                Some(("file", path)) => file_path = Some(PathBuf::from(path)),
+                _ => language = language.or(Some(token)),
+            },
+        }
+    }
+
+    let extension = match language {
+        Some("rust") | Some("rs") => "rs",
+        Some("python") | Some("py") => "py",
+        Some("typescript") | Some("ts") => "ts",
+        Some("javascript") | Some("js") => "js",
+        Some(other) => other,
+        None => "txt",
+    };
+
+    Some(ExampleFence {
+        expectation: expectation?,
+        file_path: file_path.unwrap_or_else(|| PathBuf::from(format!("example.{}", extension))),
+    })
+}
+
+/// A code example attached to a rule
+#[derive(Debug, Clone)]
+pub struct RuleExample {
+    /// Line of the opening fence in the rule file
+    pub line: usize,
+    pub fence: ExampleFence,
+    pub code: String,
+}
+
+/// Collect the examples of a rule file, grouped under the rule they follow
+///
+/// `rules` are the rule file's parsed rules with their line numbers, as
+/// returned by `RuleParser::parse_rule_lines`. Examples above the first rule
+/// belong to no rule and are returned with `None`.
+pub fn extract_examples(content: &str, rules: &[(usize, CompiledRule)]) -> Vec<(Option<CompiledRule>, RuleExample)> {
+    let mut examples = Vec::new();
+    let mut open: Option<(usize, ExampleFence, Vec<&str>)> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        match open.take() {
+            Some((start, fence, code)) if line.trim_start().starts_with("```") => {
+                let rule = rules.iter()
+                    .take_while(|(rule_line, _)| *rule_line < start)
+                    .last()
+                    .map(|(_, rule)| rule.clone());
+                examples.push((rule, RuleExample { line: start, fence, code: code.join("\n") }));
+            }
+            Some((start, fence, mut code)) => {
+                code.push(line);
+                open = Some((start, fence, code));
+            }
+            None => {
+                open = parse_example_fence(line).map(|fence| (line_number, fence, Vec::new()));
+            }
+        }
+    }
+
+    examples
+}
+
+/// Outcome of running one example against its rule
+#[derive(Debug, Clone)]
+pub struct ExampleResult {
+    pub rule_file: PathBuf,
+    pub line: usize,
+    /// Generated ID of the rule, or `None` for an example not under any rule
+    pub rule_name: Option<String>,
+    pub expectation: ExampleExpectation,
+    pub violations: usize,
+    /// Set when the example couldn't be checked at all
+    pub error: Option<String>,
+}
+
+impl ExampleResult {
+    /// Whether the rule treated the example as declared
+    pub fn passed(&self) -> bool {
+        if self.error.is_some() || self.rule_name.is_none() {
+            return false;
+        }
+        match self.expectation {
+            ExampleExpectation::Pass => self.violations == 0,
+            ExampleExpectation::Fail => self.violations > 0,
+        }
+    }
+}
+
+impl RuleSystem {
+    /// Run the embedded examples of every rule file under `root_path`
+    pub fn test_rules(&self, root_path: &Path) -> crate::Result<Vec<ExampleResult>> {
+        let mut results = Vec::new();
+
+        for path in self.discovery.find_rule_files(root_path)? {
+            let content = fs::read_to_string(&path)?;
+            if let Err(e) = self.parser.parse_content(&content, path.clone())
+                && is_unmarked_file_error(&e)
+            {
+                continue;
+            }
+
+            // Lines that fail to parse are reported by `synapse rules lint`
+            let rules: Vec<(usize, CompiledRule)> = self.parser.parse_rule_lines(&content)
+                .into_iter()
+                .filter_map(|(line, result)| result.ok().map(|rule| (line, rule)))
+                .collect();
+
+            for (rule, example) in extract_examples(&content, &rules) {
+                let mut result = ExampleResult {
+                    rule_file: path.clone(),
+                    line: example.line,
+                    rule_name: rule.as_ref().map(|r| r.rule.name.clone()),
+                    expectation: example.fence.expectation,
+                    violations: 0,
+                    error: None,
+                };
+                if let Some(rule) = rule {
+                    // Examples show what the rule matches, even while it is rolled out
+                    match check_rules(&example.fence.file_path, &example.code, &[without_rollout(&rule)]) {
+                        Ok(violations) => result.violations = violations.len(),
+                        Err(e) => result.error = Some(e.to_string()),
+                    }
+                }
+                results.push(result);
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_example_fence() {
+        let fence = parse_example_fence("```rust fail").unwrap();
+        assert_eq!(fence.expectation, ExampleExpectation::Fail);
+        assert_eq!(fence.file_path, PathBuf::from("example.rs"));
+
+        let fence = parse_example_fence("```pass file=src/db/pool.rs").unwrap();
+        assert_eq!(fence.expectation, ExampleExpectation::Pass);
+        assert_eq!(fence.file_path, PathBuf::from("src/db/pool.rs"));
+
+        assert!(parse_example_fence("```rust").is_none());
+        assert!(parse_example_fence("pass").is_none());
+    }
+
+    #[test]
+    fn test_examples_run_against_their_rule() {
+        let temp_dir = TempDir::new().unwrap();
+        let synapse_dir = temp_dir.path().join(".synapse");
+        fs::create_dir_all(&synapse_dir).unwrap();
+        fs::write(synapse_dir.join("rules.md"), r#"---
+mcp: synapse
+---
+FORBIDDEN: `literal:println!(` - Use the logger
+
+```rust fail
+println!("debugging");
+```
+
+```rust pass
+tracing::info!("ready");
+```
+
+REQUIRED: `SPDX-License-Identifier` - Add a license header
+
+```rust fail
+fn main() {}
+```
+
+```rust fail
+// SPDX-License-Identifier: MIT
+```
+"#).unwrap();
+
+        let results = RuleSystem::new().test_rules(temp_dir.path()).unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert!(results[..3].iter().all(ExampleResult::passed));
+        // The last example is mislabelled: the header is present, so the rule passes
+        assert!(!results[3].passed());
+        assert_eq!(results[3].line, 20);
+        assert_eq!(results[3].rule_name.as_deref(), Some("required-1-SPDX-License-Identifier"));
+    }
+}
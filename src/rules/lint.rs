@@ -0,0 +1,440 @@
+//! Static checks for rule files (`synapse rules lint`)
+//!
+//! Loading rules is forgiving: files without the `mcp: synapse` marker are
+//! skipped and broken files are dropped with a log warning. The linter loads
+//! every discovered rule file and reports those problems, plus mistakes that
+//! only show up across an inheritance chain, with file and line numbers.
+
+use super::packs::{PackRef, load_pack};
+use super::{RuleSystem, is_unmarked_file_error};
+use crate::models::{PatternMatcher, Rule, RuleSet, RuleType};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How serious a lint finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    /// Suspicious but the rules still load as written
+    Warning,
+    /// Rules are dropped or behave differently than written
+    Error,
+}
+
+/// A problem found in a rule file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LintIssue {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(file: &Path, line: Option<usize>, severity: LintSeverity, message: String) -> Self {
+        Self { file: file.to_path_buf(), line, severity, message }
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            LintSeverity::Warning => "warning",
+            LintSeverity::Error => "error",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.file.display(), line, severity, self.message),
+            None => write!(f, "{}: {}: {}", self.file.display(), severity, self.message),
+        }
+    }
+}
+
+/// A rule file that parsed, with the line of each rule and frontmatter entry
+struct LintedFile {
+    rule_set: RuleSet,
+    content: String,
+    rule_lines: HashMap<String, usize>,
+}
+
+impl LintedFile {
+    fn rule_line(&self, rule: &Rule) -> Option<usize> {
+        self.rule_lines.get(&rule.name).copied()
+    }
+
+    /// First line mentioning a frontmatter value such as an `inherits` path
+    fn line_of(&self, needle: &str) -> Option<usize> {
+        self.content.lines().position(|line| line.contains(needle)).map(|index| index + 1)
+    }
+}
+
+impl RuleSystem {
+    /// Load every rule file under `root_path` and report problems with them
+    ///
+    /// Issues are sorted by file and line.
+    pub fn lint_rules(&self, root_path: &Path) -> crate::Result<Vec<LintIssue>> {
+        let mut issues = Vec::new();
+        let mut files = Vec::new();
+
+        for (path, governs) in self.discover_rule_files(root_path)? {
+            let content = fs::read_to_string(&path)?;
+            let parsed = self.parser.parse_content(&content, path.clone())
+                .map(|rule_set| RuleSet { governs, ..rule_set });
+
+            if let Err(e) = &parsed && is_unmarked_file_error(e) {
+                issues.push(LintIssue::new(&path, None, LintSeverity::Warning, format!(
+                    "Skipped: {} (add `mcp: synapse` to the frontmatter)", e
+                )));
+                continue;
+            }
+
+            let mut rule_lines = HashMap::new();
+            let mut line_errors = false;
+            for (line, result) in self.parser.parse_rule_lines(&content) {
+                match result {
+                    Ok(compiled_rule) => {
+                        let rule = &compiled_rule.rule;
+                        let plain = matches!(compiled_rule.matcher, PatternMatcher::Literal(_) | PatternMatcher::Regex(_));
+                        if plain && !rule.is_secret() && rule.is_literal_fallback() {
+                            issues.push(LintIssue::new(&path, Some(line), LintSeverity::Warning, format!(
+                                "Pattern `{}` isn't a valid regex and is matched as literal text (prefix it with `literal:`, or with `regex:` to have it checked)",
+                                rule.pattern
+                            )));
+                        }
+                        rule_lines.insert(rule.name.clone(), line);
+                    }
+                    Err(e) => {
+                        line_errors = true;
+                        issues.push(LintIssue::new(&path, Some(line), LintSeverity::Error, e.to_string()));
+                    }
+                }
+            }
+
+            match parsed {
+                Ok(rule_set) => files.push(LintedFile { rule_set, content, rule_lines }),
+                // Errors on rule lines have been reported above with their line
+                Err(_) if line_errors => {}
+                Err(e) => issues.push(LintIssue::new(&path, None, LintSeverity::Error, e.to_string())),
+            }
+        }
+
+        self.lint_inherits(&files, &mut issues);
+        self.lint_extends(&files, &mut issues);
+        self.lint_chains(&files, &mut issues);
+        if !cfg!(feature = "ast-fixes") {
+            self.lint_structural(&files, &mut issues);
+        }
+
+        issues.sort();
+        issues.dedup();
+        Ok(issues)
+    }
+
+    /// Report `inherits` entries that don't resolve and inheritance cycles
+    fn lint_inherits(&self, files: &[LintedFile], issues: &mut Vec<LintIssue>) {
+        let mut files_by_dir: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+        for (index, file) in files.iter().enumerate() {
+            if let Some(dir) = self.governed_dir(&file.rule_set) {
+                files_by_dir.entry(dir).or_default().push(index);
+            }
+        }
+
+        let mut edges: Vec<Vec<usize>> = vec![Vec::new(); files.len()];
+        for (index, file) in files.iter().enumerate() {
+            let path = &file.rule_set.path;
+            for inherit_path in &file.rule_set.inherits {
+                let line = file.line_of(&inherit_path.to_string_lossy());
+                let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+                if !base_dir.join(inherit_path).exists() {
+                    issues.push(LintIssue::new(path, line, LintSeverity::Error, format!(
+                        "inherits path '{}' does not exist", inherit_path.display()
+                    )));
+                    continue;
+                }
+
+                match self.inherited_dir(path, inherit_path).and_then(|dir| files_by_dir.get(&dir)) {
+                    Some(targets) => edges[index].extend(targets.iter().copied().filter(|t| *t != index)),
+                    None => issues.push(LintIssue::new(path, line, LintSeverity::Error, format!(
+                        "inherits path '{}' contains no synapse rule files", inherit_path.display()
+                    ))),
+                }
+            }
+        }
+
+        // Depth-first search; an edge back onto the current stack closes a cycle
+        fn visit(node: usize, edges: &[Vec<usize>], state: &mut [u8], stack: &mut Vec<usize>, cycles: &mut Vec<Vec<usize>>) {
+            state[node] = 1;
+            stack.push(node);
+            for &next in &edges[node] {
+                match state[next] {
+                    0 => visit(next, edges, state, stack, cycles),
+                    1 => {
+                        let start = stack.iter().position(|n| *n == next).unwrap_or(0);
+                        cycles.push(stack[start..].to_vec());
+                    }
+                    _ => {}
+                }
+            }
+            stack.pop();
+            state[node] = 2;
+        }
+
+        let mut state = vec![0u8; files.len()];
+        let mut cycles = Vec::new();
+        for node in 0..files.len() {
+            if state[node] == 0 {
+                visit(node, &edges, &mut state, &mut Vec::new(), &mut cycles);
+            }
+        }
+
+        for cycle in cycles {
+            let closing = &files[*cycle.last().unwrap()];
+            let first = &files[cycle[0]];
+            let chain = cycle.iter()
+                .chain(std::iter::once(&cycle[0]))
+                .map(|index| files[*index].rule_set.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            let line = closing.rule_set.inherits.iter()
+                .find(|inherit| self.inherited_dir(&closing.rule_set.path, inherit) == self.governed_dir(&first.rule_set))
+                .and_then(|inherit| closing.line_of(&inherit.to_string_lossy()));
+            issues.push(LintIssue::new(&closing.rule_set.path, line, LintSeverity::Error, format!(
+                "Inheritance cycle: {}", chain
+            )));
+        }
+    }
+
+    /// Report `extends` entries whose pack can't be loaded
+    fn lint_extends(&self, files: &[LintedFile], issues: &mut Vec<LintIssue>) {
+        for file in files {
+            let path = &file.rule_set.path;
+            for entry in &file.rule_set.extends {
+                let loaded = PackRef::parse(entry).and_then(|pack| load_pack(&self.parser, &self.discovery, path, &pack));
+                if let Err(e) = loaded {
+                    issues.push(LintIssue::new(path, file.line_of(entry), LintSeverity::Error, format!(
+                        "extends entry '{}' can't be loaded: {}", entry, e
+                    )));
+                }
+            }
+        }
+    }
+
+    /// Report `[ast]` rules, including those pulled in from packs, in a build
+    /// without the `ast-fixes` feature, where they never report anything
+    fn lint_structural(&self, files: &[LintedFile], issues: &mut Vec<LintIssue>) {
+        const DISABLED: &str = "synapse was built without the `ast-fixes` feature";
+        for file in files {
+            let path = &file.rule_set.path;
+            for rule in file.rule_set.rules.iter().filter(|rule| rule.is_structural()) {
+                issues.push(LintIssue::new(path, file.rule_line(rule), LintSeverity::Warning, format!(
+                    "Structural rule '{}' is never checked: {}", rule.name, DISABLED
+                )));
+            }
+            for entry in &file.rule_set.extends {
+                let structural = PackRef::parse(entry)
+                    .and_then(|pack| load_pack(&self.parser, &self.discovery, path, &pack))
+                    .map(|rule_sets| rule_sets.iter().flat_map(|set| &set.rules).filter(|rule| rule.is_structural()).count())
+                    .unwrap_or(0);
+                if structural > 0 {
+                    issues.push(LintIssue::new(path, file.line_of(entry), LintSeverity::Warning, format!(
+                        "{} structural rule(s) from '{}' are never checked: {}", structural, entry, DISABLED
+                    )));
+                }
+            }
+        }
+    }
+
+    /// Report duplicate rule IDs, contradicting rules and unused `overrides`
+    /// within each inheritance chain
+    fn lint_chains(&self, files: &[LintedFile], issues: &mut Vec<LintIssue>) {
+        let mut rule_sets: Vec<RuleSet> = files.iter().map(|f| f.rule_set.clone()).collect();
+        self.load_packs(&mut rule_sets);
+        let index_by_path: HashMap<&PathBuf, usize> = files.iter()
+            .enumerate()
+            .map(|(index, file)| (&file.rule_set.path, index))
+            .collect();
+        let mut used_overrides: HashSet<(usize, &str)> = HashSet::new();
+        let mut seen_dirs = HashSet::new();
+
+        for file in files {
+            let Some(dir) = self.governed_dir(&file.rule_set) else {
+                continue;
+            };
+            if !seen_dirs.insert(dir.clone()) {
+                continue;
+            }
+
+            // The chain that applies to any file directly in this directory
+            let composite = self.rules_for_path(&dir.join("__synapse_lint__"), &rule_sets);
+            let mut chain: Vec<usize> = composite.inheritance_chain.iter()
+                .filter_map(|path| index_by_path.get(path).copied())
+                .collect();
+            chain.sort_by(|a, b| files[*a].rule_set.path.cmp(&files[*b].rule_set.path));
+
+            let rules: Vec<(usize, &Rule)> = chain.iter()
+                .flat_map(|index| files[*index].rule_set.rules.iter().map(move |rule| (*index, rule)))
+                .collect();
+            // Overrides may also target rules from extended packs
+            let pack_rules: Vec<&Rule> = composite.inheritance_chain.iter()
+                .filter(|path| !index_by_path.contains_key(path))
+                .filter_map(|path| rule_sets.iter().find(|rule_set| &rule_set.path == path))
+                .flat_map(|rule_set| rule_set.rules.iter())
+                .collect();
+
+            for (position, (index, rule)) in rules.iter().enumerate() {
+                let file = &files[*index];
+                for (other_index, other) in &rules[..position] {
+                    let other_file = &files[*other_index];
+                    if other.name == rule.name && other_index != index {
+                        issues.push(LintIssue::new(&file.rule_set.path, file.rule_line(rule), LintSeverity::Warning, format!(
+                            "Duplicate rule ID '{}' (also defined in {}); overrides will match both",
+                            rule.name, other_file.rule_set.path.display()
+                        )));
+                    }
+                    if contradicts(rule, other) {
+                        issues.push(LintIssue::new(&file.rule_set.path, file.rule_line(rule), LintSeverity::Error, format!(
+                            "{:?} rule on `{}` contradicts {:?} rule '{}' in {}",
+                            rule.rule_type, rule.pattern, other.rule_type, other.name, other_file.rule_set.path.display()
+                        )));
+                    }
+                }
+            }
+
+            for index in &chain {
+                let rule_set = &files[*index].rule_set;
+                let override_ids = rule_set.overrides.iter()
+                    .chain(rule_set.modifications.iter().map(|m| &m.rule_id));
+                for override_id in override_ids {
+                    let matches = |rule: &Rule| &rule.id == override_id || &rule.name == override_id;
+                    if rules.iter().any(|(_, rule)| matches(rule)) || pack_rules.iter().any(|rule| matches(rule)) {
+                        used_overrides.insert((*index, override_id.as_str()));
+                    }
+                }
+            }
+        }
+
+        for (index, file) in files.iter().enumerate() {
+            let override_ids = file.rule_set.overrides.iter()
+                .chain(file.rule_set.modifications.iter().map(|m| &m.rule_id));
+            for override_id in override_ids {
+                if !used_overrides.contains(&(index, override_id.as_str())) {
+                    issues.push(LintIssue::new(&file.rule_set.path, file.line_of(override_id), LintSeverity::Warning, format!(
+                        "overrides entry '{}' matches no rule in the inheritance chain", override_id
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// A REQUIRED and a FORBIDDEN rule on the same plain pattern can never both pass
+fn contradicts(a: &Rule, b: &Rule) -> bool {
+    let plain = |rule: &Rule| !["max", "min", "threshold"].iter().any(|key| rule.metadata.contains_key(*key));
+    let types = [&a.rule_type, &b.rule_type];
+    types.contains(&&RuleType::Required)
+        && types.contains(&&RuleType::Forbidden)
+        && a.pattern == b.pattern
+        && a.pattern_kind() == b.pattern_kind()
+        && a.metadata.get("target") == b.metadata.get("target")
+        && plain(a)
+        && plain(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::TestProject;
+
+
+    fn lint(root: &Path) -> Vec<LintIssue> {
+        RuleSystem::new().lint_rules(root).unwrap()
+    }
+
+    #[test]
+    fn test_lint_clean_project() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\n").unwrap();
+
+        assert!(lint(project.root()).is_empty());
+    }
+
+    #[test]
+    fn test_lint_reports_bad_lines_and_skipped_files() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md", "---\nmcp: synapse\n---\n# Rules\nFORBIDDEN: `println!(` - Use the logger\nFORBIDDEN: `regex:dbg!(` - No dbg!\n").unwrap();
+        project.add_rule_file(".synapse/notes.md", "# Just notes\n").unwrap();
+
+        let issues = lint(project.root());
+
+        assert_eq!(issues.len(), 3);
+        let notes = issues.iter().find(|i| i.file.ends_with("notes.md")).unwrap();
+        assert_eq!(notes.severity, LintSeverity::Warning);
+        let literal = issues.iter().find(|i| i.line == Some(5)).unwrap();
+        assert_eq!(literal.severity, LintSeverity::Warning);
+        assert!(literal.message.contains("matched as literal text"));
+        let bad_regex = issues.iter().find(|i| i.line == Some(6)).unwrap();
+        assert_eq!(bad_regex.severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_lint_inherits_and_cycles() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file("a/.synapse/rules.md", "---\nmcp: synapse\ninherits: [\"../../b\"]\n---\nFORBIDDEN: `x` - x\n").unwrap();
+        project.add_rule_file("b/.synapse/rules.md", "---\nmcp: synapse\ninherits: [\"../../a\"]\n---\nFORBIDDEN: `y` - y\n").unwrap();
+        project.add_rule_file("c/.synapse/rules.md", "---\nmcp: synapse\ninherits: [\"../../missing\"]\n---\nFORBIDDEN: `z` - z\n").unwrap();
+
+        let issues = lint(project.root());
+
+        assert!(issues.iter().any(|i| i.message.starts_with("Inheritance cycle")));
+        let missing = issues.iter().find(|i| i.message.contains("does not exist")).unwrap();
+        assert!(missing.file.ends_with("c/.synapse/rules.md"));
+        assert_eq!(missing.line, Some(3));
+    }
+
+    #[test]
+    fn test_lint_extends() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file("shared/rules.md", "---\nmcp: synapse\nversion: 1\n---\nFORBIDDEN: `eval` - No eval\n").unwrap();
+        project.add_rule_file("a/.synapse/rules.md",
+            "---\nmcp: synapse\nextends: [\"pack:../../shared@2\"]\noverrides: [\"forbidden-0-eval\"]\n---\n").unwrap();
+        project.add_rule_file("b/.synapse/rules.md",
+            "---\nmcp: synapse\nextends: [\"pack:../../shared\"]\noverrides: [\"forbidden-0-eval\"]\n---\n").unwrap();
+
+        let issues = lint(project.root());
+
+        let version = issues.iter().find(|i| i.message.contains("requires version 2")).unwrap();
+        assert!(version.file.ends_with("a/.synapse/rules.md"));
+        assert_eq!(version.line, Some(3));
+        // Overrides of pack rules count as used
+        assert!(!issues.iter().any(|i| i.file.ends_with("b/.synapse/rules.md")));
+    }
+
+    #[cfg(not(feature = "ast-fixes"))]
+    #[test]
+    fn test_lint_structural_rules_without_feature() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md",
+            "---\nmcp: synapse\nextends: [\"builtin:rust-safety\"]\n---\nFORBIDDEN[ast]: `pub-fn-without-doc` - Document it\n").unwrap();
+
+        let issues = lint(project.root());
+
+        let rule = issues.iter().find(|i| i.message.starts_with("Structural rule")).unwrap();
+        assert_eq!((rule.severity, rule.line), (LintSeverity::Warning, Some(5)));
+        assert!(issues.iter().any(|i| i.message.contains("from 'builtin:rust-safety'") && i.line == Some(3)));
+    }
+
+    #[test]
+    fn test_lint_chain_conflicts_duplicates_and_overrides() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md", "---\nmcp: synapse\n---\nFORBIDDEN: `async` - Keep it sync\n").unwrap();
+        project.add_rule_file("src/.synapse/rules.md",
+            "---\nmcp: synapse\noverrides: [\"no-such-rule\"]\n---\nFORBIDDEN: `async` - Again\nREQUIRED: `async` - Must be async\n").unwrap();
+
+        let issues = lint(project.root());
+        let child: Vec<&LintIssue> = issues.iter().filter(|i| i.file.ends_with("src/.synapse/rules.md")).collect();
+
+        assert!(child.iter().any(|i| i.message.starts_with("Duplicate rule ID 'forbidden-0-async'") && i.line == Some(5)));
+        assert!(child.iter().any(|i| i.message.contains("contradicts") && i.line == Some(6)));
+        assert!(child.iter().any(|i| i.message.contains("'no-such-rule'") && i.line == Some(3)));
+    }
+}
@@ -1,10 +1,21 @@
 pub mod discovery;
+pub mod examples;
+pub mod lint;
+pub mod packs;
 pub mod parser;
+pub mod resolution;
 
 pub use discovery::RuleDiscovery;
+pub use examples::{ExampleExpectation, ExampleResult};
+pub use lint::{LintIssue, LintSeverity};
 pub use parser::RuleParser;
+pub use resolution::{RuleResolution, RuleSource, RuleSourceKind};
+use packs::PackRef;
+use resolution::{AppliedRule, DirectoryStep, OverriddenRule, OverrideEntry, ShadowedRule};
+use crate::config::DiscoveryConfig;
 use crate::models::{RuleSet, CompositeRules};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
 
 /// Main interface for rule system
 #[derive(Debug)]
@@ -21,59 +32,133 @@ impl RuleSystem {
         }
     }
 
+    /// Rule system using the `[discovery]` settings of the project's config.toml
+    pub fn for_project(root_path: &Path) -> Self {
+        match crate::config::Config::load_from_dir(root_path) {
+            Ok(config) => Self::new().with_discovery(config.discovery),
+            Err(e) => {
+                warn!("Failed to load discovery settings from {}: {}", root_path.display(), e);
+                Self::new()
+            }
+        }
+    }
+
+    pub fn with_discovery(mut self, config: DiscoveryConfig) -> Self {
+        self.discovery = RuleDiscovery::with_config(config);
+        self
+    }
+
+    pub fn discovery(&self) -> &RuleDiscovery {
+        &self.discovery
+    }
+
+    /// Rule files to load for a project: those found under `root_path`,
+    /// then external policies paired with the directory they govern
+    fn discover_rule_files(&self, root_path: &Path) -> crate::Result<Vec<(PathBuf, Option<PathBuf>)>> {
+        let project_root = root_path.canonicalize().ok();
+        let mut rule_files: Vec<(PathBuf, Option<PathBuf>)> = self.discovery.find_rule_files(root_path)?
+            .into_iter()
+            .map(|path| (path, None))
+            .collect();
+        rule_files.extend(self.discovery.find_external_rule_files(root_path)
+            .into_iter()
+            .map(|path| (path, project_root.clone())));
+        Ok(rule_files)
+    }
+
     /// Find and parse all .synapse.md files in a directory tree
     pub fn load_rules(&self, root_path: &PathBuf) -> crate::Result<Vec<RuleSet>> {
-        let rule_files = self.discovery.find_rule_files(root_path)?;
+        let rule_files = self.discover_rule_files(root_path)?;
         let mut rule_sets = Vec::new();
 
-        for file_path in rule_files {
+        for (file_path, governs) in rule_files {
             match self.parser.parse_rule_file(&file_path) {
-                Ok(rule_set) => rule_sets.push(rule_set),
+                Ok(rule_set) => rule_sets.push(RuleSet { governs, ..rule_set }),
+                Err(e) if is_unmarked_file_error(&e) => {
+                    // Files without the synapse marker aren't rule files
+                    debug!("Skipping {}: {}", file_path.display(), e);
+                }
                 Err(e) => {
-                    // Only warn for actual parse errors, silently skip non-synapse files
-                    let error_msg = e.to_string();
-                    if error_msg.contains("not marked for synapse MCP") 
-                        || error_msg.contains("missing 'mcp' field") 
-                        || error_msg.contains("no YAML frontmatter") {
-                        // Silently skip files without synapse marker
-                        continue;
-                    } else {
-                        eprintln!("Warning: Failed to parse rule file {}: {}", file_path.display(), e);
-                        continue;
-                    }
+                    warn!("Failed to parse rule file {} (run `synapse rules lint` for details): {}", file_path.display(), e);
                 }
             }
         }
 
+        self.load_packs(&mut rule_sets);
+        if !cfg!(feature = "ast-fixes") {
+            warn_inactive_structural_rules(&rule_sets);
+        }
         Ok(rule_sets)
     }
 
+    /// Append the rule sets of every pack named in `extends:`, including packs
+    /// extended by other packs
+    fn load_packs(&self, rule_sets: &mut Vec<RuleSet>) {
+        let mut loaded: std::collections::HashSet<PathBuf> = rule_sets.iter()
+            .map(|rule_set| rule_set.path.canonicalize().unwrap_or_else(|_| rule_set.path.clone()))
+            .collect();
+
+        let mut index = 0;
+        while index < rule_sets.len() {
+            let rule_file = rule_sets[index].path.clone();
+            for entry in rule_sets[index].extends.clone() {
+                let pack_sets = PackRef::parse(&entry)
+                    .and_then(|pack| packs::load_pack(&self.parser, &self.discovery, &rule_file, &pack));
+                match pack_sets {
+                    Ok(pack_sets) => {
+                        for pack_set in pack_sets {
+                            if loaded.insert(pack_set.path.clone()) {
+                                rule_sets.push(pack_set);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to load pack '{}' extended by {} (run `synapse rules lint` for details): {}", entry, rule_file.display(), e);
+                    }
+                }
+            }
+            index += 1;
+        }
+    }
+
     /// Build composite rules for a specific file path considering inheritance
     pub fn rules_for_path(&self, target_path: &PathBuf, rule_sets: &[RuleSet]) -> CompositeRules {
-        let mut composite = CompositeRules::new();
-        let mut inheritance_chain = Vec::new();
+        self.resolve_path(target_path, rule_sets).into_composite()
+    }
+
+    /// Resolve the rules for a path, recording every step for `synapse explain`
+    pub fn resolve_path(&self, target_path: &Path, rule_sets: &[RuleSet]) -> RuleResolution {
+        let mut resolution = RuleResolution {
+            target: target_path.to_path_buf(),
+            directories: Vec::new(),
+            sources: Vec::new(),
+            overrides: Vec::new(),
+            overridden: Vec::new(),
+            shadowed: Vec::new(),
+            applied: Vec::new(),
+            stopped_at_root: None,
+        };
         let mut applicable_rule_sets = Vec::new();
         let mut visited_paths = std::collections::HashSet::new();
 
         // Create a map from canonical DIRECTORY path to its RuleSets
-        // Rules in .synapse/ directories should apply to their parent directory
+        // Rules in .synapse/ directories should apply to their parent directory.
+        // Sorting keeps resolution order independent of how rule sets were stored.
+        // External policies rank after the project's own files in the same directory.
+        let mut sorted_rule_sets: Vec<&RuleSet> = rule_sets.iter().collect();
+        sorted_rule_sets.sort_by(|a, b| (a.governs.is_some(), &a.path).cmp(&(b.governs.is_some(), &b.path)));
         let mut dir_rule_map: std::collections::HashMap<PathBuf, Vec<&RuleSet>> = std::collections::HashMap::new();
-        for rule_set in rule_sets.iter() {
-            if let Some(rule_parent) = rule_set.path.parent() {
-                // Check if this is a .synapse directory
-                if rule_parent.file_name() == Some(".synapse".as_ref()) {
-                    // Rules in .synapse/ directory apply to the parent of .synapse
-                    if let Some(synapse_parent) = rule_parent.parent() {
-                        if let Ok(canon_dir) = synapse_parent.canonicalize() {
-                            dir_rule_map.entry(canon_dir).or_insert_with(Vec::new).push(rule_set);
-                        }
-                    }
-                } else {
-                    // Legacy: rules in directory apply to that directory (old .synapse.md style)
-                    if let Ok(canon_dir) = rule_parent.canonicalize() {
-                        dir_rule_map.entry(canon_dir).or_insert_with(Vec::new).push(rule_set);
-                    }
-                }
+        for rule_set in sorted_rule_sets.iter().copied() {
+            if let Some(canon_dir) = self.governed_dir(rule_set) {
+                dir_rule_map.entry(canon_dir).or_default().push(rule_set);
+            }
+        }
+
+        // Rule sets that `extends:` entries can name
+        let mut pack_map: std::collections::HashMap<PathBuf, Vec<&RuleSet>> = std::collections::HashMap::new();
+        for rule_set in &sorted_rule_sets {
+            if let Some(key) = packs::pack_key_of(rule_set) {
+                pack_map.entry(key).or_default().push(rule_set);
             }
         }
 
@@ -86,83 +171,187 @@ impl RuleSystem {
         // Walk up the directory tree, looking up DIRECTORIES in the map
         let mut current_dir = canonical_target.parent();
         while let Some(dir) = current_dir {
-            if let Ok(canon_dir) = dir.canonicalize() {
-                if let Some(rule_sets_in_dir) = dir_rule_map.get(&canon_dir) {
-                    // Process all rule sets in this directory
-                    for rule_set in rule_sets_in_dir {
-                        if visited_paths.insert(rule_set.path.clone()) {
-                            applicable_rule_sets.push(*rule_set);
-                            self.add_inherited_rule_sets(
-                                rule_set,
-                                &dir_rule_map,
-                                &mut applicable_rule_sets,
-                                &mut visited_paths,
-                            );
-                        }
-                    }
+            let rule_sets_in_dir = dir.canonicalize().ok().and_then(|canon_dir| dir_rule_map.get(&canon_dir));
+            resolution.directories.push(DirectoryStep {
+                dir: dir.to_path_buf(),
+                rule_files: rule_sets_in_dir
+                    .map(|sets| sets.iter().map(|rule_set| rule_set.path.clone()).collect())
+                    .unwrap_or_default(),
+            });
+
+            // Process all rule sets in this directory
+            for rule_set in rule_sets_in_dir.into_iter().flatten() {
+                if visited_paths.insert(rule_set.path.clone()) {
+                    applicable_rule_sets.push((*rule_set, RuleSourceKind::Directory));
+                    self.add_inherited_rule_sets(
+                        rule_set,
+                        &dir_rule_map,
+                        &pack_map,
+                        &mut applicable_rule_sets,
+                        &mut visited_paths,
+                    );
                 }
             }
+
+            // `root: true` makes this directory the top of the walk
+            if let Some(root_set) = rule_sets_in_dir.into_iter().flatten().find(|rule_set| rule_set.is_root()) {
+                resolution.stopped_at_root = Some(root_set.path.clone());
+                break;
+            }
             current_dir = dir.parent();
         }
 
-        inheritance_chain.extend(visited_paths.iter().cloned());
-
-        // Second, collect all overrides from all rule sets (children override parents)
-        for rule_set in &applicable_rule_sets {
+        // Rule files earlier in `applicable_rule_sets` take precedence: the
+        // nearest directory first, each file followed by the files it inherits
+        // in declaration order. A file's overrides only reach rules from files
+        // of equal or lower precedence.
+        let mut modifications = Vec::new();
+        for (position, (rule_set, _)) in applicable_rule_sets.iter().enumerate() {
             for override_id in &rule_set.overrides {
-                composite = composite.add_override(override_id.clone());
+                resolution.overrides.push(OverrideEntry {
+                    rule_id: override_id.clone(),
+                    declared_in: rule_set.path.clone(),
+                    position,
+                });
             }
+            modifications.extend(rule_set.modifications.iter().map(|m| (position, m, &rule_set.path)));
         }
 
-        // Third, add rules from all levels, skipping overridden ones
-        // Process in reverse order so children's rules come first (proper precedence)
-        for rule_set in applicable_rule_sets.iter().rev() {
-            for rule in rule_set.rules.iter().rev() {
-                // Skip if rule is overridden (check both ID and name for compatibility)
-                if !composite.overridden_rules.contains(&rule.id) && 
-                   !composite.overridden_rules.contains(&rule.name) {
-                    composite = composite.add_rule(rule.clone());
+        // Add rules in precedence order, skipping overridden ones and
+        // duplicates of a rule already added from a nearer file
+        for (position, (rule_set, _)) in applicable_rule_sets.iter().enumerate() {
+            for rule in &rule_set.rules {
+                // Check both ID and name for compatibility
+                let override_entry = resolution.overrides.iter()
+                    .find(|entry| entry.position <= position && RuleResolution::rule_matches(rule, &entry.rule_id));
+                if let Some(entry) = override_entry {
+                    resolution.overridden.push(OverriddenRule {
+                        rule: rule.clone(),
+                        source: rule_set.path.clone(),
+                        overridden_by: entry.declared_in.clone(),
+                    });
+                    continue;
                 }
+
+                if let Some(winner) = resolution.applied.iter().find(|applied| RuleResolution::same_rule(&applied.rule, rule)) {
+                    resolution.shadowed.push(ShadowedRule {
+                        rule: rule.clone(),
+                        source: rule_set.path.clone(),
+                        shadowed_by: winner.source.clone(),
+                    });
+                    continue;
+                }
+
+                let modification = modifications.iter()
+                    .find(|(declared_at, m, _)| *declared_at <= position && RuleResolution::rule_matches(rule, &m.rule_id));
+                resolution.applied.push(AppliedRule {
+                    rule: modification.map_or_else(|| rule.clone(), |(_, m, _)| m.apply(rule)),
+                    source: rule_set.path.clone(),
+                    modified_by: modification.map(|(_, _, path)| (*path).clone()),
+                });
             }
         }
 
-        composite.with_inheritance_chain(inheritance_chain)
+        resolution.sources = applicable_rule_sets.into_iter()
+            .map(|(rule_set, kind)| RuleSource { path: rule_set.path.clone(), kind })
+            .collect();
+        resolution
     }
 
     /// Helper method to recursively add inherited rule sets
     fn add_inherited_rule_sets<'a>(&self,
                                    rule_set: &RuleSet,
                                    dir_rule_map: &'a std::collections::HashMap<PathBuf, Vec<&RuleSet>>,
-                                   applicable_rule_sets: &mut Vec<&'a RuleSet>,
+                                   pack_map: &'a std::collections::HashMap<PathBuf, Vec<&RuleSet>>,
+                                   applicable_rule_sets: &mut Vec<(&'a RuleSet, RuleSourceKind)>,
                                    visited_paths: &mut std::collections::HashSet<PathBuf>) {
-        for inherit_path in &rule_set.inherits {
-            let base_dir = rule_set.path.parent().unwrap_or_else(|| std::path::Path::new("."));
-            if let Ok(absolute_inherit_path) = base_dir.join(inherit_path).canonicalize() {
-                // The inherited path could be a file or a directory. We check for both.
-                // Case 1: Path is a directory.
-                if let Some(inherited_rule_sets) = dir_rule_map.get(&absolute_inherit_path) {
-                    for inherited_rule_set in inherited_rule_sets {
-                        if visited_paths.insert(inherited_rule_set.path.clone()) {
-                            applicable_rule_sets.push(*inherited_rule_set);
-                            self.add_inherited_rule_sets(inherited_rule_set, dir_rule_map, applicable_rule_sets, visited_paths);
-                        }
-                    }
-                // Case 2: Path is a file, so we get its parent directory.
-                } else if let Some(parent_dir) = absolute_inherit_path.parent() {
-                    if let Some(inherited_rule_sets) = dir_rule_map.get(parent_dir) {
-                        for inherited_rule_set in inherited_rule_sets {
-                            if visited_paths.insert(inherited_rule_set.path.clone()) {
-                                applicable_rule_sets.push(*inherited_rule_set);
-                                self.add_inherited_rule_sets(inherited_rule_set, dir_rule_map, applicable_rule_sets, visited_paths);
-                            }
-                        }
-                    }
-                }
+        let inherited = rule_set.inherits.iter()
+            .filter_map(|inherit_path| self.inherited_dir(&rule_set.path, inherit_path))
+            .filter_map(|dir| dir_rule_map.get(&dir))
+            .flat_map(|rule_sets| rule_sets.iter().map(|inherited| (*inherited, RuleSourceKind::Inherited { from: rule_set.path.clone() })));
+
+        // Packs rank after the explicit `inherits` entries
+        let extended = rule_set.extends.iter()
+            .filter_map(|entry| {
+                let key = PackRef::parse(entry).ok()?.key(&self.discovery, &rule_set.path)?;
+                pack_map.get(&key).map(|rule_sets| (entry, rule_sets))
+            })
+            .flat_map(|(entry, rule_sets)| rule_sets.iter().map(move |pack_set| (*pack_set, RuleSourceKind::Extended {
+                from: rule_set.path.clone(),
+                pack: entry.clone(),
+            })));
+
+        for (next, kind) in inherited.chain(extended).collect::<Vec<_>>() {
+            if visited_paths.insert(next.path.clone()) {
+                applicable_rule_sets.push((next, kind));
+                self.add_inherited_rule_sets(next, dir_rule_map, pack_map, applicable_rule_sets, visited_paths);
             }
         }
     }
 }
 
+impl RuleSystem {
+    /// Canonical directory whose files a rule set applies to
+    ///
+    /// Rules in a rule directory (`.synapse/` by default) apply to its parent;
+    /// legacy `.synapse.md` files apply to the directory they sit in, and
+    /// external policies to the directory recorded in `governs`.
+    pub(crate) fn governed_dir(&self, rule_set: &RuleSet) -> Option<PathBuf> {
+        if let Some(dir) = &rule_set.governs {
+            return dir.canonicalize().ok();
+        }
+        let rule_parent = rule_set.path.parent()?;
+        let dir = if rule_parent.file_name().is_some_and(|name| self.discovery.is_rule_dir_name(name)) {
+            rule_parent.parent()?
+        } else {
+            rule_parent
+        };
+        dir.canonicalize().ok()
+    }
+
+    /// Canonical directory an `inherits` entry refers to
+    ///
+    /// The entry is resolved relative to the rule file and may name a directory,
+    /// a rule directory or a rule file; the latter two refer to the directory
+    /// those rules govern.
+    pub(crate) fn inherited_dir(&self, rule_file: &Path, inherit_path: &Path) -> Option<PathBuf> {
+        let base_dir = rule_file.parent().unwrap_or_else(|| Path::new("."));
+        let target = base_dir.join(inherit_path).canonicalize().ok()?;
+        let dir = if target.is_dir() { target } else { target.parent()?.to_path_buf() };
+        if dir.file_name().is_some_and(|name| self.discovery.is_rule_dir_name(name)) {
+            dir.parent().map(Path::to_path_buf)
+        } else {
+            Some(dir)
+        }
+    }
+}
+
+/// Report the `[ast]` rules of each rule set, packs included, that this
+/// build can't check; returns how many there are
+fn warn_inactive_structural_rules(rule_sets: &[RuleSet]) -> usize {
+    let mut inactive = 0;
+    for rule_set in rule_sets {
+        let structural = rule_set.rules.iter().filter(|rule| rule.is_structural()).count();
+        if structural > 0 {
+            warn!(
+                "{} structural rule(s) in {} are never checked: synapse was built without the `ast-fixes` feature",
+                structural,
+                rule_set.path.display()
+            );
+        }
+        inactive += structural;
+    }
+    inactive
+}
+
+/// Whether a parse error means the file lacks the `mcp: synapse` marker
+pub(crate) fn is_unmarked_file_error(error: &crate::SynapseError) -> bool {
+    let message = error.to_string();
+    message.contains("not marked for synapse MCP")
+        || message.contains("missing 'mcp' field")
+        || message.contains("no YAML frontmatter")
+}
+
 impl Default for RuleSystem {
     fn default() -> Self {
         Self::new()
@@ -172,7 +361,9 @@ impl Default for RuleSystem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::test_helpers::TestProject;
     use tempfile::TempDir;
+    use crate::models::RuleType;
 
     #[test]
     fn test_rule_system_creation() {
@@ -201,4 +392,135 @@ mod tests {
         assert_eq!(composite.applicable_rules.len(), 0);
         assert_eq!(composite.inheritance_chain.len(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_resolve_path_traces_sources_and_overrides() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file(".synapse/base.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\n").unwrap();
+        project.add_rule_file("shared/.synapse/shared.md", "---\nmcp: synapse\n---\nREQUIRED: `SPDX` - License header\n").unwrap();
+        project.add_rule_file("src/.synapse/src.md",
+            "---\nmcp: synapse\ninherits: [\"../../shared\"]\noverrides: [\"forbidden-0-TODO\"]\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        project.add_file("src/main.rs", "fn main() {}\n").unwrap();
+
+        let rule_system = RuleSystem::new();
+        let rule_sets = rule_system.load_rules(&root.to_path_buf()).unwrap();
+        let resolution = rule_system.resolve_path(&root.join("src/main.rs"), &rule_sets);
+
+        let sources: Vec<_> = resolution.sources.iter()
+            .map(|s| s.path.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(sources, vec![
+            PathBuf::from("src/.synapse/src.md"),
+            PathBuf::from("shared/.synapse/shared.md"),
+            PathBuf::from(".synapse/base.md"),
+        ]);
+        assert!(matches!(resolution.sources[1].kind, RuleSourceKind::Inherited { .. }));
+        assert_eq!(resolution.directories[0].rule_files.len(), 1);
+
+        assert_eq!(resolution.overridden.len(), 1);
+        assert_eq!(resolution.overridden[0].rule.name, "forbidden-0-TODO");
+        assert!(resolution.overridden[0].overridden_by.ends_with("src/.synapse/src.md"));
+
+        let composite = resolution.into_composite();
+        assert_eq!(composite.applicable_rules.len(), 2);
+        assert_eq!(composite.inheritance_chain.len(), 3);
+    }
+
+    #[test]
+    fn test_resolve_path_precedence_root_and_modifications() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file(".synapse/outer.md", "---\nmcp: synapse\n---\nFORBIDDEN: `unsafe` - Outside the project root\n").unwrap();
+        project.add_rule_file("project/.synapse/base.md", "---\nmcp: synapse\nroot: true\n---\nFORBIDDEN: `TODO` - No TODOs\nREQUIRED: `SPDX` - License header\n").unwrap();
+        project.add_rule_file("project/src/.synapse/src.md",
+            "---\nmcp: synapse\noverrides:\n  - rule: required-1-SPDX\n    severity: standard\n    message: Header recommended\n---\nFORBIDDEN: `TODO` - Track work in issues\n").unwrap();
+        project.add_file("project/src/main.rs", "fn main() {}\n").unwrap();
+
+        let rule_system = RuleSystem::new();
+        let rule_sets = rule_system.load_rules(&root.to_path_buf()).unwrap();
+        let resolution = rule_system.resolve_path(&root.join("project/src/main.rs"), &rule_sets);
+
+        // The walk stops at the directory marked `root: true`
+        assert!(resolution.stopped_at_root.as_ref().unwrap().ends_with("project/.synapse/base.md"));
+        assert_eq!(resolution.sources.len(), 2);
+
+        // Nearest definition wins and comes first
+        let applied: Vec<_> = resolution.applied.iter().map(|a| (a.rule.rule_type.clone(), a.rule.message.clone())).collect();
+        assert_eq!(applied, vec![
+            (RuleType::Forbidden, "Track work in issues".to_string()),
+            (RuleType::Standard, "Header recommended".to_string()),
+        ]);
+        assert_eq!(resolution.shadowed.len(), 1);
+        assert!(resolution.shadowed[0].shadowed_by.ends_with("project/src/.synapse/src.md"));
+        assert!(resolution.applied[1].modified_by.as_ref().unwrap().ends_with("project/src/.synapse/src.md"));
+    }
+
+    #[test]
+    fn test_same_rule_with_different_owners_is_shadowed() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        for (relative, owners) in [(".synapse/base.md", "platform"), ("src/.synapse/src.md", "\"Payments, EU\"")] {
+            project.add_rule_file(relative, format!("---\nmcp: synapse\nowners: {}\n---\nFORBIDDEN: `TODO` - No TODOs\n", owners)).unwrap();
+        }
+
+        let rule_system = RuleSystem::new();
+        let rule_sets = rule_system.load_rules(&root.to_path_buf()).unwrap();
+        let resolution = rule_system.resolve_path(&root.join("src/main.rs"), &rule_sets);
+
+        assert_eq!(resolution.applied.len(), 1);
+        assert_eq!(resolution.applied[0].rule.owners(), vec!["Payments, EU"]);
+        assert_eq!(resolution.shadowed.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_path_includes_extended_packs() {
+        let project = TestProject::new().unwrap();
+        let root = project.root();
+        project.add_rule_file("shared-rules/security/security.md", "---\nmcp: synapse\nversion: 1\n---\nFORBIDDEN: `eval` - No eval\n").unwrap();
+        project.add_rule_file("services/api/.synapse/rules.md",
+            "---\nmcp: synapse\nextends: [\"builtin:rust-safety\", \"pack:../../../shared-rules/security@1\"]\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        project.add_file("services/api/main.rs", "fn main() {}\n").unwrap();
+
+        let rule_system = RuleSystem::new();
+        let rule_sets = rule_system.load_rules(&root.to_path_buf()).unwrap();
+        let resolution = rule_system.resolve_path(&root.join("services/api/main.rs"), &rule_sets);
+
+        let sources: Vec<_> = resolution.sources.iter().map(|s| s.path.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(sources, vec!["rules.md", "builtin:rust-safety", "security.md"]);
+        assert!(matches!(&resolution.sources[1].kind, RuleSourceKind::Extended { pack, .. } if pack == "builtin:rust-safety"));
+        assert!(resolution.applied.iter().any(|a| a.rule.pattern == "eval"));
+        assert_eq!(resolution.applied[0].rule.pattern, "dbg!");
+    }
+
+    #[test]
+    fn test_structural_rules_from_packs_are_counted_at_load() {
+        let project = TestProject::new().unwrap();
+        project.add_rule_file(".synapse/rules.md",
+            "---\nmcp: synapse\nextends: [\"builtin:rust-safety\"]\n---\nFORBIDDEN[ast]: `pub-fn-without-doc` - Document pub fns\n").unwrap();
+
+        let rule_sets = RuleSystem::new().load_rules(&project.root().to_path_buf()).unwrap();
+
+        // The file's own rule and the two `[ast]` rules of rust-safety
+        assert_eq!(warn_inactive_structural_rules(&rule_sets), 3);
+    }
+
+    #[test]
+    fn test_resolve_path_with_discovery_config() {
+        let project = TestProject::new().unwrap();
+        let root = project.path("project");
+        project.add_rule_file("org/policy.md", "---\nmcp: synapse\n---\nFORBIDDEN: `unsafe` - No unsafe\n").unwrap();
+        project.add_rule_file("project/.rules/base.md", "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - No TODOs\n").unwrap();
+        project.add_rule_file("project/src/.rules/src.md", "---\nmcp: synapse\ninherits: [\"../../lib/.rules\"]\n---\nFORBIDDEN: `dbg!` - No dbg!\n").unwrap();
+        project.add_rule_file("project/lib/.rules/lib.md", "---\nmcp: synapse\n---\nFORBIDDEN: `panic!` - No panics\n").unwrap();
+        project.add_file("project/config.toml", "[discovery]\nrule_dirs = [\".rules\"]\nexternal = [\"../org\"]\n").unwrap();
+
+        let rule_system = RuleSystem::for_project(&root);
+        let rule_sets = rule_system.load_rules(&root).unwrap();
+        let resolution = rule_system.resolve_path(&root.join("src/main.rs"), &rule_sets);
+
+        let patterns: Vec<_> = resolution.applied.iter().map(|a| a.rule.pattern.as_str()).collect();
+        assert_eq!(patterns, vec!["dbg!", "panic!", "TODO", "unsafe"]);
+        assert_eq!(rule_system.governed_dir(&rule_sets[0]), root.canonicalize().ok());
+    }
+}
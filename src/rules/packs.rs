@@ -0,0 +1,409 @@
+//! Shared rule packs (`extends:` frontmatter)
+//!
+//! A rule file can pull in the rules of a pack without copying them:
+//!
+//! ```markdown
+//! ---
+//! mcp: synapse
+//! extends: ["builtin:rust-safety", "pack:../../shared-rules/security@2"]
+//! ---
+//! ```
+//!
+//! `builtin:<name>` names one of the [`BUILTIN_PACKS`] shipped with the
+//! binary. `pack:<dir>` names a directory, relative to the rule file, whose
+//! `.md` rule files (or those in its rule directory, `.synapse/` unless
+//! `[discovery] rule_dirs` says otherwise) form the pack.
+//! An `@<version>` suffix requires the pack files to declare that `version:`
+//! in their frontmatter.
+//!
+//! Pack rules rank right after the extending file's own `inherits`, with the
+//! extending file's precedence; see [`super::resolution`].
+
+use super::discovery::RuleDiscovery;
+use super::{RuleParser, is_unmarked_file_error};
+use crate::models::RuleSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Guidance shared by the `rust-standards` pack and the `init` Rust template
+pub const RUST_STANDARDS_GUIDELINES: &str = r#"## Code Formatting
+- **Always** use `cargo fmt` before committing
+- Line length: 100 characters maximum
+- Use trailing commas in multi-line expressions
+
+## Naming Conventions
+- **Types**: `PascalCase` (structs, enums, traits)
+- **Functions/Variables**: `snake_case`
+- **Constants**: `SCREAMING_SNAKE_CASE`
+- **Modules**: `snake_case`
+
+## Error Handling
+- Use `Result<T, E>` for recoverable errors
+- Use `anyhow::Result<T>` for application errors
+- Use `thiserror` for library errors
+- Never `unwrap()` or `expect()` in production code except for:
+  - Static data that is guaranteed valid
+  - Test code
+  - Early development prototypes (mark with TODO)
+
+## Performance Guidelines
+- Use `&str` instead of `String` when possible
+- Prefer borrowing over cloning
+- Use `Vec::with_capacity()` when size is known
+- Profile before optimizing
+
+## Testing
+- Unit tests in same file using `#[cfg(test)]`
+- Integration tests in `tests/` directory
+- Use `#[test]` for simple tests
+- Use `#[tokio::test]` for async tests
+- Mock external dependencies
+
+## Documentation
+- All public items must have doc comments (`///`)
+- Include examples in doc comments for public APIs
+- Use `#[doc(hidden)]` for internal public items
+
+## Cargo Dependencies
+- Minimize dependencies
+- Prefer standard library when possible
+- Pin major versions in `Cargo.toml`
+- Regular dependency audit with `cargo audit`
+
+## Linting
+- All code must pass `cargo clippy -- -D warnings`
+- Use `#[allow(clippy::...)]` sparingly with comments
+- Run `cargo check` before committing
+"#;
+
+/// Guidance shared by the `rust-safety` pack and the `init` Rust template
+pub const RUST_SECURITY_GUIDELINES: &str = r#"## Input Validation
+- Validate all external input (API, file, database)
+- Use strong typing to prevent invalid states
+- Sanitize data before database queries
+- Use prepared statements for SQL queries
+
+## Memory Safety
+- Avoid `unsafe` blocks unless absolutely necessary
+- When using `unsafe`, document safety invariants
+- Use `cargo miri` for testing unsafe code
+- Prefer safe alternatives (e.g., `Vec` over raw pointers)
+
+## Authentication & Authorization
+- Use JWT tokens with reasonable expiration
+- Implement rate limiting on public endpoints
+- Log security events for auditing
+- Use HTTPS in production
+
+## Data Protection
+- Hash passwords with `argon2`
+- Encrypt sensitive data at rest
+- Use secure random number generation
+- Implement proper session management
+
+## Error Handling Security
+- Never leak sensitive information in error messages
+- Log security-relevant errors
+- Use generic error messages for public APIs
+- Implement proper error propagation
+
+## Dependencies
+- Regular security audits with `cargo audit`
+- Keep dependencies up to date
+- Review security advisories
+- Minimize dependency surface area
+
+## Logging
+- Never log sensitive data (passwords, tokens, PII)
+- Use structured logging for security events
+- Implement log rotation and retention
+- Monitor for suspicious patterns
+"#;
+
+/// A rule pack compiled into the binary
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinPack {
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Markdown guidance shown in generated context
+    pub guidelines: &'static str,
+    /// Rule lines enforcing the guidance
+    pub rules: &'static str,
+}
+
+impl BuiltinPack {
+    /// Path recorded for the pack's rules, e.g. `builtin:rust-safety`
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(format!("builtin:{}", self.name))
+    }
+
+    /// The pack as a rule file
+    pub fn content(&self) -> String {
+        format!(
+            "---\nmcp: synapse\ntitle: \"{}\"\n---\n\n# {}\n\n{}\n## Enforced Rules\n\n{}",
+            self.title, self.title, self.guidelines, self.rules
+        )
+    }
+}
+
+/// Rule packs available as `builtin:<name>`
+pub const BUILTIN_PACKS: &[BuiltinPack] = &[
+    BuiltinPack {
+        name: "rust-standards",
+        title: "Rust Coding Standards",
+        guidelines: RUST_STANDARDS_GUIDELINES,
+        rules: r#"MAX_LINE_LENGTH[files: *.rs]: 100 - Line length: 100 characters maximum
+FORBIDDEN[files: *.rs]: `literal:dbg!(` - Remove dbg! before committing
+FORBIDDEN[ast]: `pub-fn-without-doc` - All public functions must have doc comments (`///`)
+FORBIDDEN[ast]: `pub-type-without-doc` - All public types must have doc comments (`///`)
+"#,
+    },
+    BuiltinPack {
+        name: "rust-safety",
+        title: "Rust Security Guidelines",
+        guidelines: RUST_SECURITY_GUIDELINES,
+        rules: r#"FORBIDDEN[ast]: `unsafe-without-safety-comment` - When using `unsafe`, document safety invariants in a `// SAFETY:` comment
+FORBIDDEN[ast]: `unwrap-outside-tests` - Never `unwrap()` or `expect()` in production code; propagate the error
+FORBIDDEN[files: *.rs, ignore-case]: `regex:(password|secret|api_key|token)\s*[:=]\s*"[^"]+"` - Never hard-code credentials
+"#,
+    },
+    BuiltinPack {
+        name: "secrets",
+        title: "Secret Detection",
+        guidelines: r#"## Credentials
+- Never commit credentials; load them from the environment or a secret manager
+- Rotate any credential that was committed, even if the commit was reverted
+- Keep private keys out of the repository
+
+## Detection
+- Known formats (AWS, GitHub and Slack keys, JWTs, private keys) are matched by pattern
+- Values assigned to `password`, `secret`, `token` or `api_key` names are reported
+  when their entropy shows they are random rather than placeholders
+"#,
+        rules: r#"FORBIDDEN[secret]: `aws-access-key` - Never commit AWS access keys; load them from the environment
+FORBIDDEN[secret]: `aws-secret-key` - Never commit AWS secret access keys; load them from the environment
+FORBIDDEN[secret]: `github-token` - Never commit GitHub tokens; revoke this one
+FORBIDDEN[secret]: `slack-token` - Never commit Slack tokens; revoke this one
+FORBIDDEN[secret]: `private-key` - Never commit private keys
+FORBIDDEN[secret]: `jwt` - Never commit JSON Web Tokens
+FORBIDDEN[secret]: `assigned-credential` - Never hard-code credentials; load them from the environment or a secret manager
+"#,
+    },
+    BuiltinPack {
+        name: "python",
+        title: "Python Standards",
+        guidelines: r#"## Error Handling
+- Catch specific exceptions, never use a bare `except:`
+- Never evaluate untrusted input with `eval()` or `exec()`
+
+## Logging
+- Use the `logging` module instead of `print()`
+"#,
+        rules: r#"FORBIDDEN[files: *.py]: `regex:except\s*:` - Catch specific exceptions, never use a bare `except:`
+FORBIDDEN[files: *.py]: `regex:\b(eval|exec)\(` - Never evaluate code with eval() or exec()
+FORBIDDEN[files: *.py]: `regex:^\s*print\(` - Use the logging module instead of print()
+"#,
+    },
+    BuiltinPack {
+        name: "typescript",
+        title: "TypeScript Standards",
+        guidelines: r#"## Type Safety
+- Avoid `any`; use `unknown` and narrow it
+- Fix type errors instead of silencing them with `@ts-ignore`
+
+## Logging
+- Use the project logger instead of `console.log`
+"#,
+        rules: r#"FORBIDDEN[files: *.{ts,tsx}]: `regex::\s*any\b` - Avoid `any`; use `unknown` and narrow it
+FORBIDDEN[files: *.{ts,tsx}]: `literal:@ts-ignore` - Fix the type error instead of silencing it
+FORBIDDEN[files: *.{ts,tsx,js,jsx}]: `literal:console.log(` - Use the project logger instead of console.log
+"#,
+    },
+];
+
+/// Look up a built-in pack by name
+pub fn builtin_pack(name: &str) -> Option<&'static BuiltinPack> {
+    BUILTIN_PACKS.iter().find(|pack| pack.name == name)
+}
+
+/// A parsed `extends:` entry
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackRef {
+    /// `builtin:<name>`
+    Builtin(String),
+    /// `pack:<dir>[@<version>]`, relative to the extending rule file
+    Local { path: PathBuf, version: Option<String> },
+}
+
+impl PackRef {
+    pub fn parse(entry: &str) -> crate::Result<Self> {
+        if let Some(name) = entry.strip_prefix("builtin:") {
+            if builtin_pack(name).is_none() {
+                let known: Vec<&str> = BUILTIN_PACKS.iter().map(|pack| pack.name).collect();
+                return Err(crate::SynapseError::Parse(format!(
+                    "Unknown builtin pack '{}' (available: {})", name, known.join(", ")
+                )));
+            }
+            return Ok(PackRef::Builtin(name.to_string()));
+        }
+        if let Some(spec) = entry.strip_prefix("pack:") {
+            let (path, version) = match spec.rsplit_once('@') {
+                Some((path, version)) => (path, Some(version.to_string())),
+                None => (spec, None),
+            };
+            return Ok(PackRef::Local { path: PathBuf::from(path), version });
+        }
+        Err(crate::SynapseError::Parse(format!(
+            "Invalid extends entry '{}' (expected builtin:<name> or pack:<dir>)", entry
+        )))
+    }
+
+    /// Key matching the rule sets of this pack, see [`pack_key_of`]
+    pub fn key(&self, discovery: &RuleDiscovery, rule_file: &Path) -> Option<PathBuf> {
+        match self {
+            PackRef::Builtin(name) => builtin_pack(name).map(BuiltinPack::path),
+            PackRef::Local { path, .. } => local_pack_dir(discovery, rule_file, path),
+        }
+    }
+}
+
+/// Directory holding the rule files of a local pack: its first configured
+/// rule directory that exists, or the pack directory itself
+fn local_pack_dir(discovery: &RuleDiscovery, rule_file: &Path, pack_path: &Path) -> Option<PathBuf> {
+    let base_dir = rule_file.parent().unwrap_or_else(|| Path::new("."));
+    let dir = base_dir.join(pack_path).canonicalize().ok()?;
+    let rule_dir = discovery.config().rule_dirs.iter()
+        .map(|name| dir.join(name))
+        .find(|rule_dir| rule_dir.is_dir());
+    Some(rule_dir.unwrap_or(dir))
+}
+
+/// Key under which a loaded rule set can be extended
+///
+/// Built-in packs are keyed by their `builtin:` path, files of local packs
+/// by their canonical directory.
+pub fn pack_key_of(rule_set: &RuleSet) -> Option<PathBuf> {
+    if rule_set.path.to_string_lossy().starts_with("builtin:") {
+        return Some(rule_set.path.clone());
+    }
+    rule_set.path.parent()?.canonicalize().ok()
+}
+
+/// Load the rule sets of the pack an `extends:` entry names
+pub fn load_pack(parser: &RuleParser, discovery: &RuleDiscovery, rule_file: &Path, pack: &PackRef) -> crate::Result<Vec<RuleSet>> {
+    match pack {
+        PackRef::Builtin(name) => {
+            let builtin = builtin_pack(name).ok_or_else(|| crate::SynapseError::Parse(format!(
+                "Unknown builtin pack '{}'", name
+            )))?;
+            Ok(vec![parser.parse_content(&builtin.content(), builtin.path())?])
+        }
+        PackRef::Local { path, version } => {
+            let dir = local_pack_dir(discovery, rule_file, path).ok_or_else(|| crate::SynapseError::Parse(format!(
+                "Pack directory '{}' does not exist", path.display()
+            )))?;
+
+            let mut files: Vec<PathBuf> = fs::read_dir(&dir)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|file| file.extension().is_some_and(|ext| ext == "md"))
+                .collect();
+            files.sort();
+
+            let mut rule_sets = Vec::new();
+            for file in files {
+                match parser.parse_rule_file(&file) {
+                    Ok(rule_set) => rule_sets.push(rule_set),
+                    Err(e) if is_unmarked_file_error(&e) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            if rule_sets.is_empty() {
+                return Err(crate::SynapseError::Parse(format!(
+                    "Pack directory '{}' contains no synapse rule files", path.display()
+                )));
+            }
+
+            if let Some(version) = version
+                && let Some(mismatch) = rule_sets.iter().find(|rule_set| rule_set.metadata.get("version") != Some(version))
+            {
+                return Err(crate::SynapseError::Parse(format!(
+                    "Pack '{}' requires version {}, but {} declares {}",
+                    path.display(),
+                    version,
+                    mismatch.path.display(),
+                    mismatch.metadata.get("version").map_or("no version", String::as_str)
+                )));
+            }
+
+            Ok(rule_sets)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DiscoveryConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_builtin_packs_parse() {
+        let parser = RuleParser::new();
+        for pack in BUILTIN_PACKS {
+            let rule_set = parser.parse_content(&pack.content(), pack.path())
+                .unwrap_or_else(|e| panic!("builtin:{} failed to parse: {}", pack.name, e));
+            assert!(!rule_set.rules.is_empty(), "builtin:{} has no rules", pack.name);
+        }
+    }
+
+    #[test]
+    fn test_parse_pack_refs() {
+        assert_eq!(PackRef::parse("builtin:rust-safety").unwrap(), PackRef::Builtin("rust-safety".to_string()));
+        assert_eq!(PackRef::parse("pack:../shared/security@2").unwrap(), PackRef::Local {
+            path: PathBuf::from("../shared/security"),
+            version: Some("2".to_string()),
+        });
+        assert!(PackRef::parse("builtin:no-such-pack").is_err());
+        assert!(PackRef::parse("../shared").is_err());
+    }
+
+    #[test]
+    fn test_load_local_pack_checks_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let pack_dir = temp_dir.path().join("shared/security");
+        fs::create_dir_all(&pack_dir).unwrap();
+        fs::write(pack_dir.join("security.md"), "---\nmcp: synapse\nversion: 2\n---\nFORBIDDEN: `eval` - No eval\n").unwrap();
+        fs::write(pack_dir.join("notes.md"), "# Not a rule file\n").unwrap();
+        let rule_file = temp_dir.path().join("service/.synapse/rules.md");
+        fs::create_dir_all(rule_file.parent().unwrap()).unwrap();
+
+        let parser = RuleParser::new();
+        let discovery = RuleDiscovery::new();
+        let pack = PackRef::parse("pack:../../shared/security@2").unwrap();
+        let rule_sets = load_pack(&parser, &discovery, &rule_file, &pack).unwrap();
+        assert_eq!(rule_sets.len(), 1);
+        assert_eq!(pack_key_of(&rule_sets[0]), pack.key(&discovery, &rule_file));
+
+        let wrong_version = PackRef::parse("pack:../../shared/security@3").unwrap();
+        assert!(load_pack(&parser, &discovery, &rule_file, &wrong_version).is_err());
+    }
+
+    #[test]
+    fn test_local_pack_uses_configured_rule_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let pack_dir = temp_dir.path().join("shared/security");
+        fs::create_dir_all(pack_dir.join(".rules")).unwrap();
+        fs::write(pack_dir.join("README.md"), "---\nmcp: synapse\n---\nFORBIDDEN: `TODO` - Not part of the pack\n").unwrap();
+        fs::write(pack_dir.join(".rules/security.md"), "---\nmcp: synapse\n---\nFORBIDDEN: `eval` - No eval\n").unwrap();
+        let rule_file = temp_dir.path().join("service/.rules/rules.md");
+        fs::create_dir_all(rule_file.parent().unwrap()).unwrap();
+
+        let parser = RuleParser::new();
+        let discovery = RuleDiscovery::with_config(DiscoveryConfig { rule_dirs: vec![".rules".to_string()], ..Default::default() });
+        let pack = PackRef::parse("pack:../../shared/security").unwrap();
+        let rule_sets = load_pack(&parser, &discovery, &rule_file, &pack).unwrap();
+
+        assert_eq!(rule_sets.iter().flat_map(|set| &set.rules).map(|rule| rule.pattern.as_str()).collect::<Vec<_>>(), vec!["eval"]);
+        assert_eq!(pack.key(&discovery, &rule_file), pack_dir.join(".rules").canonicalize().ok());
+    }
+}
@@ -1,4 +1,13 @@
-use crate::models::{RuleSet, Rule, RuleType, CompiledRule};
+use crate::models::{RuleSet, Rule, RuleType, CompiledRule, PatternKind, RuleModification};
+use crate::ast_analysis::StructuralCheck;
+use crate::enforcement::commit_msg::COMMIT_MESSAGE_TARGET;
+use crate::enforcement::dependencies;
+use crate::enforcement::header::HeaderTemplate;
+use crate::enforcement::lifecycle;
+use crate::enforcement::secrets::SecretDetector;
+use crate::enforcement::scope::RuleScope;
+use super::examples::parse_example_fence;
+use super::packs::PackRef;
 use regex::Regex;
 use serde_yaml;
 use std::collections::HashMap;
@@ -9,22 +18,68 @@ use tracing::debug;
 #[derive(serde::Deserialize, Debug)]
 struct RuleFrontmatter {
     inherits: Option<Vec<String>>,
-    overrides: Option<Vec<String>>,
+    extends: Option<Vec<String>>,
+    overrides: Option<Vec<OverrideSpec>>,
+    owners: Option<Owners>,
+    enforce_from: Option<String>,
+    expires: Option<String>,
+    mode: Option<String>,
+    target: Option<String>,
     project: Option<String>,
     module: Option<String>,
     #[serde(flatten)]
     metadata: HashMap<String, serde_yaml::Value>,
 }
 
+/// An `overrides:` entry: a bare rule ID removes the rule, a mapping modifies it
+#[derive(serde::Deserialize, Debug)]
+#[serde(untagged)]
+enum OverrideSpec {
+    Remove(String),
+    Modify {
+        rule: String,
+        severity: Option<String>,
+        message: Option<String>,
+    },
+}
+
+/// `owners:` is a single team or person, or a list of them
+#[derive(serde::Deserialize, Debug)]
+#[serde(untagged)]
+enum Owners {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Owners {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Owners::One(owner) => vec![owner],
+            Owners::Many(owners) => owners,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RuleParser {
     frontmatter_regex: Regex,
+    rule_regex: Regex,
+    count_regex: Regex,
+    threshold_regex: Regex,
 }
 
 impl RuleParser {
     pub fn new() -> Self {
         Self {
             frontmatter_regex: Regex::new(r"(?s)^---\s*\n(.*?)\n---\s*\n").unwrap(),
+            // A single, robust regex to capture any valid rule on a single line.
+            // It captures the keyword, optional bracketed attributes (e.g. `[ast]` or
+            // `[scope: function]`), the pattern (which can be quoted), and the message.
+            rule_regex: Regex::new(r"^(?i)(FORBIDDEN|REQUIRED|STANDARD|CONVENTION)(?:\[([^\]]+)\])?:\s*(?:`([^`]+)`|([^\s`]+))\s*-\s*(.+)$").unwrap(),
+            // Count rules tolerate (MAX n) or demand (MIN n) a number of occurrences.
+            count_regex: Regex::new(r"^(?i)(MAX|MIN)\s+(\d+)(?:\[([^\]]+)\])?:\s*(?:`([^`]+)`|([^\s`]+))\s*-\s*(.+)$").unwrap(),
+            // Threshold rules limit file size and line length without a pattern.
+            threshold_regex: Regex::new(r"^(?i)(MAX_LINES|MAX_LINE_LENGTH):\s*(\d+)(?:\s*-\s*(.+))?$").unwrap(),
         }
     }
 
@@ -71,6 +126,7 @@ impl RuleParser {
         }
         
         let mut rule_set = RuleSet::new(file_path);
+        let mut rule_defaults = Vec::new();
 
         // Parse frontmatter if present
         if let Some(frontmatter_yaml) = frontmatter_opt {
@@ -84,9 +140,53 @@ impl RuleParser {
                 rule_set = rule_set.with_inherits(inherit_paths);
             }
 
+            // Handle rule packs
+            if let Some(extends) = frontmatter.extends {
+                for entry in &extends {
+                    PackRef::parse(entry)?;
+                }
+                rule_set = rule_set.with_extends(extends);
+            }
+
             // Handle overrides
             if let Some(overrides) = frontmatter.overrides {
-                rule_set = rule_set.with_overrides(overrides);
+                let mut removed = Vec::new();
+                let mut modifications = Vec::new();
+                for spec in overrides {
+                    match spec {
+                        OverrideSpec::Remove(rule_id) => removed.push(rule_id),
+                        OverrideSpec::Modify { rule, severity, message } => {
+                            let rule_type = severity.map(|severity| parse_rule_type(&severity).ok_or_else(|| {
+                                crate::SynapseError::Parse(format!(
+                                    "Unknown severity '{}' for override of '{}' (expected forbidden, required, standard or convention)",
+                                    severity, rule
+                                ))
+                            })).transpose()?;
+                            modifications.push(RuleModification { rule_id: rule, rule_type, message });
+                        }
+                    }
+                }
+                rule_set = rule_set.with_overrides(removed).with_modifications(modifications);
+            }
+
+            if let Some(owners) = frontmatter.owners {
+                rule_set = rule_set.with_owners(owners.into_vec());
+            }
+
+            // Rollout settings and the target are defaults for every rule in the file
+            if let Some(date) = frontmatter.enforce_from {
+                lifecycle::parse_date(&date)?;
+                rule_defaults.push(("enforce_from", date.trim().to_string()));
+            }
+            if let Some(date) = frontmatter.expires {
+                lifecycle::parse_date(&date)?;
+                rule_defaults.push(("expires", date.trim().to_string()));
+            }
+            if let Some(mode) = frontmatter.mode {
+                rule_defaults.push(("mode", lifecycle::parse_mode(&mode)?));
+            }
+            if let Some(target) = frontmatter.target {
+                rule_defaults.push(("target", parse_target(&target)?));
             }
 
             // Convert metadata
@@ -118,7 +218,14 @@ impl RuleParser {
         // Parse markdown content for rules
         let compiled_rules = self.extract_compiled_rules(&markdown_content)?;
         for compiled_rule in compiled_rules {
-            rule_set = rule_set.add_rule((*compiled_rule.rule).clone());
+            let mut rule = (*compiled_rule.rule).clone();
+            // Rules carry their file's owners so violations can be routed to them
+            rule.owners = rule_set.owners.clone();
+            // Rule attributes take precedence over frontmatter
+            for (key, value) in &rule_defaults {
+                rule.metadata.entry(key.to_string()).or_insert_with(|| value.clone());
+            }
+            rule_set = rule_set.add_rule(rule);
         }
 
         rule_set.validate()?;
@@ -138,46 +245,350 @@ impl RuleParser {
 
     /// Extract compiled rules from markdown content using a unified line-by-line parser.
     fn extract_compiled_rules(&self, content: &str) -> crate::Result<Vec<CompiledRule>> {
-        let mut compiled_rules = Vec::new();
         debug!("Extracting rules from markdown content ({} chars)", content.len());
 
-        // A single, robust regex to capture any valid rule on a single line.
-        // It captures the keyword, the pattern (which can be quoted), and the message.
-        let rule_regex = Regex::new(r"^(?i)(FORBIDDEN|REQUIRED|STANDARD|CONVENTION):\s*(?:`([^`]+)`|([^\s`]+))\s*-\s*(.+)$").unwrap();
-
-        for line in content.lines() {
-            if let Some(captures) = rule_regex.captures(line.trim()) {
-                let keyword = captures.get(1).unwrap().as_str().to_uppercase();
-                // The pattern can be in capture group 2 (quoted) or 3 (unquoted).
-                let pattern = captures.get(2).or_else(|| captures.get(3)).unwrap().as_str().trim();
-                let message = captures.get(4).unwrap().as_str().trim();
-
-                let rule_type = match keyword.as_str() {
-                    "FORBIDDEN" => RuleType::Forbidden,
-                    "REQUIRED" => RuleType::Required,
-                    "STANDARD" => RuleType::Standard,
-                    "CONVENTION" => RuleType::Convention,
-                    _ => continue, // Should not happen due to regex
-                };
+        let compiled_rules = self.extract_rule_lines(content)
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        debug!("Total rules extracted: {}", compiled_rules.len());
+        Ok(compiled_rules)
+    }
+
+    /// Parse every rule line of a rule file, keeping failures alongside their line
+    ///
+    /// Line numbers are 1-based and count the frontmatter, so they point into
+    /// the file as written. Used by `synapse rules lint` to report every bad
+    /// line instead of stopping at the first one.
+    pub fn parse_rule_lines(&self, content: &str) -> Vec<(usize, crate::Result<CompiledRule>)> {
+        let (markdown, offset) = match self.frontmatter_regex.find(content) {
+            Some(frontmatter) => (&content[frontmatter.end()..], content[..frontmatter.end()].matches('\n').count()),
+            None => (content, 0),
+        };
+
+        self.extract_rule_lines(markdown)
+            .into_iter()
+            .map(|(line, result)| (line + offset, result))
+            .collect()
+    }
 
-                let rule_id = format!("{}-{}-{}", keyword.to_lowercase(), compiled_rules.len(), pattern);
+    fn extract_rule_lines(&self, content: &str) -> Vec<(usize, crate::Result<CompiledRule>)> {
+        let mut results: Vec<(usize, crate::Result<CompiledRule>)> = Vec::new();
+        let mut index = 0;
+        let mut in_example = false;
 
-                debug!("Found {} rule: pattern='{}', message='{}'", keyword, pattern, message);
+        for (line_index, line) in content.lines().enumerate() {
+            // Code inside `pass`/`fail` example blocks is never a rule
+            if in_example {
+                in_example = !line.trim_start().starts_with("```");
+                continue;
+            }
+            if parse_example_fence(line).is_some() {
+                in_example = true;
+                continue;
+            }
 
-                let rule = Rule::new(
-                    rule_id,
-                    rule_type,
-                    pattern.to_string(),
-                    message.to_string(),
-                );
-                compiled_rules.push(CompiledRule::from_rule(rule));
+            match self.parse_rule_line(line, index) {
+                Ok(Some(compiled_rule)) => {
+                    index += 1;
+                    results.push((line_index + 1, Ok(compiled_rule)));
+                }
+                Ok(None) => {}
+                Err(e) => results.push((line_index + 1, Err(e))),
             }
         }
 
-        debug!("Total rules extracted: {}", compiled_rules.len());
-        Ok(compiled_rules)
+        results
+    }
+
+    /// Parse a single line, returning `None` if it isn't a rule
+    ///
+    /// `index` is the number of rules before this one in the file and becomes
+    /// part of the generated rule ID.
+    fn parse_rule_line(&self, line: &str, index: usize) -> crate::Result<Option<CompiledRule>> {
+        if let Some(captures) = self.threshold_regex.captures(line.trim()) {
+            let keyword = captures.get(1).unwrap().as_str().to_lowercase();
+            let limit = captures.get(2).unwrap().as_str();
+            let message = match captures.get(3) {
+                Some(message) => message.as_str().trim().to_string(),
+                None if keyword == "max_lines" => format!("Files must not exceed {} lines", limit),
+                None => format!("Lines must not exceed {} characters", limit),
+            };
+
+            let rule_id = format!("{}-{}-{}", keyword, index, limit);
+            debug!("Found {} threshold rule: limit={}", keyword, limit);
+
+            let mut rule = Rule::new(rule_id, RuleType::Forbidden, limit.to_string(), message);
+            rule.metadata.insert("threshold".to_string(), keyword);
+            return Ok(Some(CompiledRule::from_rule(rule)));
+        }
+
+        if let Some(captures) = self.count_regex.captures(line.trim()) {
+            let keyword = captures.get(1).unwrap().as_str().to_lowercase();
+            let limit = captures.get(2).unwrap().as_str();
+            let attributes = captures.get(3).map(|m| m.as_str());
+            let (kind, pattern) = split_pattern_kind(captures.get(4).or_else(|| captures.get(5)).unwrap().as_str().trim());
+            let message = captures.get(6).unwrap().as_str().trim();
+
+            // MAX rules forbid occurrences beyond the limit, MIN rules require at least as many
+            let rule_type = if keyword == "max" { RuleType::Forbidden } else { RuleType::Required };
+            let rule_id = format!("{}-{}-{}", keyword, index, pattern);
+            debug!("Found {} {} rule: pattern='{}', message='{}'", keyword, limit, pattern, message);
+
+            let mut rule = Rule::new(rule_id, rule_type, pattern.to_string(), message.to_string());
+            rule.metadata.insert(keyword, limit.to_string());
+            if let Some(kind) = kind {
+                rule.metadata.insert("kind".to_string(), kind.name().to_string());
+            }
+            if let Some(attributes) = attributes {
+                self.apply_rule_attributes(&mut rule, attributes, line.trim())?;
+            }
+            return Ok(Some(CompiledRule::try_from_rule(rule)?));
+        }
+
+        if let Some(captures) = self.rule_regex.captures(line.trim()) {
+            let keyword = captures.get(1).unwrap().as_str().to_uppercase();
+            let attributes = captures.get(2).map(|m| m.as_str());
+            // The pattern can be in capture group 3 (quoted) or 4 (unquoted).
+            let pattern = captures.get(3).or_else(|| captures.get(4)).unwrap().as_str().trim();
+            let (kind, pattern) = split_pattern_kind(pattern);
+            let message = captures.get(5).unwrap().as_str().trim();
+
+            let Some(rule_type) = parse_rule_type(&keyword) else {
+                return Ok(None); // Should not happen due to regex
+            };
+
+            let rule_id = format!("{}-{}-{}", keyword.to_lowercase(), index, pattern);
+
+            debug!("Found {} rule: pattern='{}', message='{}'", keyword, pattern, message);
+
+            let mut rule = Rule::new(
+                rule_id,
+                rule_type,
+                pattern.to_string(),
+                message.to_string(),
+            );
+
+            if let Some(kind) = kind {
+                rule.metadata.insert("kind".to_string(), kind.name().to_string());
+            }
+            if let Some(attributes) = attributes {
+                self.apply_rule_attributes(&mut rule, attributes, line.trim())?;
+            }
+
+            return Ok(Some(CompiledRule::try_from_rule(rule)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Apply bracketed rule attributes such as `[ast]` or `[scope: function]`
+    /// 
+    /// Attributes are comma-separated; each is either a bare flag or a
+    /// `key: value` pair, and is recorded in the rule's metadata.
+    fn apply_rule_attributes(&self, rule: &mut Rule, attributes: &str, line: &str) -> crate::Result<()> {
+        for attribute in split_attributes(attributes) {
+            let (key, value) = match attribute.split_once(':') {
+                Some((key, value)) => (key.trim().to_lowercase(), Some(value.trim().to_string())),
+                None => (attribute.to_lowercase(), None),
+            };
+
+            match (key.as_str(), value) {
+                ("ast", None) => {
+                    if StructuralCheck::from_name(&rule.pattern).is_none() {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Unknown structural check '{}' (available: {})",
+                            rule.pattern,
+                            StructuralCheck::ALL.iter().map(|c| c.name()).collect::<Vec<_>>().join(", ")
+                        )));
+                    }
+                    // A structural check reports offending items; there is nothing to require
+                    if rule.rule_type != RuleType::Forbidden {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "ast is only supported on FORBIDDEN rules in line: {}", line
+                        )));
+                    }
+                    rule.metadata.insert("engine".to_string(), "ast".to_string());
+                }
+                ("secret", None) => {
+                    if SecretDetector::from_name(&rule.pattern).is_none() {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Unknown secret detector '{}' (available: {})",
+                            rule.pattern,
+                            SecretDetector::ALL.iter().map(|d| d.name()).collect::<Vec<_>>().join(", ")
+                        )));
+                    }
+                    if rule.rule_type != RuleType::Forbidden {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "secret is only supported on FORBIDDEN rules in line: {}", line
+                        )));
+                    }
+                    rule.metadata.insert("target".to_string(), "secret".to_string());
+                }
+                ("header", None) => {
+                    if rule.rule_type != RuleType::Required {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "header is only supported on REQUIRED rules in line: {}", line
+                        )));
+                    }
+                    HeaderTemplate::parse(&rule.pattern)?;
+                    rule.metadata.insert("target".to_string(), "header".to_string());
+                }
+                ("lines", Some(lines)) => {
+                    if !lines.parse::<usize>().is_ok_and(|lines| lines > 0) {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Invalid lines '{}' (expected a positive number) in line: {}", lines, line
+                        )));
+                    }
+                    rule.metadata.insert("lines".to_string(), lines);
+                }
+                ("entropy", Some(bits)) => {
+                    if !bits.parse::<f64>().is_ok_and(|bits| bits > 0.0) {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Invalid entropy '{}' (expected bits per character, such as 3.5) in line: {}", bits, line
+                        )));
+                    }
+                    rule.metadata.insert("entropy".to_string(), bits);
+                }
+                ("scope", Some(scope)) => {
+                    let scope = scope.to_lowercase();
+                    if RuleScope::from_name(&scope).is_none() {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Unknown scope '{}' (expected file, item or function) in line: {}", scope, line
+                        )));
+                    }
+                    if rule.rule_type != RuleType::Required {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "scope is only supported on REQUIRED rules in line: {}", line
+                        )));
+                    }
+                    rule.metadata.insert("scope".to_string(), scope);
+                }
+                ("enforce_from" | "enforce-from", Some(date)) => {
+                    lifecycle::parse_date(&date)?;
+                    rule.metadata.insert("enforce_from".to_string(), date);
+                }
+                ("expires", Some(date)) => {
+                    lifecycle::parse_date(&date)?;
+                    rule.metadata.insert("expires".to_string(), date);
+                }
+                ("mode", Some(mode)) => {
+                    rule.metadata.insert("mode".to_string(), lifecycle::parse_mode(&mode)?);
+                }
+                ("shadow", None) => {
+                    rule.metadata.insert("mode".to_string(), "shadow".to_string());
+                }
+                ("ignore-case", None) => {
+                    rule.metadata.insert("ignore_case".to_string(), "true".to_string());
+                }
+                ("path", None) => {
+                    rule.metadata.insert("target".to_string(), "path".to_string());
+                }
+                ("import", None) => {
+                    rule.metadata.insert("target".to_string(), "import".to_string());
+                }
+                ("dependency", None) => {
+                    rule.metadata.insert("target".to_string(), "dependency".to_string());
+                }
+                ("version", Some(constraint)) => {
+                    dependencies::parse_version_req(&constraint)?;
+                    rule.metadata.insert("version".to_string(), constraint);
+                }
+                ("target", Some(target)) => {
+                    rule.metadata.insert("target".to_string(), parse_target(&target)?);
+                }
+                ("except", Some(glob)) => {
+                    if let Err(e) = globset::Glob::new(&glob) {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Invalid except glob '{}' in line: {} ({})", glob, line, e
+                        )));
+                    }
+                    rule.metadata.insert("except".to_string(), glob);
+                }
+                ("files", Some(glob)) => {
+                    if let Err(e) = globset::Glob::new(&glob) {
+                        return Err(crate::SynapseError::Parse(format!(
+                            "Invalid files glob '{}' in line: {} ({})", glob, line, e
+                        )));
+                    }
+                    rule.metadata.insert("files".to_string(), glob);
+                }
+                _ => {
+                    return Err(crate::SynapseError::Parse(format!(
+                        "Unknown rule attribute '{}' in line: {}", attribute, line
+                    )));
+                }
+            }
+        }
+        if rule.metadata.contains_key("version") && rule.metadata.get("target").map(String::as_str) != Some("dependency") {
+            return Err(crate::SynapseError::Parse(format!(
+                "version is only supported on dependency rules in line: {}", line
+            )));
+        }
+        if rule.metadata.contains_key("lines") && !rule.is_header() {
+            return Err(crate::SynapseError::Parse(format!(
+                "lines is only supported on header rules in line: {}", line
+            )));
+        }
+        if rule.metadata.contains_key("entropy") && !rule.is_secret() {
+            return Err(crate::SynapseError::Parse(format!(
+                "entropy is only supported on secret rules in line: {}", line
+            )));
+        }
+        Ok(())
+    }
+
+}
+
+/// Validate a `target` value: what a rule checks instead of file content
+fn parse_target(value: &str) -> crate::Result<String> {
+    let target = value.trim().to_lowercase();
+    match target.as_str() {
+        "path" | "import" | "dependency" | COMMIT_MESSAGE_TARGET => Ok(target),
+        _ => Err(crate::SynapseError::Parse(format!(
+            "Unknown target '{}' (expected path, import, dependency or {})", value, COMMIT_MESSAGE_TARGET
+        ))),
     }
+}
 
+/// Parse a rule keyword such as `forbidden` (case-insensitive)
+fn parse_rule_type(keyword: &str) -> Option<RuleType> {
+    match keyword.to_uppercase().as_str() {
+        "FORBIDDEN" => Some(RuleType::Forbidden),
+        "REQUIRED" => Some(RuleType::Required),
+        "STANDARD" => Some(RuleType::Standard),
+        "CONVENTION" => Some(RuleType::Convention),
+        _ => None,
+    }
+}
+
+/// Split an explicit `regex:`, `literal:` or `word:` prefix off a rule pattern
+fn split_pattern_kind(pattern: &str) -> (Option<PatternKind>, &str) {
+    pattern.split_once(':')
+        .and_then(|(prefix, rest)| PatternKind::from_name(prefix).map(|kind| (Some(kind), rest)))
+        .unwrap_or((None, pattern))
+}
+
+/// Split a bracketed attribute list on commas, keeping `{a,b}` glob alternatives intact
+fn split_attributes(attributes: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in attributes.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(attributes[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(attributes[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
 }
 
 impl Default for RuleParser {
@@ -189,6 +600,7 @@ impl Default for RuleParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
     use tempfile::TempDir;
 
     fn create_test_file(dir: &Path, filename: &str, content: &str) -> PathBuf {
@@ -385,6 +797,323 @@ invalid: [unclosed array
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_structural_rules() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN[ast]: `unsafe-without-safety-comment` - Document unsafe blocks with // SAFETY:
+FORBIDDEN: `println!` - Use logging instead
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/rules.md")).unwrap();
+
+        assert_eq!(result.rules.len(), 2);
+        assert!(result.rules[0].is_structural());
+        assert_eq!(result.rules[0].pattern, "unsafe-without-safety-comment");
+        assert!(!result.rules[1].is_structural());
+    }
+
+    #[test]
+    fn test_parse_unknown_structural_check() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN[ast]: `no-such-check` - Should fail
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/rules.md"));
+        assert!(result.is_err());
+
+        // Structural checks report offending items, so only FORBIDDEN makes sense
+        let content = "---\nmcp: synapse\n---\nREQUIRED[ast]: `pub-fn-without-doc` - Should fail\n";
+        let err = parser.parse_content(content, PathBuf::from(".synapse/rules.md")).unwrap_err().to_string();
+        assert!(err.contains("only supported on FORBIDDEN"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_parse_scoped_required_rule() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+REQUIRED[scope: function]: `///` - Every function needs a doc comment
+REQUIRED[scope: galaxy]: `///` - Bad scope
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/rules.md"));
+        assert!(result.is_err());
+
+        let content = r#"---
+mcp: synapse
+---
+REQUIRED[scope: function]: `///` - Every function needs a doc comment
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/rules.md")).unwrap();
+        assert_eq!(result.rules[0].metadata.get("scope").unwrap(), "function");
+    }
+
+    #[test]
+    fn test_parse_count_and_threshold_rules() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+MAX 3: `literal:unwrap()` - Keep unwrap() calls to a minimum
+MIN 1: `#[test]` - Every file needs a test
+MAX_LINES: 500
+MAX_LINE_LENGTH: 120 - Wrap long lines
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/rules.md")).unwrap();
+
+        assert_eq!(result.rules.len(), 4);
+        assert_eq!(result.rules[0].rule_type, RuleType::Forbidden);
+        assert_eq!(result.rules[0].metadata.get("max").unwrap(), "3");
+        assert_eq!(result.rules[0].pattern_kind(), PatternKind::Literal);
+        assert_eq!(result.rules[1].rule_type, RuleType::Required);
+        assert_eq!(result.rules[1].metadata.get("min").unwrap(), "1");
+        assert_eq!(result.rules[2].metadata.get("threshold").unwrap(), "max_lines");
+        assert_eq!(result.rules[2].pattern, "500");
+        assert_eq!(result.rules[3].message, "Wrap long lines");
+
+        let compiled = CompiledRule::from_rule(result.rules[3].clone());
+        assert!(matches!(compiled.matcher, crate::PatternMatcher::Threshold(crate::Threshold::MaxLineLength(120))));
+    }
+
+    #[test]
+    fn test_parse_path_rules() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+CONVENTION[path, files: *.{rs,toml}]: `snake_case` - Rust files must be snake_case
+REQUIRED[path, files: *.py]: `_test\.py$` - Test files must end in _test.py
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from("tests/.synapse/naming.md")).unwrap();
+
+        assert_eq!(result.rules.len(), 2);
+        assert_eq!(result.rules[0].rule_type, RuleType::Convention);
+        assert_eq!(result.rules[0].metadata.get("target").unwrap(), "path");
+        assert_eq!(result.rules[0].metadata.get("files").unwrap(), "*.{rs,toml}");
+        assert_eq!(result.rules[1].pattern, r"_test\.py$");
+    }
+
+    #[test]
+    fn test_parse_import_rules() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN[import]: `src/infra/**` - Domain must not depend on infrastructure
+FORBIDDEN[import, except: src/db/**]: `neo4rs` - Only the db layer may use neo4rs
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from("src/domain/.synapse/layers.md")).unwrap();
+
+        assert_eq!(result.rules.len(), 2);
+        assert_eq!(result.rules[0].metadata.get("target").unwrap(), "import");
+        assert_eq!(result.rules[0].pattern, "src/infra/**");
+        assert_eq!(result.rules[1].metadata.get("except").unwrap(), "src/db/**");
+    }
+
+    #[test]
+    fn test_parse_commit_message_target() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+target: commit-message
+---
+FORBIDDEN: `regex:^WIP` - No work in progress
+FORBIDDEN[target: path]: `regex:\.orig$` - No merge leftovers
+"#;
+        let result = parser.parse_content(content, PathBuf::from(".synapse/commits.md")).unwrap();
+        assert_eq!(result.rules[0].metadata.get("target").unwrap(), "commit-message");
+        assert_eq!(result.rules[1].metadata.get("target").unwrap(), "path");
+
+        let invalid = "---\nmcp: synapse\n---\nFORBIDDEN[target: branch]: `WIP` - No WIP\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+    }
+
+    #[test]
+    fn test_parse_dependency_rules() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN[dependency]: `openssl` - Use rustls
+FORBIDDEN[dependency, version: >=0.1 <0.12]: `reqwest` - Upgrade reqwest to 0.12
+"#;
+        let result = parser.parse_content(content, PathBuf::from(".synapse/deps.md")).unwrap();
+        assert_eq!(result.rules[0].metadata.get("target").unwrap(), "dependency");
+        assert_eq!(result.rules[1].metadata.get("version").unwrap(), ">=0.1 <0.12");
+
+        let invalid = "---\nmcp: synapse\n---\nFORBIDDEN[dependency, version: newest]: `serde` - Pin serde\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+        let invalid = "---\nmcp: synapse\n---\nFORBIDDEN[version: <1]: `serde` - Pin serde\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+    }
+
+    #[test]
+    fn test_parse_header_rules() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+REQUIRED[header, lines: 5]: `Copyright {year} {company=Acme, Inc.}\nSPDX-License-Identifier: {spdx}` - Add the license header
+"#;
+        let result = parser.parse_content(content, PathBuf::from(".synapse/license.md")).unwrap();
+        assert_eq!(result.rules[0].metadata.get("target").unwrap(), "header");
+        assert_eq!(result.rules[0].metadata.get("lines").unwrap(), "5");
+
+        let invalid = "---\nmcp: synapse\n---\nFORBIDDEN[header]: `Copyright {year}` - No headers\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+        let invalid = "---\nmcp: synapse\n---\nREQUIRED[header]: `Copyright {year` - Add the header\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+        let invalid = "---\nmcp: synapse\n---\nREQUIRED[lines: 3]: `Copyright` - Add the header\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+    }
+
+    #[test]
+    fn test_parse_modifying_overrides() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+root: true
+overrides:
+  - forbidden-0-TODO
+  - rule: required-1-SPDX
+    severity: standard
+    message: License header recommended
+---
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from("test.md")).unwrap();
+
+        assert!(result.is_root());
+        assert_eq!(result.overrides, vec!["forbidden-0-TODO".to_string()]);
+        assert_eq!(result.modifications.len(), 1);
+        assert_eq!(result.modifications[0].rule_id, "required-1-SPDX");
+        assert_eq!(result.modifications[0].rule_type, Some(RuleType::Standard));
+        assert_eq!(result.modifications[0].message.as_deref(), Some("License header recommended"));
+
+        let invalid = "---\nmcp: synapse\noverrides:\n  - rule: x\n    severity: fatal\n---\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+    }
+
+    #[test]
+    fn test_parse_owners() {
+        let parser = RuleParser::new();
+
+        let content = "---\nmcp: synapse\nowners: [platform-team, \"@alice\"]\n---\nFORBIDDEN: `TODO` - No TODOs\n";
+        let result = parser.parse_content(content, PathBuf::from("test.md")).unwrap();
+        assert_eq!(result.owners, vec!["platform-team".to_string(), "@alice".to_string()]);
+        assert_eq!(result.rules[0].owners(), vec!["platform-team", "@alice"]);
+
+        let content = "---\nmcp: synapse\nowners: security\n---\nFORBIDDEN: `TODO` - No TODOs\n";
+        let result = parser.parse_content(content, PathBuf::from("test.md")).unwrap();
+        assert_eq!(result.rules[0].owners(), vec!["security"]);
+        assert!(!result.rules[0].metadata.contains_key("owners"));
+
+        let content = "---\nmcp: synapse\nowners: [\"Payments, EU\", security]\n---\nFORBIDDEN: `TODO` - No TODOs\n";
+        let result = parser.parse_content(content, PathBuf::from("test.md")).unwrap();
+        assert_eq!(result.rules[0].owners(), vec!["Payments, EU", "security"]);
+    }
+
+    #[test]
+    fn test_parse_rollout_settings() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+enforce_from: 2026-12-01
+mode: shadow
+---
+FORBIDDEN: `TODO` - No TODOs
+FORBIDDEN[mode: enforce, expires: 2027-06-30]: `dbg!` - No dbg!
+"#;
+        let result = parser.parse_content(content, PathBuf::from("test.md")).unwrap();
+        assert!(result.rules[0].is_shadow());
+        assert_eq!(result.rules[0].enforce_from(), NaiveDate::from_ymd_opt(2026, 12, 1));
+        assert!(!result.rules[1].is_shadow());
+        assert_eq!(result.rules[1].expires(), NaiveDate::from_ymd_opt(2027, 6, 30));
+
+        let invalid = "---\nmcp: synapse\n---\nFORBIDDEN[expires: soon]: `TODO` - No TODOs\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+        let invalid = "---\nmcp: synapse\nmode: loud\n---\n";
+        assert!(parser.parse_content(invalid, PathBuf::from("test.md")).is_err());
+    }
+
+    #[test]
+    fn test_parse_explicit_pattern_kinds() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN: `literal:println!(` - Use the logger
+FORBIDDEN[ignore-case]: `word:todo` - Track work in issues
+MAX 2: `regex:\.unwrap\(\)` - Keep unwraps rare
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/kinds.md")).unwrap();
+
+        assert_eq!(result.rules.len(), 3);
+        assert_eq!(result.rules[0].pattern, "println!(");
+        assert_eq!(result.rules[0].pattern_kind(), PatternKind::Literal);
+        assert_eq!(result.rules[1].pattern_kind(), PatternKind::Word);
+        assert!(result.rules[1].ignores_case());
+        assert_eq!(result.rules[2].pattern, r"\.unwrap\(\)");
+    }
+
+    #[test]
+    fn test_invalid_regex_reported_at_load() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN: `regex:println!(` - Use the logger
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/bad.md"));
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("literal:"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_unprefixed_invalid_regex_is_literal() {
+        let parser = RuleParser::new();
+
+        let content = r#"---
+mcp: synapse
+---
+FORBIDDEN: `println!(` - Use the logger
+"#;
+
+        let result = parser.parse_content(content, PathBuf::from(".synapse/plain.md")).unwrap();
+
+        assert_eq!(result.rules[0].pattern_kind(), PatternKind::Literal);
+        assert!(result.rules[0].is_literal_fallback());
+        let compiled = CompiledRule::try_from_rule(result.rules[0].clone()).unwrap();
+        assert!(matches!(compiled.matcher, crate::models::PatternMatcher::Literal(ref p) if p == "println!("));
+    }
+
     #[test]
     fn test_case_insensitive_rule_parsing() {
         let temp_dir = TempDir::new().unwrap();  
@@ -0,0 +1,121 @@
+//! Resolution trace for the rules that apply to a path (`synapse explain`)
+//!
+//! [`RuleSystem::resolve_path`](super::RuleSystem::resolve_path) records every
+//! step of rule resolution; `rules_for_path` flattens the trace into
+//! [`CompositeRules`].
+//!
+//! Precedence is defined as follows. Directories are walked from the target
+//! upwards, stopping after a directory whose rule files set `root: true`.
+//! Rule files governing nearer directories come first; each file is followed
+//! by the files it `inherits`, in declaration order, and then by the rule
+//! packs it `extends`. Rules keep that order, a duplicate of an earlier rule
+//! is shadowed, and `overrides:` entries only remove or modify rules from
+//! their own file or files of lower precedence.
+
+use crate::models::{CompositeRules, Rule};
+use std::path::PathBuf;
+
+/// A directory visited while walking up from the target path
+#[derive(Debug, Clone)]
+pub struct DirectoryStep {
+    pub dir: PathBuf,
+    /// Rule files governing this directory, in path order
+    pub rule_files: Vec<PathBuf>,
+}
+
+/// Why a rule file takes part in the resolution
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleSourceKind {
+    /// It governs a directory on the walk
+    Directory,
+    /// It was pulled in by the `inherits:` entry of another rule file
+    Inherited { from: PathBuf },
+    /// It belongs to a rule pack named in the `extends:` entry of another rule file
+    Extended { from: PathBuf, pack: String },
+}
+
+/// A rule file contributing to the resolution
+#[derive(Debug, Clone)]
+pub struct RuleSource {
+    pub path: PathBuf,
+    pub kind: RuleSourceKind,
+}
+
+/// An `overrides:` entry and the rule file declaring it
+#[derive(Debug, Clone)]
+pub struct OverrideEntry {
+    pub rule_id: String,
+    pub declared_in: PathBuf,
+    /// Index of the declaring file in the resolution order
+    pub position: usize,
+}
+
+/// A rule dropped because an `overrides:` entry names it
+#[derive(Debug, Clone)]
+pub struct OverriddenRule {
+    pub rule: Rule,
+    pub source: PathBuf,
+    pub overridden_by: PathBuf,
+}
+
+/// A rule dropped because a file with higher precedence defines the same rule
+#[derive(Debug, Clone)]
+pub struct ShadowedRule {
+    pub rule: Rule,
+    pub source: PathBuf,
+    pub shadowed_by: PathBuf,
+}
+
+/// A rule in the final result and the file it came from
+#[derive(Debug, Clone)]
+pub struct AppliedRule {
+    /// The rule with any modifying override applied
+    pub rule: Rule,
+    pub source: PathBuf,
+    /// Rule file whose modifying override changed the rule
+    pub modified_by: Option<PathBuf>,
+}
+
+/// Full trace of how the rules for a path were resolved
+#[derive(Debug, Clone)]
+pub struct RuleResolution {
+    pub target: PathBuf,
+    /// Directories walked, nearest first
+    pub directories: Vec<DirectoryStep>,
+    /// Contributing rule files in resolution order
+    pub sources: Vec<RuleSource>,
+    pub overrides: Vec<OverrideEntry>,
+    pub overridden: Vec<OverriddenRule>,
+    pub shadowed: Vec<ShadowedRule>,
+    /// Applicable rules in precedence order
+    pub applied: Vec<AppliedRule>,
+    /// Rule file with `root: true` that ended the walk up the tree
+    pub stopped_at_root: Option<PathBuf>,
+}
+
+impl RuleResolution {
+    /// Whether a rule matches an ID or generated name given on the command line
+    pub fn rule_matches(rule: &Rule, rule_id: &str) -> bool {
+        rule.id == rule_id || rule.name == rule_id
+    }
+
+    /// Whether two rules from different files define the same check
+    ///
+    /// Rules are the same when type, pattern and attributes match; the
+    /// message may differ, and the nearer file's message wins.
+    pub fn same_rule(a: &Rule, b: &Rule) -> bool {
+        a.rule_type == b.rule_type && a.pattern == b.pattern && a.metadata == b.metadata
+    }
+
+    /// Flatten the trace into the rules that apply
+    pub fn into_composite(self) -> CompositeRules {
+        let mut composite = CompositeRules::new();
+        for entry in self.overrides {
+            composite = composite.add_override(entry.rule_id);
+        }
+        for applied in self.applied {
+            composite = composite.add_rule(applied.rule);
+        }
+        composite.with_inheritance_chain(self.sources.into_iter().map(|source| source.path).collect())
+    }
+}
@@ -117,7 +117,7 @@ pub mod test_helpers {
         /// ]))?;
         /// # Ok::<(), synapse_mcp::SynapseError>(())
         /// ```
-        pub fn add_rule_file(&self, relative_path: &str, content: &str) -> Result<PathBuf> {
+        pub fn add_rule_file(&self, relative_path: &str, content: impl AsRef<[u8]>) -> Result<PathBuf> {
             let file_path = self.project_root.join(relative_path);
             
             // Create parent directories if they don't exist
@@ -165,8 +165,8 @@ pub mod test_helpers {
         /// # Arguments
         /// 
         /// * `relative_path` - Path relative to project root
-        /// * `content` - File content to write
-        pub fn add_file(&self, relative_path: &str, content: &str) -> Result<PathBuf> {
+        /// * `content` - File content to write, text or raw bytes
+        pub fn add_file(&self, relative_path: &str, content: impl AsRef<[u8]>) -> Result<PathBuf> {
             self.add_rule_file(relative_path, content) // Same implementation
         }
 
@@ -310,28 +310,28 @@ pub mod test_helpers {
         
         // Add global rules
         project.add_rule_file(".synapse/rules/global.md", 
-            &create_rule_content(&[
+            create_rule_content(&[
                 ("FORBIDDEN", "TODO"),
                 ("REQUIRED", "SPDX-License-Identifier"),
             ]))?;
         
         // Add source-specific rules
         project.add_rule_file("src/.synapse/coding.md",
-            &create_rule_content(&[
+            create_rule_content(&[
                 ("FORBIDDEN", "unwrap()"),
                 ("REQUIRED", "#[derive(Debug)]"),
             ]))?;
         
         // Add utility-specific rules
         project.add_rule_file("src/utils/.synapse/utility.md",
-            &create_rule_content(&[
+            create_rule_content(&[
                 ("STANDARD", "inline"),
                 ("CONVENTION", "short function names"),
             ]))?;
         
         // Add test-specific rules
         project.add_rule_file("tests/.synapse/testing.md",
-            &create_rule_content(&[
+            create_rule_content(&[
                 ("REQUIRED", "#[test]"),
                 ("CONVENTION", "descriptive test names"),
             ]))?;
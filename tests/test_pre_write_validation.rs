@@ -171,6 +171,43 @@ REQUIRED: `#[test]` - All functions need tests
     assert!(data.violations[0].rule_type == synapse_mcp::RuleType::Required);
 }
 
+/// Test that missing license headers come back fixed
+#[tokio::test]
+async fn test_pre_write_fixes_license_header() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_root = temp_dir.path();
+
+    let rule_content = r#"---
+mcp: synapse
+type: rule
+---
+
+REQUIRED[header]: `Copyright (c) {year} Acme\nSPDX-License-Identifier: {spdx=MIT}` - Add the license header
+"#;
+
+    let synapse_dir = project_root.join(".synapse");
+    fs::create_dir(&synapse_dir).expect("Failed to create .synapse dir");
+    fs::write(synapse_dir.join("license.md"), rule_content).expect("Failed to write rule file");
+
+    let rule_graph = RuleGraph::from_project(&PathBuf::from(project_root))
+        .expect("Failed to create rule graph");
+    let enforcer = PatternEnforcer::new(rule_graph);
+
+    let request = PreWriteRequest::new(PreWriteData {
+        file_path: project_root.join("scripts/deploy.py"),
+        content: "#!/usr/bin/env python3\nimport os\n".to_string(),
+    });
+
+    let response = enforcer.validate_pre_write(request)
+        .expect("Pre-write validation should not fail");
+
+    let data = response.data.expect("Response should have data");
+    assert!(!data.valid, "Should detect the missing header");
+    let fixed = data.fixed_content.expect("Header should be fixed");
+    assert!(fixed.starts_with("#!/usr/bin/env python3\n# Copyright (c) "));
+    assert!(fixed.contains(" Acme\n# SPDX-License-Identifier: MIT\n\nimport os\n"));
+}
+
 /// Test inheritance works in pre-write validation
 #[tokio::test]
 async fn test_pre_write_respects_rule_inheritance() {